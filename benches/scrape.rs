@@ -0,0 +1,103 @@
+//! Timings for scraping a large page, run with `cargo bench`
+//!
+//! This uses a plain timing loop rather than a benchmarking framework, so as not to add
+//! dependencies. Each benchmark reports the mean time per iteration over a fixed time budget
+
+use std::time::{Duration, Instant};
+
+use recipe_scraper::{SchemaOrgEntry, Scrape, Scraper};
+
+const BUDGET: Duration = Duration::from_secs(2);
+
+/// A recipe as its own `ld+json` block's body
+fn recipe_json() -> String {
+    let ingredients = (1..=20)
+        .map(|i| format!(r#""{} cups of ingredient number {}""#, i, i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let steps = (1..=15)
+        .map(|i| {
+            format!(
+                r#"{{"@type": "HowToStep", "text": "Do step {} carefully"}}"#,
+                i
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"{{"@context": "https://schema.org", "@type": "Recipe", "name": "Stew",
+            "description": "A hearty stew", "cookTime": "PT1H30M", "prepTime": "PT20M",
+            "recipeIngredient": [{}], "recipeInstructions": [{}]}}"#,
+        ingredients, steps
+    )
+}
+
+/// A page much like a large recipe blog's: a long article, breadcrumbs, several unrelated
+/// `ld+json` blocks and a single recipe
+fn large_page() -> String {
+    let breadcrumbs = (1..=10)
+        .map(|i| {
+            format!(
+                r#"{{"@type": "ListItem", "position": {}, "name": "Level {}"}}"#,
+                i, i
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let unrelated = [
+        format!(
+            r#"{{"@context": "https://schema.org", "@type": "BreadcrumbList", "itemListElement": [{}]}}"#,
+            breadcrumbs
+        ),
+        r#"{"@context": "https://schema.org", "@type": "WebSite", "name": "Example",
+            "potentialAction": {"@type": "SearchAction", "target": "https://example.com/?s={q}"}}"#
+            .to_string(),
+        r#"{"@context": "https://schema.org", "@type": "Organization", "name": "Example",
+            "logo": "https://example.com/logo.png", "sameAs": ["https://example.social/ex"]}"#
+            .to_string(),
+    ];
+    let scripts = unrelated
+        .iter()
+        .cycle()
+        .take(30)
+        .chain(std::iter::once(&recipe_json()))
+        .map(|body| format!(r#"<script type="application/ld+json">{}</script>"#, body))
+        .collect::<String>();
+    let article = "<p>Lorem ipsum dolor sit amet, consectetur adipiscing elit.</p>".repeat(2000);
+    format!(
+        "<html><head><title>Stew</title>{}</head><body>{}</body></html>",
+        scripts, article
+    )
+}
+
+/// Time `f`, which returns the number of recipes it found. Their total is printed alongside so
+/// that the work can't be optimized away
+fn bench(name: &str, mut f: impl FnMut() -> usize) {
+    let start = Instant::now();
+    let (mut iterations, mut found) = (0u32, 0);
+    while start.elapsed() < BUDGET {
+        found += f();
+        iterations += 1;
+    }
+    let per_iteration = start.elapsed() / iterations;
+    println!(
+        "{:<24} {:>12.1?}/iter ({} recipes)",
+        name, per_iteration, found
+    );
+}
+
+fn main() {
+    let page = large_page();
+    let json = recipe_json();
+    let scraper = Scraper::new();
+
+    bench("scrape_html", || {
+        let entries = SchemaOrgEntry::scrape_html(&page);
+        entries.iter().map(|e| e.recipes().len()).sum()
+    });
+    bench("Scraper::scrape", || scraper.scrape(&page).len());
+    bench("from_json_str", || {
+        let entry = SchemaOrgEntry::from_json_str(&json);
+        entry.map_or(0, |e| e.recipes().len())
+    });
+}
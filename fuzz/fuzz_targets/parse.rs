@@ -0,0 +1,20 @@
+//! Feed arbitrary bytes through JSON-LD parsing and HTML scraping, which should only ever return
+//! errors or empty results. Run with `cargo fuzz run parse`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use recipe_scraper::{Extract, SchemaOrgEntry, Scrape, Scraper};
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+
+    if let Ok(entry) = SchemaOrgEntry::from_json_bytes(data) {
+        for recipe in entry.extract_recipes() {
+            let _ = recipe.total_time().as_ref().map(|t| t.human_readable());
+            let _ = recipe.to_html();
+        }
+    }
+    let _ = SchemaOrgEntry::scrape_html(&text);
+    let _ = Scraper::new().heuristics(true).scrape(&text);
+});
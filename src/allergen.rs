@@ -0,0 +1,168 @@
+//! Spot common allergens among a recipe's ingredients by keyword
+
+use crate::ParsedIngredient;
+
+/// A common food allergen, as detected by
+/// [`Recipe::detected_allergens`](crate::SchemaOrgRecipe::detected_allergens)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Allergen {
+    Dairy,
+    Egg,
+    Fish,
+    Gluten,
+    Peanut,
+    Sesame,
+    Shellfish,
+    Soy,
+    TreeNut,
+}
+
+/// Each allergen, alongside the ingredient names which indicate it and those which would match
+/// one of them but don't (such as "coconut milk" for "milk")
+const KEYWORDS: &[(Allergen, &[&str], &[&str])] = &[
+    (
+        Allergen::Dairy,
+        &[
+            "milk",
+            "buttermilk",
+            "butter",
+            "cream",
+            "cheese",
+            "parmesan",
+            "mozzarella",
+            "cheddar",
+            "ricotta",
+            "feta",
+            "yogurt",
+            "yoghurt",
+            "ghee",
+            "whey",
+        ],
+        &[
+            "coconut milk",
+            "coconut cream",
+            "almond milk",
+            "oat milk",
+            "soy milk",
+            "rice milk",
+            "peanut butter",
+            "almond butter",
+            "cocoa butter",
+            "cream of tartar",
+            "dairy free",
+            "vegan",
+        ],
+    ),
+    (
+        Allergen::Egg,
+        &["egg", "mayonnaise", "meringue"],
+        &["vegan"],
+    ),
+    (
+        Allergen::Fish,
+        &[
+            "fish", "salmon", "tuna", "cod", "anchovy", "sardine", "trout", "halibut", "tilapia",
+            "mackerel",
+        ],
+        &[],
+    ),
+    (
+        Allergen::Gluten,
+        &[
+            "wheat",
+            "flour",
+            "bread",
+            "breadcrumb",
+            "panko",
+            "pasta",
+            "spaghetti",
+            "noodle",
+            "couscous",
+            "semolina",
+            "barley",
+            "rye",
+        ],
+        &[
+            "almond flour",
+            "coconut flour",
+            "rice flour",
+            "rice noodle",
+            "corn flour",
+            "gluten free",
+        ],
+    ),
+    (Allergen::Peanut, &["peanut"], &[]),
+    (Allergen::Sesame, &["sesame", "tahini"], &[]),
+    (
+        Allergen::Shellfish,
+        &[
+            "shrimp", "prawn", "crab", "lobster", "scallop", "mussel", "clam", "oyster",
+        ],
+        &["oyster mushroom"],
+    ),
+    (
+        Allergen::Soy,
+        &["soy", "soya", "tofu", "edamame", "tempeh", "miso", "tamari"],
+        &[],
+    ),
+    (
+        Allergen::TreeNut,
+        &[
+            "almond",
+            "walnut",
+            "pecan",
+            "cashew",
+            "pistachio",
+            "hazelnut",
+            "macadamia",
+            "pine nut",
+        ],
+        &[],
+    ),
+];
+
+/// The allergens indicated by any of `ingredients`' names, per [`ParsedIngredient::matches`],
+/// in the order they are declared by [`Allergen`]
+pub(crate) fn detect(ingredients: &[ParsedIngredient]) -> Vec<Allergen> {
+    KEYWORDS
+        .iter()
+        .filter(|(_, keywords, exceptions)| {
+            ingredients.iter().any(|i| {
+                keywords.iter().any(|k| i.matches(k)) && !exceptions.iter().any(|e| i.matches(e))
+            })
+        })
+        .map(|(allergen, _, _)| *allergen)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let detect = |lines: &[&str]| {
+            let ingredients = lines
+                .iter()
+                .map(ParsedIngredient::parse)
+                .collect::<Vec<_>>();
+            detect(&ingredients)
+        };
+
+        assert_eq!(
+            vec![Allergen::Dairy, Allergen::Egg, Allergen::Gluten],
+            detect(&["2 cups Flour", "3 eggs", "1 cup whole milk"])
+        );
+        assert_eq!(
+            vec![Allergen::Peanut, Allergen::Soy, Allergen::TreeNut],
+            detect(&[
+                "400ml coconut milk",
+                "2 tbsp peanut butter",
+                "1 tbsp soy sauce",
+                "1 eggplant",
+                "1/2 cup toasted almonds",
+            ])
+        );
+        assert!(detect(&["1 cup rice flour", "salt"]).is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+//! Decode raw HTML bytes into text, according to the character encoding declared by a
+//! byte-order mark, the `Content-Type` header or the document's own `<meta>` tags
+//!
+//! Only the encodings most commonly seen on recipe sites are supported: UTF-8, UTF-16 (when
+//! marked by a BOM) and windows-1252, which per the [WHATWG Encoding Standard] is also how
+//! `iso-8859-1`, `latin1` and `us-ascii` labels are to be decoded. Anything else is decoded as
+//! UTF-8, replacing invalid sequences
+//!
+//! [WHATWG Encoding Standard]: https://encoding.spec.whatwg.org/#names-and-labels
+
+use std::borrow::Cow;
+
+/// How far into a document to look for a `<meta>` charset declaration, as per the HTML
+/// standard's prescan
+const PRESCAN_BYTES: usize = 1024;
+
+/// The characters windows-1252 assigns to bytes 0x80 through 0x9F, which differ from latin1's
+/// C1 control characters. Unassigned bytes map to themselves
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+const WINDOWS_1252_LABELS: &[&str] = &[
+    "windows-1252",
+    "cp1252",
+    "x-cp1252",
+    "iso-8859-1",
+    "iso8859-1",
+    "iso_8859-1",
+    "latin1",
+    "l1",
+    "us-ascii",
+    "ascii",
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Windows1252,
+}
+
+/// The value of a `charset=` parameter within `s`, e.g. a `Content-Type` header value
+fn charset_param(s: &str) -> Option<&str> {
+    let lower = s.to_ascii_lowercase();
+    let idx = lower.find("charset=")? + "charset=".len();
+    let value = s[idx..].trim_start_matches(|c| c == '"' || c == '\'');
+    let end = value
+        .find(|c: char| c == '"' || c == '\'' || c == ';' || c.is_whitespace())
+        .unwrap_or(value.len());
+    Some(&value[..end]).filter(|v| !v.is_empty())
+}
+
+fn from_label(label: &str) -> Option<Encoding> {
+    let label = label.trim().to_ascii_lowercase();
+    match label.as_str() {
+        "utf-8" | "utf8" | "unicode-1-1-utf-8" => Some(Encoding::Utf8),
+        "utf-16le" | "utf-16" => Some(Encoding::Utf16Le),
+        "utf-16be" => Some(Encoding::Utf16Be),
+        l if WINDOWS_1252_LABELS.contains(&l) => Some(Encoding::Windows1252),
+        _ => None,
+    }
+}
+
+/// The charset declared by a `<meta charset>` or `<meta http-equiv="Content-Type">` tag near
+/// the start of the document
+fn meta_charset(bytes: &[u8]) -> Option<Encoding> {
+    let head = &bytes[..bytes.len().min(PRESCAN_BYTES)];
+    let head = String::from_utf8_lossy(head);
+    head.match_indices("<meta")
+        .chain(head.match_indices("<META"))
+        .filter_map(|(idx, _)| {
+            let tag = &head[idx..];
+            let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+            charset_param(tag).and_then(from_label)
+        })
+        .next()
+}
+
+fn detect(bytes: &[u8], content_type: Option<&str>) -> (Encoding, usize) {
+    match bytes {
+        [0xef, 0xbb, 0xbf, ..] => return (Encoding::Utf8, 3),
+        [0xff, 0xfe, ..] => return (Encoding::Utf16Le, 2),
+        [0xfe, 0xff, ..] => return (Encoding::Utf16Be, 2),
+        _ => {}
+    }
+
+    // A document can't sensibly declare itself as UTF-16 from within an ASCII-compatible
+    // prescan, so such declarations are treated as UTF-8, as browsers do
+    let meta = || {
+        meta_charset(bytes).map(|e| match e {
+            Encoding::Utf16Le | Encoding::Utf16Be => Encoding::Utf8,
+            e => e,
+        })
+    };
+    let declared = content_type
+        .and_then(charset_param)
+        .and_then(from_label)
+        .or_else(meta);
+    (declared.unwrap_or(Encoding::Utf8), 0)
+}
+
+/// Decode `bytes` into text using the encoding given by a byte-order mark, else by the charset
+/// of `content_type` (a `Content-Type` header value), else by the document's `<meta>` tags.
+/// Documents declaring nothing are decoded as UTF-8
+pub(crate) fn decode<'a>(bytes: &'a [u8], content_type: Option<&str>) -> Cow<'a, str> {
+    let (encoding, bom) = detect(bytes, content_type);
+    let bytes = &bytes[bom..];
+
+    let utf16 = |to_unit: fn([u8; 2]) -> u16| {
+        let units = bytes
+            .chunks(2)
+            .map(|c| to_unit([c[0], *c.get(1).unwrap_or(&0)]));
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect::<String>()
+    };
+
+    match encoding {
+        Encoding::Utf8 => String::from_utf8_lossy(bytes),
+        Encoding::Utf16Le => Cow::Owned(utf16(u16::from_le_bytes)),
+        Encoding::Utf16Be => Cow::Owned(utf16(u16::from_be_bytes)),
+        Encoding::Windows1252 => Cow::Owned(
+            bytes
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9f => WINDOWS_1252_HIGH[usize::from(b - 0x80)],
+                    b => char::from(b),
+                })
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let latin1 = b"<meta charset=\"ISO-8859-1\"><p>Cr\xe8me br\xfbl\xe9e \x96 \x80</p>";
+        assert_eq!(
+            "<meta charset=\"ISO-8859-1\"><p>Crème brûlée – €</p>",
+            decode(latin1, None)
+        );
+
+        let header = b"<p>Jalape\xf1o</p>";
+        assert_eq!(
+            "<p>Jalapeño</p>",
+            decode(header, Some("text/html; charset=windows-1252"))
+        );
+
+        let http_equiv =
+            b"<META http-equiv=\"Content-Type\" content=\"text/html; charset=latin1\">\xe9";
+        assert!(decode(http_equiv, None).ends_with('é'));
+
+        assert_eq!("Crème", decode("\u{feff}Crème".as_bytes(), None));
+        assert_eq!("Crème", decode("Crème".as_bytes(), Some("text/html")));
+        assert_eq!("hé", decode(b"\xff\xfeh\x00\xe9\x00", None));
+    }
+}
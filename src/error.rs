@@ -0,0 +1,74 @@
+use std::fmt::{self, Display};
+
+/// Errors which can occur while reading or parsing recipes
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ScrapeError {
+    /// The underlying reader failed
+    Io(std::io::Error),
+    /// The input was not valid JSON, or did not match any known recipe structure
+    Json(serde_json::Error),
+}
+
+impl Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read input: {}", e),
+            Self::Json(e) => write!(f, "failed to parse JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for ScrapeError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ScrapeError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Why a string could not be parsed as an ISO 8601 duration, as reported by
+/// [`MaybeDuration::try_parse`](crate::MaybeDuration::try_parse)
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DurationError {
+    /// The string was empty, or only whitespace
+    Empty,
+    /// The string did not start with the `P` designator, e.g. "30 minutes"
+    MissingDesignator { input: String },
+    /// The string was malformed from the given byte offset onwards, e.g. "PT30X"
+    Invalid { input: String, position: usize },
+}
+
+impl Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty duration"),
+            Self::MissingDesignator { input } => {
+                write!(f, "duration {:?} does not start with 'P'", input)
+            }
+            Self::Invalid { input, position } => write!(
+                f,
+                "invalid duration {:?} at position {} (near {:?})",
+                input,
+                position,
+                input.get(*position..).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
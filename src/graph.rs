@@ -0,0 +1,492 @@
+//! Resolve JSON-LD node references (`{"@id": "..."}`) against the nodes declared elsewhere in
+//! the same document, so that e.g. a recipe's `author` is populated from the `Person` node it
+//! points at
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Options applied while resolving `@id` references and otherwise filling in recipes from the
+/// rest of their document
+///
+/// The limits keep cyclic or maliciously deep documents from looping forever or expanding without
+/// bound. When a limit is hit, the offending reference is left unresolved and the rest of the
+/// document is still returned
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ResolveOptions {
+    /// How deeply nested references may be followed
+    pub max_depth: usize,
+    /// How many references may be substituted in a single document
+    pub max_references: usize,
+    /// Whether recipes without an `author` are attributed to the document's publisher instead:
+    /// the recipe's own `publisher`, else that of another top-level node (such as its
+    /// `WebPage`), else the first top-level `Organization`. Off by default, as a publisher isn't
+    /// necessarily the recipe's author
+    pub publisher_as_author: bool,
+    /// Whether recipes without a `recipeCategory` take their categories from the document's
+    /// `BreadcrumbList` instead, such as "Desserts" and "Cakes" for a Home > Desserts > Cakes >
+    /// Chocolate Cake trail. Crumbs naming the home page or the recipe itself are left out. Off
+    /// by default, as not every site's breadcrumbs are categories
+    pub breadcrumbs_as_category: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_references: 1024,
+            publisher_as_author: false,
+            breadcrumbs_as_category: false,
+        }
+    }
+}
+
+/// The `@id` of an object which is only a reference to a node declared elsewhere
+fn reference_id(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(o) if o.len() == 1 => o.get("@id").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Collect every node declared at the top level of the document (including within `@graph`)
+/// which carries an `@id` alongside other properties
+fn index(doc: &Value) -> HashMap<String, Value> {
+    let nodes: Vec<&Value> = match doc {
+        Value::Array(v) => v.iter().collect(),
+        Value::Object(o) => match o.get("@graph") {
+            Some(Value::Array(graph)) => graph.iter().chain(Some(doc)).collect(),
+            _ => vec![doc],
+        },
+        _ => Vec::new(),
+    };
+
+    nodes
+        .into_iter()
+        .filter(|n| reference_id(n).is_none())
+        .filter_map(|n| Some((n.get("@id")?.as_str()?.to_string(), n.clone())))
+        .collect()
+}
+
+/// Whether a property value carries no information, so that any other value should win over it
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(v) => v.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// The nodes declared at the top level of a document which may be merged with nodes of other
+/// documents: the items of a top-level array or `@graph`, or the document itself
+fn top_level_nodes_mut(doc: &mut Value) -> Vec<&mut Value> {
+    if doc.get("@graph").map_or(false, Value::is_array) {
+        return match doc.get_mut("@graph") {
+            Some(Value::Array(graph)) => graph.iter_mut().collect(),
+            _ => Vec::new(),
+        };
+    }
+    match doc {
+        Value::Array(v) => v.iter_mut().collect(),
+        Value::Object(_) => vec![doc],
+        _ => Vec::new(),
+    }
+}
+
+/// Like [`top_level_nodes_mut`], but borrowing the document immutably
+fn top_level_nodes(doc: &Value) -> Vec<&Value> {
+    match (doc, doc.get("@graph")) {
+        (_, Some(Value::Array(graph))) => graph.iter().collect(),
+        (Value::Array(v), _) => v.iter().collect(),
+        (Value::Object(_), _) => vec![doc],
+        _ => Vec::new(),
+    }
+}
+
+/// The first node anywhere within `value` declaring the `@id` `id` (rather than only referring
+/// to it), in document order
+fn find_node<'a>(value: &'a Value, id: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(o) => {
+            let declares = o.get("@id").and_then(Value::as_str) == Some(id);
+            if declares && reference_id(value).is_none() {
+                return Some(value);
+            }
+            o.values().find_map(|v| find_node(v, id))
+        }
+        Value::Array(v) => v.iter().find_map(|v| find_node(v, id)),
+        _ => None,
+    }
+}
+
+/// Merge the top-level nodes of several documents (such as a page's separate `ld+json` blocks)
+/// which declare the same `@id`, so that a node split across documents is seen whole. The
+/// merged node takes the place of the first occurrence, with each property taken from the first
+/// occurrence for which it isn't empty. Later occurrences are replaced by references to it
+pub(crate) fn merge_duplicate_nodes(docs: &mut [Value]) {
+    // Most pages declare each `@id` once, so repeated ones are found before any node is cloned
+    let declared_id = |node: &Value| match node.get("@id").and_then(Value::as_str) {
+        Some(id) if reference_id(node).is_none() => Some(id.to_string()),
+        _ => None,
+    };
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for node in docs.iter().flat_map(top_level_nodes) {
+        if let Some(id) = declared_id(node) {
+            *counts.entry(id).or_default() += 1;
+        }
+    }
+    counts.retain(|_, count| *count > 1);
+    if counts.is_empty() {
+        return;
+    }
+
+    let mut merged: HashMap<String, Value> = HashMap::new();
+    for node in docs.iter().flat_map(top_level_nodes) {
+        let id = match declared_id(node) {
+            Some(id) if counts.contains_key(&id) => id,
+            _ => continue,
+        };
+        let (existing, node) = match (merged.get_mut(&id), node) {
+            (Some(Value::Object(existing)), Value::Object(node)) => (existing, node),
+            (None, node) => {
+                merged.insert(id, node.clone());
+                continue;
+            }
+            _ => continue,
+        };
+        for (key, value) in node.iter() {
+            let missing = existing.get(key.as_str()).map_or(true, is_empty);
+            if missing && !is_empty(value) {
+                existing.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    for node in docs.iter_mut().flat_map(top_level_nodes_mut) {
+        let id = match declared_id(node) {
+            Some(id) if counts.contains_key(&id) => id,
+            _ => continue,
+        };
+        *node = match merged.remove(&id) {
+            Some(merged) => merged,
+            None => serde_json::json!({ "@id": id }),
+        };
+    }
+}
+
+struct Resolver<'a> {
+    nodes: &'a HashMap<String, Value>,
+    options: ResolveOptions,
+    visiting: Vec<String>,
+    resolved: usize,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, value: &mut Value, depth: usize) {
+        if let Some(id) = reference_id(value) {
+            let cyclic = self.visiting.iter().any(|v| v == id);
+            let exhausted =
+                depth >= self.options.max_depth || self.resolved >= self.options.max_references;
+            let node = match self.nodes.get(id) {
+                Some(node) if !cyclic && !exhausted => node,
+                _ => return,
+            };
+
+            self.resolved += 1;
+            let mut node = node.clone();
+            self.visiting.push(id.to_string());
+            self.resolve_properties(&mut node, depth + 1);
+            self.visiting.pop();
+            *value = node;
+        } else {
+            self.resolve_properties(value, depth);
+        }
+    }
+
+    fn resolve_properties(&mut self, value: &mut Value, depth: usize) {
+        match value {
+            Value::Array(v) => v.iter_mut().for_each(|v| self.resolve(v, depth)),
+            Value::Object(o) => {
+                let id = o.get("@id").and_then(Value::as_str).map(str::to_string);
+                self.visiting.extend(id.clone());
+                o.iter_mut()
+                    .filter(|(k, _)| !k.starts_with('@') || *k == "@graph")
+                    .for_each(|(_, v)| self.resolve(v, depth));
+                if id.is_some() {
+                    self.visiting.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replace node references throughout `doc` with the nodes they point at, then fill in missing
+/// authors if [`publisher_as_author`](ResolveOptions::publisher_as_author) is set, and missing
+/// categories if [`breadcrumbs_as_category`](ResolveOptions::breadcrumbs_as_category) is
+pub(crate) fn resolve_references(doc: &mut Value, options: ResolveOptions) {
+    let nodes = index(doc);
+    if !nodes.is_empty() {
+        let mut resolver = Resolver {
+            nodes: &nodes,
+            options,
+            visiting: Vec::new(),
+            resolved: 0,
+        };
+        resolver.resolve_properties(doc, 0);
+    }
+
+    if options.publisher_as_author {
+        attribute_to_publisher(doc);
+    }
+    if options.breadcrumbs_as_category {
+        categorize_from_breadcrumbs(std::slice::from_mut(doc));
+    }
+}
+
+/// The names along a `BreadcrumbList`'s trail, ordered by their `position`, leaving out blank
+/// ones and the home page
+fn breadcrumb_trail(list: &Value) -> Vec<String> {
+    let items = list.get("itemListElement").and_then(Value::as_array);
+    let mut items = items
+        .map(|v| v.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    items.sort_by_key(|item| {
+        let position = item.get("position");
+        let position = position.and_then(|p| p.as_u64().or_else(|| p.as_str()?.parse().ok()));
+        position.unwrap_or(u64::MAX)
+    });
+
+    fn name(item: &Value) -> Option<&str> {
+        let name = item.get("name").or_else(|| item.get("item")?.get("name"));
+        name.and_then(Value::as_str).map(str::trim)
+    }
+    items
+        .into_iter()
+        .filter_map(name)
+        .filter(|name| !name.is_empty() && !name.eq_ignore_ascii_case("home"))
+        .map(String::from)
+        .collect()
+}
+
+/// Set the `recipeCategory` of every top-level recipe lacking one to the trail of the first
+/// `BreadcrumbList` among `docs` (declared at their top level or as a node's `breadcrumb`), per
+/// [`ResolveOptions::breadcrumbs_as_category`]. A `breadcrumb` given as an `@id` reference is
+/// looked up anywhere within `docs`, as it's often declared in another block than the node
+/// referring to it, where reference resolution (being per document) won't find it
+pub(crate) fn categorize_from_breadcrumbs(docs: &mut [Value]) {
+    use crate::schema_org::has_type;
+
+    let is_list = |node: &&Value| has_type(node, "BreadcrumbList");
+    let trail = {
+        let docs = &*docs;
+        let node_with_id = |id: &str| docs.iter().find_map(|doc| find_node(doc, id));
+        docs.iter().flat_map(top_level_nodes).find_map(|node| {
+            let crumb = node.get("breadcrumb");
+            let crumb = crumb.map(|c| reference_id(c).and_then(node_with_id).unwrap_or(c));
+            let list = Some(node).filter(is_list).or_else(|| crumb.filter(is_list));
+            list.map(breadcrumb_trail)
+        })
+    };
+    let trail = match trail {
+        Some(trail) if !trail.is_empty() => trail,
+        _ => return,
+    };
+
+    let nodes = docs.iter_mut().flat_map(top_level_nodes_mut);
+    for recipe in nodes.filter(|n| has_type(n, "Recipe")) {
+        if let Value::Object(recipe) = recipe {
+            if !recipe.get("recipeCategory").map_or(true, is_empty) {
+                continue;
+            }
+            let name = recipe
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let categories = trail
+                .iter()
+                .filter(|crumb| !crumb.eq_ignore_ascii_case(name.trim()))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !categories.is_empty() {
+                recipe.insert("recipeCategory".into(), Value::from(categories));
+            }
+        }
+    }
+}
+
+/// Set the `author` of every top-level recipe lacking one to its publisher, per
+/// [`ResolveOptions::publisher_as_author`]
+fn attribute_to_publisher(doc: &mut Value) {
+    use crate::schema_org::has_type;
+
+    let mut nodes = top_level_nodes_mut(doc);
+    let publisher = |node: &Value| node.get("publisher").filter(|p| !is_empty(p)).cloned();
+    let site_publisher = nodes
+        .iter()
+        .filter(|n| !has_type(n, "Recipe"))
+        .find_map(|n| publisher(n))
+        .or_else(|| {
+            let organization = nodes.iter().find(|n| has_type(n, "Organization"));
+            organization.map(|n| (**n).clone())
+        });
+
+    for recipe in nodes.iter_mut().filter(|n| has_type(n, "Recipe")) {
+        let publisher = publisher(recipe).or_else(|| site_publisher.clone());
+        if let (Value::Object(recipe), Some(publisher)) = (&mut **recipe, publisher) {
+            if recipe.get("author").map_or(true, is_empty) {
+                recipe.insert("author".into(), publisher);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_references() {
+        let mut doc = json!({"@graph": [
+            {"@id": "#recipe", "name": "A recipe", "author": {"@id": "#author"}},
+            {"@id": "#author", "name": "Jane", "image": {"@id": "#missing"}},
+        ]});
+        resolve_references(&mut doc, ResolveOptions::default());
+
+        assert_eq!(
+            json!({"@id": "#author", "name": "Jane", "image": {"@id": "#missing"}}),
+            doc["@graph"][0]["author"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_references_cycle() {
+        let mut doc = json!({"@graph": [
+            {"@id": "#recipe", "isPartOf": {"@id": "#recipe"}, "author": {"@id": "#a"}},
+            {"@id": "#a", "knows": {"@id": "#b"}},
+            {"@id": "#b", "knows": {"@id": "#a"}},
+        ]});
+        resolve_references(&mut doc, ResolveOptions::default());
+
+        let recipe = &doc["@graph"][0];
+        assert_eq!(json!({"@id": "#recipe"}), recipe["isPartOf"]);
+        assert_eq!(json!({"@id": "#a"}), recipe["author"]["knows"]["knows"]);
+    }
+
+    #[test]
+    fn test_merge_duplicate_nodes() {
+        let mut docs = [
+            json!({"@graph": [
+                {"@id": "#recipe", "@type": "Recipe", "name": "Cake", "description": ""},
+                {"@id": "#site", "name": "Site"},
+            ]}),
+            json!({"@graph": [{
+                "@id": "#recipe",
+                "name": "Not cake",
+                "description": "Fluffy",
+                "recipeIngredient": ["flour"],
+            }]}),
+        ];
+        merge_duplicate_nodes(&mut docs);
+
+        assert_eq!(
+            json!({
+                "@id": "#recipe",
+                "@type": "Recipe",
+                "name": "Cake",
+                "description": "Fluffy",
+                "recipeIngredient": ["flour"],
+            }),
+            docs[0]["@graph"][0]
+        );
+        assert_eq!(
+            json!({"@id": "#site", "name": "Site"}),
+            docs[0]["@graph"][1]
+        );
+        assert_eq!(json!([{"@id": "#recipe"}]), docs[1]["@graph"]);
+    }
+
+    #[test]
+    fn test_publisher_as_author() {
+        let doc = json!({"@graph": [
+            {"@type": "Recipe", "name": "Cake"},
+            {"@type": "Recipe", "name": "Pie", "author": "Jane"},
+            {"@type": "Recipe", "name": "Tart", "publisher": {"@id": "#other"}},
+            {"@type": "WebPage", "publisher": {"@id": "#site"}},
+            {"@id": "#site", "@type": "Organization", "name": "Bakes"},
+            {"@id": "#other", "@type": "Organization", "name": "Other Bakes"},
+        ]});
+        let options = ResolveOptions {
+            publisher_as_author: true,
+            ..Default::default()
+        };
+
+        let mut unchanged = doc.clone();
+        resolve_references(&mut unchanged, ResolveOptions::default());
+        assert_eq!(Value::Null, unchanged["@graph"][0]["author"]);
+
+        let mut attributed = doc;
+        resolve_references(&mut attributed, options);
+        let author = |idx: usize| attributed["@graph"][idx]["author"]["name"].clone();
+        assert_eq!(json!("Bakes"), author(0));
+        assert_eq!(json!("Jane"), attributed["@graph"][1]["author"]);
+        assert_eq!(json!("Other Bakes"), author(2));
+
+        let mut organization_only = json!([
+            {"@type": "Organization", "name": "Bakes"},
+            {"@type": "Recipe", "name": "Cake", "author": ""},
+        ]);
+        resolve_references(&mut organization_only, options);
+        assert_eq!(json!("Bakes"), organization_only[1]["author"]["name"]);
+    }
+
+    #[test]
+    fn test_breadcrumbs_as_category() {
+        let doc = json!({"@graph": [
+            {"@type": "Recipe", "name": "Chocolate Cake"},
+            {"@type": "Recipe", "name": "Pie", "recipeCategory": "Pastry"},
+            {"@type": "WebPage", "breadcrumb": {"@id": "#crumbs"}},
+            {"@id": "#crumbs", "@type": "BreadcrumbList", "itemListElement": [
+                {"@type": "ListItem", "position": 3, "name": "Cakes"},
+                {"@type": "ListItem", "position": "1", "item": {"@id": "/", "name": "Home"}},
+                {"@type": "ListItem", "position": 4, "name": "Chocolate cake"},
+                {"@type": "ListItem", "position": 2, "item": {"@id": "/desserts", "name": "Desserts"}},
+            ]},
+        ]});
+        let options = ResolveOptions {
+            breadcrumbs_as_category: true,
+            ..Default::default()
+        };
+
+        let mut unchanged = doc.clone();
+        resolve_references(&mut unchanged, ResolveOptions::default());
+        assert_eq!(Value::Null, unchanged["@graph"][0]["recipeCategory"]);
+
+        let mut categorized = doc;
+        resolve_references(&mut categorized, options);
+        assert_eq!(
+            json!(["Desserts", "Cakes"]),
+            categorized["@graph"][0]["recipeCategory"]
+        );
+        assert_eq!(json!("Pastry"), categorized["@graph"][1]["recipeCategory"]);
+    }
+
+    #[test]
+    fn test_resolve_references_max_depth() {
+        let mut doc = json!({"@graph": [
+            {"@id": "#recipe", "author": {"@id": "#a"}},
+            {"@id": "#a", "knows": {"@id": "#b"}},
+            {"@id": "#b", "name": "B"},
+        ]});
+        let options = ResolveOptions {
+            max_depth: 1,
+            ..Default::default()
+        };
+        resolve_references(&mut doc, options);
+
+        assert_eq!(json!({"@id": "#b"}), doc["@graph"][0]["author"]["knows"]);
+    }
+}
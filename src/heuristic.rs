@@ -0,0 +1,188 @@
+//! A best-effort fallback for pages without any structured recipe data, which pieces a recipe
+//! together from Open Graph metadata and the lists following "Ingredients" and "Instructions"
+//! headings
+//!
+//! This is explicitly heuristic: recipes found this way are typically sparse, and may contain
+//! text which isn't part of the recipe at all
+
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{json, Value};
+
+use crate::{FieldSource, SchemaOrgRecipe as Recipe};
+
+/// Lowercase heading text fragments which introduce an ingredient list
+const INGREDIENT_HEADINGS: &[&str] = &["ingredient"];
+
+/// Lowercase heading text fragments which introduce a list of steps
+const INSTRUCTION_HEADINGS: &[&str] =
+    &["instruction", "direction", "method", "step", "preparation"];
+
+pub(crate) fn selector(selector: &str) -> Selector {
+    // Unwrap is appropriate here as this is only used with static selectors
+    Selector::parse(selector).unwrap()
+}
+
+/// The trimmed, whitespace-collapsed text of an element
+pub(crate) fn text(el: ElementRef<'_>) -> String {
+    el.text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The `content` of the first `<meta>` tag with the given `property` (or `name`)
+fn meta(html: &Html, property: &str) -> Option<String> {
+    let selector = selector(&format!(
+        r#"meta[property="{0}"], meta[name="{0}"]"#,
+        property
+    ));
+    html.select(&selector)
+        .find_map(|el| el.value().attr("content"))
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+}
+
+/// The items of the first list following a heading containing one of `headings`, before any
+/// other heading
+fn list_after_heading(html: &Html, headings: &[&str]) -> Vec<String> {
+    let mut in_section = false;
+    for el in html.select(&selector("h1, h2, h3, h4, h5, h6, ul, ol")) {
+        match el.value().name() {
+            "ul" | "ol" if in_section => {
+                let items = el
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|li| li.value().name() == "li")
+                    .map(text)
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>();
+                if !items.is_empty() {
+                    return items;
+                }
+            }
+            "ul" | "ol" => {}
+            _ => {
+                let heading = text(el).to_lowercase();
+                in_section = headings.iter().any(|h| heading.contains(h));
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Piece together a recipe from a document's metadata and markup, alongside where each populated
+/// property (by its schema.org name) was found. A title and at least one ingredient or step are
+/// required, so that arbitrary pages don't yield recipes
+pub(crate) fn scrape_with_sources(
+    html: &Html,
+) -> Option<(Recipe, Vec<(&'static str, FieldSource)>)> {
+    let og_title = meta(html, "og:title");
+    let name_source = match og_title {
+        Some(_) => FieldSource::Metadata,
+        None => FieldSource::DomHeuristic,
+    };
+    let name = og_title
+        .or_else(|| html.select(&selector("h1")).next().map(text))
+        .filter(|n| !n.is_empty())?;
+    let ingredients = list_after_heading(html, INGREDIENT_HEADINGS);
+    let steps = list_after_heading(html, INSTRUCTION_HEADINGS);
+    if ingredients.is_empty() && steps.is_empty() {
+        return None;
+    }
+
+    let description = meta(html, "og:description").or_else(|| meta(html, "description"));
+    let mut sources = vec![("name", name_source)];
+    let mut recipe = json!({
+        "name": name,
+        "description": description.clone().unwrap_or_default(),
+        "recipeIngredient": ingredients,
+    });
+    let optional = [
+        (
+            "description",
+            description.map(Value::from),
+            FieldSource::Metadata,
+        ),
+        (
+            "recipeIngredient",
+            Some(json!(ingredients)).filter(|_| !ingredients.is_empty()),
+            FieldSource::DomHeuristic,
+        ),
+        (
+            "recipeInstructions",
+            Some(json!(steps)).filter(|_| !steps.is_empty()),
+            FieldSource::DomHeuristic,
+        ),
+        (
+            "image",
+            meta(html, "og:image").map(Value::from),
+            FieldSource::Metadata,
+        ),
+        (
+            "url",
+            meta(html, "og:url").map(Value::from),
+            FieldSource::Metadata,
+        ),
+    ];
+    for (key, value, source) in optional {
+        if let Some(value) = value {
+            recipe[key] = value;
+            sources.push((key, source));
+        }
+    }
+
+    let recipe = serde_json::from_value(recipe).ok()?;
+    Some((recipe, sources))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_scrape() {
+        let html = Html::parse_document(
+            r#"<html><head>
+                <meta property="og:title" content="Grandma's Pancakes">
+                <meta property="og:description" content="Fluffy">
+                <meta property="og:image" content="https://example.com/p.jpg">
+            </head><body>
+                <ul class="nav"><li>Home</li></ul>
+                <h2>Ingredients</h2>
+                <div><ul><li>1 cup  flour</li><li>2 eggs</li></ul></div>
+                <h2>Instructions</h2>
+                <ol><li>Mix</li><li>Fry</li></ol>
+                <h2>Comments</h2>
+                <ul><li>Great!</li></ul>
+            </body></html>"#,
+        );
+        let (recipe, sources) = scrape_with_sources(&html).unwrap();
+
+        assert_eq!("Grandma's Pancakes", recipe.name());
+        assert_eq!("Fluffy", recipe.description());
+        assert_eq!(
+            vec!["1 cup flour", "2 eggs"],
+            recipe.ingredients().entries().collect::<Vec<_>>()
+        );
+        let steps = recipe.directions().as_ref().unwrap().steps();
+        assert_eq!(
+            vec!["Mix", "Fry"],
+            steps.iter().map(|s| s.text()).collect::<Vec<_>>()
+        );
+        assert!(recipe.completeness() < 0.7);
+
+        assert_eq!(
+            vec![
+                ("name", FieldSource::Metadata),
+                ("description", FieldSource::Metadata),
+                ("recipeIngredient", FieldSource::DomHeuristic),
+                ("recipeInstructions", FieldSource::DomHeuristic),
+                ("image", FieldSource::Metadata),
+            ],
+            sources
+        );
+
+        let html = Html::parse_document("<h1>About us</h1><ul><li>Team</li></ul>");
+        assert_eq!(None, scrape_with_sources(&html));
+    }
+}
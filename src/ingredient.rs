@@ -0,0 +1,666 @@
+//! Break free-form ingredient lines ("1 1/2 cups flour") into a quantity, unit and name
+
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+
+use accessory::Accessors;
+
+use crate::SchemaOrgRecipe as Recipe;
+
+/// Canonical unit names, alongside the (lowercase) spellings which map onto them
+pub(crate) const UNITS: &[(&str, &[&str])] = &[
+    ("tsp", &["tsp", "tsps", "teaspoon", "teaspoons"]),
+    (
+        "tbsp",
+        &["tbsp", "tbsps", "tbs", "tbl", "tablespoon", "tablespoons"],
+    ),
+    ("cup", &["cup", "cups"]),
+    (
+        "ml",
+        &[
+            "ml",
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+        ],
+    ),
+    ("l", &["l", "liter", "liters", "litre", "litres"]),
+    ("g", &["g", "gram", "grams"]),
+    ("kg", &["kg", "kilogram", "kilograms"]),
+    ("oz", &["oz", "ounce", "ounces"]),
+    ("lb", &["lb", "lbs", "pound", "pounds"]),
+    ("pinch", &["pinch", "pinches"]),
+    ("dash", &["dash", "dashes"]),
+    ("clove", &["clove", "cloves"]),
+    ("can", &["can", "cans"]),
+    ("slice", &["slice", "slices"]),
+];
+
+/// The kind of measurement an ingredient's unit makes, e.g. for grouping a shopping list
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnitType {
+    /// Measured by volume, such as cups, tablespoons or millilitres
+    Volume,
+    /// Measured by weight, such as grams or pounds
+    Weight,
+    /// Counted, either by a unit like cloves or cans, or by a bare quantity ("3 eggs")
+    Count,
+    /// Without a quantity or a built-in unit, such as "salt to taste"
+    Unknown,
+}
+
+/// The built-in canonical units of each [`UnitType`], except counted ones
+const UNIT_TYPES: &[(UnitType, &[&str])] = &[
+    (
+        UnitType::Volume,
+        &["tsp", "tbsp", "cup", "ml", "l", "pinch", "dash"],
+    ),
+    (UnitType::Weight, &["g", "kg", "oz", "lb"]),
+    (UnitType::Count, &["clove", "can", "slice"]),
+];
+
+const VULGAR_FRACTIONS: &[(char, f64)] = &[
+    ('½', 1. / 2.),
+    ('⅓', 1. / 3.),
+    ('⅔', 2. / 3.),
+    ('¼', 1. / 4.),
+    ('¾', 3. / 4.),
+    ('⅕', 1. / 5.),
+    ('⅖', 2. / 5.),
+    ('⅗', 3. / 5.),
+    ('⅘', 4. / 5.),
+    ('⅙', 1. / 6.),
+    ('⅚', 5. / 6.),
+    ('⅛', 1. / 8.),
+    ('⅜', 3. / 8.),
+    ('⅝', 5. / 8.),
+    ('⅞', 7. / 8.),
+];
+
+fn vulgar_fraction(c: char) -> Option<f64> {
+    VULGAR_FRACTIONS
+        .iter()
+        .find_map(|&(f, v)| (f == c).then(|| v))
+}
+
+/// Parse a single number token: an integer, a decimal, a fraction (`1/2`), a vulgar fraction
+/// (`½`) or an integer immediately followed by a vulgar fraction (`1½`)
+fn parse_number(token: &str) -> Option<f64> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if vulgar_fraction(c).is_some() => return vulgar_fraction(c),
+        _ => {}
+    }
+
+    match token.char_indices().last() {
+        Some((idx, c)) if idx > 0 && vulgar_fraction(c).is_some() => {
+            return Some(token[..idx].parse::<f64>().ok()? + vulgar_fraction(c)?)
+        }
+        _ => {}
+    }
+
+    match token.split_once('/') {
+        Some((n, d)) => {
+            let (n, d) = (n.parse::<f64>().ok()?, d.parse::<f64>().ok()?);
+            (d != 0.).then(|| n / d)
+        }
+        None => token.parse().ok().filter(|n: &f64| n.is_finite()),
+    }
+}
+
+/// Split a leading quantity (including mixed numbers like `1 1/2` and ranges like `2-3`, which
+/// yield their lower bound) off of `text`, returning it alongside the remaining text
+pub(crate) fn split_quantity(text: &str) -> Option<(f64, &str)> {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| {
+            !(c.is_ascii_digit() || matches!(c, '.' | '/') || vulgar_fraction(c).is_some())
+        })
+        .unwrap_or(text.len());
+    let mut quantity = parse_number(&text[..end])?;
+    let mut rest = &text[end..];
+
+    // Mixed numbers, e.g. "1 1/2" or "1 ½"
+    if quantity.fract() == 0. {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let token = &trimmed[..end];
+        if token.contains('/') || token.chars().all(|c| vulgar_fraction(c).is_some()) {
+            if let Some(fraction) = parse_number(token).filter(|f| *f < 1.) {
+                quantity += fraction;
+                rest = &trimmed[end..];
+            }
+        }
+    }
+
+    // Ranges, e.g. "2-3" or "2 – 3"
+    if let Some(range) = rest.trim_start().strip_prefix(['-', '–']) {
+        if let Some((_, after)) = split_quantity(range) {
+            rest = after;
+        }
+    }
+
+    Some((quantity, rest))
+}
+
+/// Words commonly introducing the name of a group of ingredients, as in "For the dough:"
+const GROUP_PREFIXES: &[&str] = &["for the ", "for "];
+
+/// The name of the group introduced by `line` when it is a heading within an ingredient list
+/// rather than an ingredient, such as "dough" for "For the dough:". Headings end with a colon,
+/// and don't start with a quantity
+pub(crate) fn group_heading(line: &str) -> Option<&str> {
+    let heading = line.trim().strip_suffix(':')?.trim_end();
+    if heading.is_empty() || split_quantity(heading).is_some() {
+        return None;
+    }
+    let prefix = GROUP_PREFIXES.iter().find(|p| {
+        heading
+            .get(..p.len())
+            .map_or(false, |h| h.eq_ignore_ascii_case(p))
+    });
+    Some(prefix.map_or(heading, |p| heading[p.len()..].trim_start()))
+}
+
+/// Like [`split_quantity`], but for the first quantity anywhere in `text` rather than only a
+/// leading one, so that "Für 4 Personen" yields 4 and "Personen". Words before the quantity are
+/// dropped, whatever their language
+pub(crate) fn find_quantity(text: &str) -> Option<(f64, &str)> {
+    let start = quantity_start(text)?;
+    split_quantity(&text[start..])
+}
+
+/// The byte offset at which [`find_quantity`]'s quantity starts
+fn quantity_start(text: &str) -> Option<usize> {
+    text.char_indices()
+        .filter(|&(_, c)| c.is_ascii_digit() || vulgar_fraction(c).is_some())
+        .map(|(idx, _)| idx)
+        .find(|&idx| split_quantity(&text[idx..]).is_some())
+}
+
+/// Like [`scale_line`], but for the first quantity anywhere in `text`, as found by
+/// [`find_quantity`], so that "Serves 4" scaled by 2 becomes "Serves 8". The text around the
+/// quantity is kept as-is
+pub(crate) fn scale_first(text: &str, factor: f64) -> String {
+    match quantity_start(text) {
+        Some(start) => format!("{}{}", &text[..start], scale_line(&text[start..], factor)),
+        None => text.to_string(),
+    }
+}
+
+/// Format a quantity for display in an ingredient line, as a whole number or mixed fraction
+/// ("1 1/2") when it is close to one with a denominator of 2, 3, 4 or 8, else as a decimal
+pub(crate) fn format_quantity(quantity: f64) -> String {
+    const TOLERANCE: f64 = 0.01;
+
+    let whole = quantity.floor();
+    let fraction = quantity - whole;
+    if fraction < TOLERANCE || 1. - fraction < TOLERANCE {
+        return quantity.round().to_string();
+    }
+
+    let fraction = [2., 3., 4., 8.].iter().find_map(|&d| {
+        let n = (fraction * d).round();
+        ((fraction - n / d).abs() < TOLERANCE).then(|| format!("{}/{}", n, d))
+    });
+    match fraction {
+        Some(f) if whole == 0. => f,
+        Some(f) => format!("{} {}", whole, f),
+        None => {
+            let decimal = format!("{:.2}", quantity);
+            decimal
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        }
+    }
+}
+
+/// Multiply the leading quantity (or both bounds of a leading range) of `text` by `factor`,
+/// keeping the rest of the text as-is. Text without a leading quantity is returned unchanged
+pub(crate) fn scale_line(text: &str, factor: f64) -> String {
+    let (quantity, rest) = match split_quantity(text) {
+        Some(split) => split,
+        None => return text.to_string(),
+    };
+
+    let head = text.trim_start();
+    let head = &head[..head.len() - rest.len()];
+    let upper = head
+        .split_once(['-', '–'])
+        .and_then(|(_, upper)| split_quantity(upper));
+    let scaled = match upper {
+        Some((upper, _)) => format!(
+            "{}-{}",
+            format_quantity(quantity * factor),
+            format_quantity(upper * factor)
+        ),
+        None => format_quantity(quantity * factor),
+    };
+    format!("{}{}", scaled, rest)
+}
+
+/// The units recognized when parsing ingredients, mapping each of their spellings onto a
+/// canonical name. The default dictionary holds common English cooking units (tsp, tbsp, cup, ml,
+/// g, oz, lb, pinch, clove, ...), and can be extended with custom ones:
+///
+/// ```
+/// use recipe_scraper::{ParsedIngredient, UnitDictionary};
+///
+/// let units = UnitDictionary::default().with_unit("knob", ["knobs"]);
+/// let butter = ParsedIngredient::parse_with("2 knobs of butter", &units);
+/// assert_eq!(Some("knob"), butter.unit());
+/// assert_eq!("butter", butter.name());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnitDictionary {
+    builtin: bool,
+    custom: Vec<(String, Vec<String>)>,
+}
+
+impl Default for UnitDictionary {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl UnitDictionary {
+    /// A dictionary without any of the built-in units
+    pub fn empty() -> Self {
+        Self {
+            builtin: false,
+            custom: Vec::new(),
+        }
+    }
+
+    /// Recognize `canonical` and each of its `spellings` (matched case-insensitively, ignoring
+    /// a trailing period) as a unit. Custom units take precedence over built-in ones
+    pub fn with_unit<S: Into<String>>(
+        mut self,
+        canonical: impl Into<String>,
+        spellings: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let canonical = canonical.into();
+        let spellings = std::iter::once(canonical.clone())
+            .chain(spellings.into_iter().map(Into::into))
+            .map(|s| s.to_lowercase());
+        match self.custom.iter_mut().find(|(c, _)| *c == canonical) {
+            Some((_, existing)) => existing.extend(spellings),
+            None => self.custom.push((canonical, spellings.collect())),
+        }
+        self
+    }
+
+    /// Look up the canonical form of a unit by any of its known spellings
+    pub fn canonical(&self, word: &str) -> Option<Cow<'static, str>> {
+        let word = word.trim_end_matches('.').to_lowercase();
+        let custom = self
+            .custom
+            .iter()
+            .find(|(_, spellings)| spellings.contains(&word))
+            .map(|(unit, _)| Cow::Owned(unit.clone()));
+        let builtin = || {
+            UNITS
+                .iter()
+                .filter(|_| self.builtin)
+                .find_map(|&(unit, aliases)| aliases.contains(&word.as_str()).then(|| unit))
+                .map(Cow::Borrowed)
+        };
+        custom.or_else(builtin)
+    }
+}
+
+/// The lowercased characters of `s`, with runs of whitespace collapsed into single spaces
+pub(crate) fn normalized_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.split_whitespace()
+        .flat_map(|w| w.chars().flat_map(char::to_lowercase).chain(Some(' ')))
+}
+
+/// Reduce a word to a crude singular stem ("tomatoes" and "tomato" both become "tomato"), which
+/// is plenty for matching ingredient names without pulling in a real stemmer
+fn stem(word: &str) -> String {
+    let word = word.to_lowercase();
+    if let Some(base) = word.strip_suffix("ies") {
+        return format!("{}y", base);
+    }
+    for suffix in ["oes", "ches", "shes", "xes", "sses"] {
+        if word.len() > suffix.len() + 1 && word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    match word.strip_suffix('s') {
+        Some(base) if base.len() > 2 && !base.ends_with(['s', 'u']) => base.to_string(),
+        _ => word,
+    }
+}
+
+/// The stems of every word in `text`
+fn stems(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(stem)
+        .collect()
+}
+
+/// A single ingredient line, broken into its quantity, unit and name
+#[derive(Clone, Debug, PartialEq, Accessors)]
+#[access(get)]
+pub struct ParsedIngredient {
+    #[access(get(cp))]
+    quantity: Option<f64>,
+    #[access(skip)]
+    unit: Option<Cow<'static, str>>,
+    name: String,
+}
+
+impl ParsedIngredient {
+    /// Parse an ingredient line, recognizing the default [`UnitDictionary`]. Parsing never fails:
+    /// text which doesn't start with a quantity or unit is kept in its entirety as the name
+    pub fn parse(text: impl AsRef<str>) -> Self {
+        Self::parse_with(text, &UnitDictionary::default())
+    }
+
+    /// Parse an ingredient line, recognizing the units in `units`
+    pub fn parse_with(text: impl AsRef<str>, units: &UnitDictionary) -> Self {
+        let text = text.as_ref().trim();
+        let (quantity, rest) = match split_quantity(text) {
+            Some((q, rest)) => (Some(q), rest.trim_start()),
+            None => (None, text),
+        };
+
+        let word_end = rest
+            .find(|c: char| c.is_whitespace() || c == ',')
+            .unwrap_or(rest.len());
+        let (unit, rest) = match units.canonical(&rest[..word_end]) {
+            Some(unit) => (Some(unit), &rest[word_end..]),
+            None => (None, rest),
+        };
+
+        let rest = rest.trim_start();
+        let name = rest.strip_prefix("of ").unwrap_or(rest).trim();
+        Self {
+            quantity,
+            unit,
+            name: name.to_string(),
+        }
+    }
+
+    /// Build an ingredient from an already-structured quantity, unit and name. Units found in
+    /// `units` are canonicalized, while others are kept verbatim
+    pub(crate) fn from_parts(
+        quantity: Option<f64>,
+        unit: Option<&str>,
+        name: &str,
+        units: &UnitDictionary,
+    ) -> Self {
+        let unit = unit.map(str::trim).filter(|u| !u.is_empty()).map(|u| {
+            units
+                .canonical(u)
+                .unwrap_or_else(|| Cow::Owned(u.to_string()))
+        });
+        Self {
+            quantity,
+            unit,
+            name: name.trim().to_string(),
+        }
+    }
+
+    /// The canonical unit, e.g. `"tbsp"` for "2 tablespoons"
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// The kind of measurement the ingredient's unit makes. Units outside the built-in
+    /// [`UnitDictionary`] are [`Unknown`](UnitType::Unknown)
+    pub fn unit_type(&self) -> UnitType {
+        match (self.unit(), self.quantity) {
+            (Some(unit), _) => UNIT_TYPES
+                .iter()
+                .find(|(_, units)| units.contains(&unit))
+                .map_or(UnitType::Unknown, |(unit_type, _)| *unit_type),
+            (None, Some(_)) => UnitType::Count,
+            (None, None) => UnitType::Unknown,
+        }
+    }
+
+    /// The ingredient as a line of text, like its [`Display`] form but with the quantity written
+    /// as a fraction where a cook would, as [`scale`](crate::SchemaOrgRecipe::scale) writes
+    /// them: "1/2 cup flour" rather than "0.5 cup flour", and "1 1/2" for 1.5. Other quantities
+    /// are rounded to two decimal places
+    pub fn display_fraction(&self) -> String {
+        self.display_with(self.quantity.map(format_quantity))
+    }
+
+    /// The ingredient as a line of text, with the quantity given as `quantity`
+    fn display_with(&self, quantity: Option<String>) -> String {
+        let parts = [quantity.as_deref(), self.unit(), Some(&self.name)];
+        let parts = parts.iter().flatten().copied().collect::<Vec<_>>();
+        parts.join(" ")
+    }
+
+    /// Whether both ingredients have the same name, ignoring case and whitespace differences
+    pub(crate) fn same_name(&self, other: &Self) -> bool {
+        // Compare lazily, rather than allocating normalized copies of both names
+        normalized_chars(&self.name).eq(normalized_chars(&other.name))
+    }
+
+    /// The ingredient in cooklang's `@name{quantity%unit}` syntax. Anything after a comma in the
+    /// name is treated as preparation, e.g. `@onion{1}(diced)` for "1 onion, diced", and the
+    /// characters cooklang reserves for this syntax are dropped from the name
+    pub(crate) fn to_cooklang(&self) -> String {
+        let clean = |s: &str| {
+            s.replace(['@', '{', '}', '%', '(', ')'], "")
+                .trim()
+                .to_string()
+        };
+        let (name, preparation) = match self.name.split_once(',') {
+            Some((name, preparation)) => (clean(name), clean(preparation)),
+            None => (clean(&self.name), String::new()),
+        };
+
+        let amount = match (self.quantity, self.unit()) {
+            (Some(q), Some(unit)) => format!("{}%{}", q, unit),
+            (Some(q), None) => q.to_string(),
+            (None, Some(unit)) => format!("%{}", unit),
+            (None, None) => String::new(),
+        };
+        let mut cooklang = format!("@{}{{{}}}", name, amount);
+        if !preparation.is_empty() {
+            cooklang.push_str(&format!("({})", preparation));
+        }
+        cooklang
+    }
+
+    /// Whether every word of `query` appears in the name, ignoring case and plurals, so that
+    /// "tomato" matches both "tomatoes" and "roma tomato"
+    pub fn matches(&self, query: &str) -> bool {
+        let name = stems(&self.name);
+        let query = stems(query);
+        !query.is_empty() && query.iter().all(|q| name.contains(q))
+    }
+}
+
+impl Display for ParsedIngredient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quantity = self.quantity.map(|q| q.to_string());
+        Display::fmt(&self.display_with(quantity), f)
+    }
+}
+
+/// Parse every ingredient of every recipe, merging ingredients which share a (normalized) name
+/// and unit into a single entry by summing their quantities. Ingredients whose units differ are
+/// kept separate, and entries appear in the order in which they were first seen
+///
+/// Recipes are only borrowed, so those held by a [`SchemaOrgEntry`](crate::SchemaOrgEntry) can be
+/// passed via [`recipes`](crate::SchemaOrgEntry::recipes) without cloning them first
+pub fn combine_shopping_list<'a>(
+    recipes: impl IntoIterator<Item = &'a Recipe>,
+) -> Vec<ParsedIngredient> {
+    let mut combined: Vec<ParsedIngredient> = Vec::new();
+
+    for ingredient in recipes.into_iter().flat_map(|r| r.ingredients().parse()) {
+        let existing = combined
+            .iter_mut()
+            .find(|i| i.unit == ingredient.unit && i.same_name(&ingredient));
+
+        match existing {
+            Some(i) => {
+                i.quantity = match (i.quantity, ingredient.quantity) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                }
+            }
+            None => combined.push(ingredient),
+        }
+    }
+
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_org::IngredientList;
+
+    fn parsed(quantity: Option<f64>, unit: Option<&str>, name: &str) -> ParsedIngredient {
+        ParsedIngredient {
+            quantity,
+            unit: unit.map(|u| Cow::Owned(u.into())),
+            name: name.into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_ingredient() {
+        assert_eq!(
+            parsed(Some(1.5), Some("cup"), "flour"),
+            ParsedIngredient::parse("1 1/2 cups flour")
+        );
+        assert_eq!(
+            parsed(Some(0.5), Some("tsp"), "salt"),
+            ParsedIngredient::parse("½ teaspoon of salt")
+        );
+        assert_eq!(
+            parsed(Some(200.), Some("g"), "butter, softened"),
+            ParsedIngredient::parse("200g butter, softened")
+        );
+        assert_eq!(
+            parsed(Some(2.), None, "eggs"),
+            ParsedIngredient::parse("2-3 eggs")
+        );
+        assert_eq!(
+            parsed(None, None, "Salt to taste"),
+            ParsedIngredient::parse("Salt to taste")
+        );
+    }
+
+    #[test]
+    fn test_scale_line() {
+        assert_eq!("3 cups flour", scale_line("1 1/2 cups flour", 2.));
+        assert_eq!("1/2 tsp salt", scale_line("¼ tsp salt", 2.));
+        assert_eq!("4-6 eggs", scale_line("2-3 eggs", 2.));
+        assert_eq!("150g butter", scale_line("100g butter", 1.5));
+        assert_eq!("1.23 l milk", scale_line("1 l milk", 1.234));
+        assert_eq!("Salt to taste", scale_line("Salt to taste", 2.));
+    }
+
+    #[test]
+    fn test_display_fraction() {
+        let display = |quantity| parsed(Some(quantity), Some("cup"), "flour").display_fraction();
+        assert_eq!("1/2 cup flour", display(0.5));
+        assert_eq!("1/4 cup flour", display(0.25));
+        assert_eq!("1/3 cup flour", display(0.33));
+        assert_eq!("1 1/2 cup flour", display(1.5));
+        assert_eq!("2 cup flour", display(2.));
+        assert_eq!("0.43 cup flour", display(0.4321));
+        assert_eq!(
+            "0.5 cup flour",
+            parsed(Some(0.5), Some("cup"), "flour").to_string()
+        );
+        assert_eq!("Salt", parsed(None, None, "Salt").display_fraction());
+    }
+
+    #[test]
+    fn test_unit_dictionary() {
+        let units = UnitDictionary::default()
+            .with_unit("glug", ["glugs"])
+            .with_unit("glug", ["splash"]);
+        assert_eq!(
+            parsed(Some(2.), Some("glug"), "olive oil"),
+            ParsedIngredient::parse_with("2 glugs olive oil", &units)
+        );
+        assert_eq!(
+            parsed(Some(1.), Some("glug"), "wine"),
+            ParsedIngredient::parse_with("1 Splash wine", &units)
+        );
+        assert_eq!(
+            parsed(Some(1.), Some("cup"), "stock"),
+            ParsedIngredient::parse_with("1 cup stock", &units)
+        );
+        assert_eq!(
+            parsed(Some(1.), None, "cup stock"),
+            ParsedIngredient::parse_with("1 cup stock", &UnitDictionary::empty())
+        );
+        assert_eq!(
+            parsed(Some(2.), None, "glugs olive oil"),
+            ParsedIngredient::parse("2 glugs olive oil")
+        );
+    }
+
+    #[test]
+    fn test_ingredient_matches() {
+        let ingredient = ParsedIngredient::parse("3 Roma tomatoes, diced");
+        assert!(ingredient.matches("tomato"));
+        assert!(ingredient.matches("Roma Tomatoes"));
+        assert!(!ingredient.matches("cherry tomato"));
+        assert!(!ingredient.matches(""));
+
+        assert!(ParsedIngredient::parse("2 cherries").matches("cherry"));
+        assert!(ParsedIngredient::parse("1 tbsp molasses").matches("molasses"));
+        assert!(ParsedIngredient::parse("4 peaches").matches("peach"));
+    }
+
+    #[test]
+    fn test_unit_type() {
+        let unit_type = |line| ParsedIngredient::parse(line).unit_type();
+        assert_eq!(UnitType::Volume, unit_type("2 tablespoons olive oil"));
+        assert_eq!(UnitType::Weight, unit_type("500g beef mince"));
+        assert_eq!(UnitType::Count, unit_type("3 eggs"));
+        assert_eq!(UnitType::Count, unit_type("2 cloves garlic"));
+        assert_eq!(UnitType::Unknown, unit_type("salt to taste"));
+
+        let units = UnitDictionary::default().with_unit("knob", ["knobs"]);
+        let butter = ParsedIngredient::parse_with("2 knobs of butter", &units);
+        assert_eq!(UnitType::Unknown, butter.unit_type());
+    }
+
+    #[test]
+    fn test_combine_shopping_list() {
+        let recipes = [
+            Recipe::new(
+                "Pancakes",
+                "",
+                IngredientList::multi(["1 cup flour", "2 eggs", "1 tbsp sugar"]),
+            ),
+            Recipe::new(
+                "Cake",
+                "",
+                IngredientList::multi(["2 cups Flour", "3 eggs", "100 g sugar"]),
+            ),
+        ];
+
+        assert_eq!(
+            vec![
+                parsed(Some(3.), Some("cup"), "flour"),
+                parsed(Some(5.), None, "eggs"),
+                parsed(Some(1.), Some("tbsp"), "sugar"),
+                parsed(Some(100.), Some("g"), "sugar"),
+            ],
+            combine_shopping_list(&recipes)
+        );
+    }
+}
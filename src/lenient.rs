@@ -0,0 +1,74 @@
+//! A best-effort pass which rewrites commonly observed JSON-ish syntax into strict JSON
+//!
+//! This accepts input which is not valid JSON (trailing commas, single-quoted strings), and
+//! may therefore accept documents a strict parser would reject outright
+
+/// Rewrite single-quoted strings as double-quoted ones and drop trailing commas before a closing
+/// `}` or `]`, leaving the contents of double-quoted strings untouched
+pub(crate) fn relax(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.char_indices();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                while let Some((_, c)) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next().map(|(_, c)| c)),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '\'' => {
+                out.push('"');
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => match chars.next() {
+                            Some((_, '\'')) => out.push('\''),
+                            Some((_, c)) => {
+                                out.push('\\');
+                                out.push(c);
+                            }
+                            None => {}
+                        },
+                        '"' => out.push_str("\\\""),
+                        '\'' => break,
+                        c => out.push(c),
+                    }
+                }
+                out.push('"');
+            }
+            ',' if matches!(
+                input[idx + 1..].trim_start().chars().next(),
+                Some('}' | ']')
+            ) => {}
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relax_trailing_commas() {
+        assert_eq!(
+            r#"{"a": [1, 2], "b": "x,}" }"#,
+            relax(r#"{"a": [1, 2,], "b": "x,}", }"#)
+        );
+    }
+
+    #[test]
+    fn test_relax_single_quotes() {
+        assert_eq!(
+            r#"{"name": "Grandma's \"best\" pie"}"#,
+            relax(r#"{'name': 'Grandma\'s "best" pie'}"#)
+        );
+    }
+}
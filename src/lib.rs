@@ -1,11 +1,122 @@
+mod allergen;
+mod charset;
+mod error;
+mod graph;
+mod heuristic;
+mod ingredient;
+#[cfg(feature = "lenient")]
+mod lenient;
+mod links;
+mod markup;
+mod microdata;
+mod print;
+mod rdfa;
 mod schema_org;
-pub use schema_org::{Recipe as SchemaOrgRecipe, SchemaEntry as SchemaOrgEntry};
+mod scrape;
+mod text;
+pub use allergen::Allergen;
+pub use error::{DurationError, ScrapeError};
+pub use graph::ResolveOptions;
+pub use ingredient::{combine_shopping_list, ParsedIngredient, UnitDictionary, UnitType};
+pub use schema_org::{
+    dedup_best, has_recipe, list_schema_types, pick_best, plan_timeline,
+    recipes_from_ndjson_reader, Difficulty, ItemList, MaybeDuration, RawRecipe,
+    Recipe as SchemaOrgRecipe, RecipeDiff, RecipeSection, RecipeStub,
+    SchemaEntry as SchemaOrgEntry, SourcedRecipe, TimeChange, TolerantRecipe, BOILERPLATE_STEPS,
+};
+#[cfg(feature = "parallel")]
+pub use scrape::DEFAULT_THREADS;
+pub use scrape::{
+    FieldProvenance, FieldSource, FileRecipes, Format, ScrapedRecipe, Scraper,
+    DEFAULT_JSON_ATTRIBUTES,
+};
+
+/// The traits and types most code using this crate needs, for glob importing
+///
+/// ```
+/// use recipe_scraper::prelude::*;
+///
+/// let html = r#"<script type="application/ld+json">
+///     {"name": "Toast", "description": "", "recipeIngredient": "bread"}
+/// </script>"#;
+/// let recipes: Vec<SchemaOrgRecipe> = SchemaOrgEntry::scrape_html(html)
+///     .iter()
+///     .flat_map(SchemaOrgEntry::extract_recipes)
+///     .collect();
+/// assert_eq!("Toast", recipes[0].name());
+/// ```
+pub mod prelude {
+    pub use crate::{Extract, SchemaOrgEntry, SchemaOrgRecipe, Scrape, ScrapeError};
+}
+
+/// Counters describing a single pass over an HTML document, as returned by
+/// [`SchemaOrgEntry::scrape_html_with_stats`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ScrapeStats {
+    /// Number of `ld+json` script blocks found in the document
+    pub scripts_found: usize,
+    /// Number of schema entries parsed from the script blocks, usually one per block (but more
+    /// for blocks holding several concatenated JSON values)
+    pub scripts_parsed: usize,
+    /// Number of recipes contained in the parsed entries
+    pub recipes_extracted: usize,
+}
 
 pub trait Extract {
     type Output;
     type Collection: IntoIterator<Item = Self::Output>;
 
     fn extract_recipes(&self) -> Self::Collection;
+
+    /// The single recipe best representing this source, defaulting to the first one extracted
+    fn primary_recipe(&self) -> Option<Self::Output> {
+        self.extract_recipes().into_iter().next()
+    }
+}
+
+/// A site-specific fixup of raw JSON-LD, for patching known-bad pages before their recipes are
+/// parsed, as run by [`SchemaOrgEntry::scrape_html_with_preprocessors`] and
+/// [`SchemaOrgEntry::from_json_value_with_preprocessors`]
+///
+/// Each preprocessor sees every JSON value exactly as it was read from the page (a whole script
+/// block, which may be a `@graph` or a list of nodes), before `@id` references are resolved and
+/// before anything is deserialized. Preprocessors run in the order given, each seeing the
+/// previous one's changes. Closures taking a `&mut serde_json::Value` are preprocessors too
+///
+/// ```
+/// use recipe_scraper::{PreProcessor, SchemaOrgEntry};
+///
+/// // A site which declares its recipes' ingredients in Spanish
+/// struct Ingredientes;
+///
+/// impl PreProcessor for Ingredientes {
+///     fn fix(&self, value: &mut serde_json::Value) {
+///         if let Some(recipe) = value.as_object_mut() {
+///             if let Some(ingredients) = recipe.remove("ingredientes") {
+///                 recipe.insert("recipeIngredient".to_string(), ingredients);
+///             }
+///         }
+///     }
+/// }
+///
+/// let html = r#"<script type="application/ld+json">
+///     {"@type": "Recipe", "name": "Toast", "description": "", "ingredientes": "pan"}
+/// </script>"#;
+/// assert!(SchemaOrgEntry::scrape_html_with_preprocessors(html, &[]).is_empty());
+/// let entries = SchemaOrgEntry::scrape_html_with_preprocessors(html, &[&Ingredientes]);
+/// assert_eq!("Toast", entries[0].recipes()[0].name());
+/// ```
+pub trait PreProcessor {
+    /// Patch `value` in place. Does nothing by default
+    fn fix(&self, value: &mut serde_json::Value) {
+        let _ = value;
+    }
+}
+
+impl<F: Fn(&mut serde_json::Value)> PreProcessor for F {
+    fn fix(&self, value: &mut serde_json::Value) {
+        self(value)
+    }
 }
 
 pub trait Scrape {
@@ -0,0 +1,193 @@
+//! Minimal [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-5.2) reference resolution,
+//! used to turn relative links found in recipes into absolute URLs
+
+fn has_scheme(s: &str) -> bool {
+    match s.find(':') {
+        Some(idx) => {
+            let scheme = &s[..idx];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let segments = path.split('/').collect::<Vec<_>>();
+    for (idx, segment) in segments.iter().enumerate() {
+        let last = idx + 1 == segments.len();
+        match *segment {
+            "." if last => output.push(""),
+            "." => {}
+            ".." => {
+                if output.len() > 1 {
+                    output.pop();
+                }
+                if last {
+                    output.push("");
+                }
+            }
+            s => output.push(s),
+        }
+    }
+    output.join("/")
+}
+
+/// Split a reference into its path and the query and fragment after it, as dot segments are only
+/// removed from the path
+fn split_path(reference: &str) -> (&str, &str) {
+    match reference.find(['?', '#']) {
+        Some(idx) => reference.split_at(idx),
+        None => (reference, ""),
+    }
+}
+
+/// Resolve `reference` against the absolute URL `base`. Returns `None` when `base` is not an
+/// absolute hierarchical URL; references which are already absolute are returned unchanged
+pub(crate) fn resolve(base: &str, reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    if has_scheme(reference) {
+        return Some(reference.to_string());
+    }
+
+    let base = base.trim();
+    let base = base.split('#').next().unwrap_or(base);
+    let (scheme, rest) = base.split_once("://")?;
+    if !has_scheme(base) || scheme.is_empty() {
+        return None;
+    }
+
+    let authority_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, path_and_query) = rest.split_at(authority_end);
+    let base_path = path_and_query.split('?').next().unwrap_or("");
+
+    let resolved = if let Some(network) = reference.strip_prefix("//") {
+        let path_start = network.find(['/', '?', '#']).unwrap_or(network.len());
+        let (authority, rest) = network.split_at(path_start);
+        let (path, suffix) = split_path(rest);
+        let path = remove_dot_segments(path);
+        format!("{}://{}{}{}", scheme, authority, path, suffix)
+    } else if reference.starts_with('/') {
+        let (path, suffix) = split_path(reference);
+        let path = remove_dot_segments(path);
+        format!("{}://{}{}{}", scheme, authority, path, suffix)
+    } else if reference.is_empty() {
+        base.to_string()
+    } else if reference.starts_with('?') {
+        format!("{}://{}{}{}", scheme, authority, base_path, reference)
+    } else if reference.starts_with('#') {
+        format!("{}{}", base, reference)
+    } else {
+        let dir = match base_path.rfind('/') {
+            Some(idx) => &base_path[..=idx],
+            None => "/",
+        };
+        let (path, suffix) = split_path(reference);
+        let path = remove_dot_segments(&format!("{}{}", dir, path));
+        format!("{}://{}{}{}", scheme, authority, path, suffix)
+    };
+
+    Some(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let base = "https://example.com/recipes/cake.html?print=1";
+        let resolve = |r| resolve(base, r).unwrap();
+
+        assert_eq!(
+            "https://cdn.example.com/a.jpg",
+            resolve("https://cdn.example.com/a.jpg")
+        );
+        assert_eq!(
+            "https://cdn.example.com/a.jpg",
+            resolve("//cdn.example.com/a.jpg")
+        );
+        assert_eq!("https://example.com/img/cake.jpg", resolve("/img/cake.jpg"));
+        assert_eq!("https://example.com/recipes/cake.jpg", resolve("cake.jpg"));
+        assert_eq!(
+            "https://example.com/img/cake.jpg",
+            resolve("../img/./cake.jpg")
+        );
+        assert_eq!("https://example.com/recipes/cake.html?p=2", resolve("?p=2"));
+        assert_eq!(None, super::resolve("not a url", "cake.jpg"));
+    }
+
+    #[test]
+    fn test_resolve_rfc_examples() {
+        // The normal and abnormal examples of RFC 3986, section 5.4
+        let examples = [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+        ];
+        for (reference, expected) in examples {
+            let resolved = resolve("http://a/b/c/d;p?q", reference);
+            assert_eq!(Some(expected), resolved.as_deref(), "{}", reference);
+        }
+    }
+
+    #[test]
+    fn test_resolve_network_and_query_references() {
+        let base = "https://example.com/recipes/cake.html?print=1#top";
+        let resolve = |r| resolve(base, r).unwrap();
+
+        assert_eq!("https://cdn.example.com", resolve("//cdn.example.com"));
+        assert_eq!(
+            "https://cdn.example.com/b.jpg?w=2",
+            resolve("//cdn.example.com/a/../b.jpg?w=2")
+        );
+        assert_eq!("https://example.com/recipes/cake.html?", resolve("?"));
+        assert_eq!(
+            "https://example.com/recipes/cake.html?a=1&b=../c",
+            resolve("?a=1&b=../c")
+        );
+        assert_eq!(
+            "https://example.com?q",
+            super::resolve("https://example.com", "?q").unwrap()
+        );
+        assert_eq!(
+            "https://example.com/cake.jpg",
+            super::resolve("https://example.com", "cake.jpg").unwrap()
+        );
+        assert_eq!("https://example.com/", resolve("../../.."));
+    }
+}
@@ -0,0 +1,137 @@
+//! The traversal shared by [microdata](crate::microdata) and [RDFa](crate::rdfa), which both mark
+//! up schema.org items with attributes on the page's own elements rather than in a script block,
+//! and differ only in the attributes used and how their values are read
+
+use scraper::node::Element;
+use scraper::{ElementRef, Html};
+use serde_json::{Map, Value};
+
+use crate::heuristic::{selector, text};
+use crate::{SchemaOrgRecipe as Recipe, TolerantRecipe};
+
+/// How deeply items may be nested within one another. Real pages rarely nest more than a few
+/// (a recipe's author, its steps), so deeper items are dropped rather than overflowing the stack
+/// on hostile documents
+const MAX_ITEM_DEPTH: usize = 16;
+
+/// The attributes a markup syntax declares items and their properties with
+pub(crate) struct Syntax {
+    /// The attribute marking an element as an item, whatever its value
+    pub(crate) scope: &'static str,
+    /// The attribute listing an item's types
+    pub(crate) types: &'static str,
+    /// The attribute listing the properties an element's value is given for
+    pub(crate) property: &'static str,
+    /// The schema.org name of a type or property term, or `None` for terms from other
+    /// vocabularies
+    pub(crate) name: fn(&str) -> Option<&str>,
+    /// The attribute value a (non-item) property element gives, taking the place of its text
+    pub(crate) value: fn(&Element) -> Option<&str>,
+}
+
+impl Syntax {
+    /// Whether `el` is an item of the schema.org type `ty`
+    fn is_item_of_type(&self, el: ElementRef<'_>, ty: &str) -> bool {
+        let value = el.value();
+        value.attr(self.scope).is_some()
+            && value.attr(self.types).map_or(false, |types| {
+                types.split_whitespace().any(|t| (self.name)(t) == Some(ty))
+            })
+    }
+
+    /// The value of a property element: a nested item, else the attribute value its syntax
+    /// gives, else its text. Items nested deeper than [`MAX_ITEM_DEPTH`] have none
+    fn property_value(&self, el: ElementRef<'_>, depth: usize) -> Option<Value> {
+        if el.value().attr(self.scope).is_some() {
+            return (depth < MAX_ITEM_DEPTH).then(|| self.item(el, depth + 1));
+        }
+        Some(match (self.value)(el.value()) {
+            Some(v) => Value::from(v.trim()),
+            None => Value::from(text(el)),
+        })
+    }
+
+    /// Add the properties found among the descendants of `el` to `properties`, in document
+    /// order, leaving nested items' properties to those items. The tree is walked with an
+    /// explicit stack, so that deeply nested markup can't overflow the call stack
+    fn collect_properties(
+        &self,
+        el: ElementRef<'_>,
+        properties: &mut Map<String, Value>,
+        depth: usize,
+    ) {
+        let mut pending = el
+            .children()
+            .rev()
+            .filter_map(ElementRef::wrap)
+            .collect::<Vec<_>>();
+        while let Some(child) = pending.pop() {
+            if let Some(terms) = child.value().attr(self.property) {
+                if let Some(value) = self.property_value(child, depth) {
+                    for name in terms.split_whitespace().filter_map(self.name) {
+                        add_property(properties, name, value.clone());
+                    }
+                }
+            }
+            if child.value().attr(self.scope).is_none() {
+                pending.extend(child.children().rev().filter_map(ElementRef::wrap));
+            }
+        }
+    }
+
+    /// An item as JSON-LD would describe it, with an `@type` from its schema.org types. `depth`
+    /// is the number of items it's nested within
+    fn item(&self, el: ElementRef<'_>, depth: usize) -> Value {
+        let mut properties = Map::new();
+        let types = el.value().attr(self.types).unwrap_or_default();
+        let types = types
+            .split_whitespace()
+            .filter_map(self.name)
+            .collect::<Vec<_>>();
+        match types.as_slice() {
+            [] => {}
+            [ty] => {
+                properties.insert("@type".to_string(), Value::from(*ty));
+            }
+            types => {
+                properties.insert("@type".to_string(), Value::from(types.to_vec()));
+            }
+        }
+        self.collect_properties(el, &mut properties, depth);
+        Value::Object(properties)
+    }
+
+    /// Every Recipe item in `html`, in document order. Recipes nested within another are
+    /// skipped, as their properties belong to the outer recipe. Malformed properties are
+    /// dropped, and missing required ones left empty, per [`TolerantRecipe`]
+    pub(crate) fn scrape(&self, html: &Html) -> Vec<Recipe> {
+        let items = selector(&format!("[{}][{}]", self.scope, self.types));
+        let recipes = html
+            .select(&items)
+            .filter(|el| self.is_item_of_type(*el, "Recipe"))
+            .filter(|el| {
+                !el.ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .any(|a| self.is_item_of_type(a, "Recipe"))
+            });
+        recipes
+            .filter_map(|el| serde_json::from_value::<TolerantRecipe>(self.item(el, 0)).ok())
+            .map(TolerantRecipe::into_recipe)
+            .collect()
+    }
+}
+
+/// Add a property's value to `properties`, collecting the values of repeated properties into a
+/// list
+fn add_property(properties: &mut Map<String, Value>, name: &str, value: Value) {
+    match properties.get_mut(name) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::from(vec![first, value]);
+        }
+        None => {
+            properties.insert(name.to_string(), value);
+        }
+    }
+}
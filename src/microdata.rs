@@ -0,0 +1,117 @@
+//! Recipes from [HTML microdata](https://html.spec.whatwg.org/multipage/microdata.html), where
+//! schema.org properties are marked up with `itemscope`, `itemtype` and `itemprop` attributes on
+//! the page's own elements rather than held in a script block
+
+use scraper::node::Element;
+use scraper::Html;
+
+use crate::markup::Syntax;
+use crate::SchemaOrgRecipe as Recipe;
+
+/// The last segment of a schema.org type or property URL, such as "Recipe" for
+/// "https://schema.org/Recipe". Plain names are returned as they are
+fn short_name(name: &str) -> Option<&str> {
+    name.rsplit(|c| c == '/' || c == '#').next()
+}
+
+/// The value of a property element, taken from the attribute the microdata specification gives
+/// for its element (such as `content` for `<meta>` and `href` for `<a>`)
+fn property_value(el: &Element) -> Option<&str> {
+    let attr = match el.name() {
+        "meta" => "content",
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => "src",
+        "a" | "area" | "link" => "href",
+        "object" => "data",
+        "data" | "meter" => "value",
+        "time" => "datetime",
+        _ => "content",
+    };
+    el.attr(attr)
+}
+
+const MICRODATA: Syntax = Syntax {
+    scope: "itemscope",
+    types: "itemtype",
+    property: "itemprop",
+    name: short_name,
+    value: property_value,
+};
+
+/// Every Recipe item in `html`, as by [`Syntax::scrape`]
+pub(crate) fn scrape(html: &Html) -> Vec<Recipe> {
+    MICRODATA.scrape(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_microdata_scrape() {
+        let html = Html::parse_document(
+            r#"<div itemscope itemtype="https://schema.org/Recipe">
+                <h1 itemprop="name">Pancakes</h1>
+                <img itemprop="image" src="https://example.com/p.jpg">
+                <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+                    <span itemprop="name">Grandma</span>
+                </span>
+                <meta itemprop="totalTime" content="PT20M">
+                <ul>
+                    <li itemprop="recipeIngredient">1 cup  flour</li>
+                    <li itemprop="recipeIngredient">2 eggs</li>
+                </ul>
+                <ol>
+                    <li itemprop="recipeInstructions" itemscope itemtype="https://schema.org/HowToStep">
+                        <span itemprop="text">Mix</span>
+                    </li>
+                    <li itemprop="recipeInstructions" itemscope itemtype="https://schema.org/HowToStep">
+                        <span itemprop="text">Fry</span>
+                    </li>
+                </ol>
+            </div>
+            <div itemscope itemtype="https://schema.org/Person"><span itemprop="name">X</span></div>"#,
+        );
+        let recipes = scrape(&html);
+
+        assert_eq!(1, recipes.len());
+        let recipe = &recipes[0];
+        assert_eq!("Pancakes", recipe.name());
+        assert_eq!("", recipe.description());
+        assert_eq!(
+            vec!["1 cup flour", "2 eggs"],
+            recipe.ingredients().entries().collect::<Vec<_>>()
+        );
+        let steps = recipe.directions().as_ref().unwrap().steps();
+        assert_eq!(
+            vec!["Mix", "Fry"],
+            steps.iter().map(|s| s.text()).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(20), recipe.total_minutes());
+        assert_eq!(Some("https://example.com/p.jpg"), recipe.thumbnail_url());
+    }
+
+    #[test]
+    fn test_microdata_deep_nesting() {
+        let depth = 500;
+        let html = format!(
+            r#"<div itemscope itemtype="https://schema.org/Recipe"><span itemprop="name">Deep</span>{}<span itemprop="recipeIngredient">salt</span>{}</div>"#,
+            "<div>".repeat(depth),
+            "</div>".repeat(depth),
+        );
+        let recipes = scrape(&Html::parse_document(&html));
+        assert_eq!("Deep", recipes[0].name());
+        assert_eq!(
+            vec!["salt"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
+        );
+
+        let nested = "<div itemprop=\"author\" itemscope itemtype=\"https://schema.org/Person\">";
+        let html = format!(
+            r#"<div itemscope itemtype="https://schema.org/Recipe"><span itemprop="name">Deep</span>{}{}</div>"#,
+            nested.repeat(depth),
+            "</div>".repeat(depth),
+        );
+        let recipes = scrape(&Html::parse_document(&html));
+        assert_eq!("Deep", recipes[0].name());
+    }
+}
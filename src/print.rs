@@ -0,0 +1,114 @@
+//! Recipes from the minimal markup of print pages (such as those saved to PDF), which commonly
+//! lack structured data but follow the predictable layouts of the popular recipe card plugins
+
+use scraper::{ElementRef, Html};
+use serde_json::{json, Value};
+
+use crate::heuristic::{selector, text};
+use crate::SchemaOrgRecipe as Recipe;
+
+/// Elements wrapping a printable recipe card, each yielding at most one recipe
+const CONTAINERS: &str = ".recipe-print, .print-recipe, .recipe-card-print, .wprm-print, \
+    .wprm-print-recipe, .tasty-recipes-print, .mv-create-print, .mv-print";
+
+/// The recipe's title, taking the first match
+const NAMES: &str = ".recipe-title, .recipe-name, .wprm-recipe-name, .tasty-recipes-title, \
+    .mv-create-title, h1, h2";
+
+/// The recipe's summary, taking the first match
+const DESCRIPTIONS: &str = ".recipe-summary, .recipe-description, .wprm-recipe-summary, \
+    .tasty-recipes-description, .mv-create-description";
+
+/// Every ingredient line
+const INGREDIENTS: &str = ".recipe-ingredients li, .ingredients li, .wprm-recipe-ingredient, \
+    .tasty-recipes-ingredients li, .mv-create-ingredients li";
+
+/// Every step
+const INSTRUCTIONS: &str = ".recipe-instructions li, .instructions li, .directions li, \
+    .wprm-recipe-instruction-text, .tasty-recipes-instructions li, .mv-create-instructions li";
+
+/// The non-empty text of every element within `container` matching `selectors`
+fn texts(container: ElementRef<'_>, selectors: &str) -> Vec<String> {
+    container
+        .select(&selector(selectors))
+        .map(text)
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// A recipe from a single print layout container, which needs a title and at least one
+/// ingredient or step
+fn card(container: ElementRef<'_>) -> Option<Recipe> {
+    let name = texts(container, NAMES).into_iter().next()?;
+    let ingredients = texts(container, INGREDIENTS);
+    let steps = texts(container, INSTRUCTIONS);
+    if ingredients.is_empty() && steps.is_empty() {
+        return None;
+    }
+
+    let description = texts(container, DESCRIPTIONS).into_iter().next();
+    let mut recipe = json!({
+        "name": name,
+        "description": description.unwrap_or_default(),
+        "recipeIngredient": ingredients,
+    });
+    if !steps.is_empty() {
+        recipe["recipeInstructions"] = Value::from(steps);
+    }
+
+    serde_json::from_value(recipe).ok()
+}
+
+/// Every recipe laid out for printing in `html`, in document order. Containers nested within
+/// another are skipped, as their content belongs to the outer recipe
+pub(crate) fn scrape(html: &Html) -> Vec<Recipe> {
+    let containers = selector(CONTAINERS);
+    html.select(&containers)
+        .filter(|el| {
+            !el.ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| containers.matches(&a))
+        })
+        .filter_map(card)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_scrape() {
+        let html = Html::parse_document(
+            r#"<body class="print">
+                <div class="wprm-print"><div class="wprm-print-recipe">
+                    <h2 class="wprm-recipe-name">Banana  Bread</h2>
+                    <div class="wprm-recipe-summary">Moist</div>
+                    <ul><li class="wprm-recipe-ingredient">3 bananas</li>
+                        <li class="wprm-recipe-ingredient">2 cups flour</li></ul>
+                    <ol><li><div class="wprm-recipe-instruction-text">Mash</div></li>
+                        <li><div class="wprm-recipe-instruction-text">Bake</div></li></ol>
+                </div></div>
+                <div class="recipe-print"><h1>Toast</h1>
+                    <div class="directions"><ol><li>Toast the bread</li></ol></div></div>
+                <div class="print-recipe"><h1>Nothing to cook</h1></div>
+            </body>"#,
+        );
+        let recipes = scrape(&html);
+
+        assert_eq!(2, recipes.len());
+        assert_eq!("Banana Bread", recipes[0].name());
+        assert_eq!("Moist", recipes[0].description());
+        assert_eq!(
+            vec!["3 bananas", "2 cups flour"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
+        );
+        let steps = recipes[0].directions().as_ref().unwrap().steps();
+        assert_eq!(
+            vec!["Mash", "Bake"],
+            steps.iter().map(|s| s.text()).collect::<Vec<_>>()
+        );
+        assert_eq!("Toast", recipes[1].name());
+        assert!(recipes[1].ingredients().is_empty());
+    }
+}
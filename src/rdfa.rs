@@ -0,0 +1,111 @@
+//! Recipes from [RDFa](https://www.w3.org/TR/rdfa-lite/), where schema.org properties are marked
+//! up with `typeof` and `property` attributes on the page's own elements, as in
+//! `<div vocab="https://schema.org/" typeof="Recipe">` or `typeof="schema:Recipe"`
+
+use scraper::node::Element;
+use scraper::Html;
+
+use crate::markup::Syntax;
+use crate::SchemaOrgRecipe as Recipe;
+
+/// The schema.org name of an RDFa term: bare names ("name") as they are, and prefixed names
+/// ("schema:name") or full URLs ("https://schema.org/name") reduced to their last segment. Terms
+/// from other vocabularies (such as "og:title") yield `None`
+fn schema_name(term: &str) -> Option<&str> {
+    let url = term
+        .strip_prefix("https://schema.org/")
+        .or_else(|| term.strip_prefix("http://schema.org/"));
+    match url.or_else(|| term.strip_prefix("schema:")) {
+        Some(name) => Some(name),
+        None if term.contains(':') => None,
+        None => Some(term),
+    }
+}
+
+/// The value of a property element: its `content`, or the URL it links to (for `href`, `src`
+/// and `resource`), in that order
+fn property_value(el: &Element) -> Option<&str> {
+    ["content", "href", "src", "resource"]
+        .iter()
+        .find_map(|a| el.attr(a))
+}
+
+const RDFA: Syntax = Syntax {
+    scope: "typeof",
+    types: "typeof",
+    property: "property",
+    name: schema_name,
+    value: property_value,
+};
+
+/// Every Recipe resource in `html`, as by [`Syntax::scrape`]
+pub(crate) fn scrape(html: &Html) -> Vec<Recipe> {
+    RDFA.scrape(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rdfa_scrape() {
+        let html = Html::parse_document(
+            r#"<head><meta property="og:title" content="Not the recipe"></head>
+            <div prefix="schema: https://schema.org/" typeof="schema:Recipe">
+                <h1 property="schema:name">Pancakes</h1>
+                <p property="description">Fluffy</p>
+                <p property="og:description">Not the description</p>
+                <meta property="schema:totalTime" content="PT20M">
+                <img property="schema:image" src="https://example.com/p.jpg">
+                <ul>
+                    <li property="schema:recipeIngredient">1 cup  flour</li>
+                    <li property="https://schema.org/recipeIngredient">2 eggs</li>
+                </ul>
+                <ol>
+                    <li property="recipeInstructions" typeof="HowToStep">
+                        <span property="text">Mix</span>
+                    </li>
+                    <li property="recipeInstructions" typeof="HowToStep">
+                        <span property="text">Fry</span>
+                    </li>
+                </ol>
+            </div>"#,
+        );
+        let recipes = scrape(&html);
+
+        assert_eq!(1, recipes.len());
+        let recipe = &recipes[0];
+        assert_eq!("Pancakes", recipe.name());
+        assert_eq!("Fluffy", recipe.description());
+        assert_eq!(
+            vec!["1 cup flour", "2 eggs"],
+            recipe.ingredients().entries().collect::<Vec<_>>()
+        );
+        let steps = recipe.directions().as_ref().unwrap().steps();
+        assert_eq!(
+            vec!["Mix", "Fry"],
+            steps.iter().map(|s| s.text()).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(20), recipe.total_minutes());
+        assert_eq!(Some("https://example.com/p.jpg"), recipe.thumbnail_url());
+    }
+
+    #[test]
+    fn test_rdfa_deep_nesting() {
+        let depth = 500;
+        let html = format!(
+            r#"<div typeof="schema:Recipe"><span property="name">Deep</span>{}<span property="recipeIngredient">salt</span>{}{}{}</div>"#,
+            "<div>".repeat(depth),
+            "</div>".repeat(depth),
+            r#"<div property="author" typeof="Person">"#.repeat(depth),
+            "</div>".repeat(depth),
+        );
+        let recipes = scrape(&Html::parse_document(&html));
+
+        assert_eq!("Deep", recipes[0].name());
+        assert_eq!(
+            vec!["salt"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
+        );
+    }
+}
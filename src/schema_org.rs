@@ -1,19 +1,25 @@
 //! Parse a [schema.org Recipe](https://schema.org/Recipe) from various commonly observed
 //! structures
 
+use std::borrow::Cow;
 use std::fmt::{self, Debug, Display};
 
 use accessory::Accessors;
 use iso8601_duration::Duration;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct MaybeDuration(Option<Duration>);
+/// A duration which may be missing or unparseable, alongside the string it was read from (if
+/// any), whether or not that was a valid ISO 8601 duration. That string is re-emitted verbatim on
+/// serialization, so that "PT0H30M" doesn't become "PT30M" and "soon" isn't lost on a round
+/// trip; durations without one (such as those built from separate `hours` and `minutes` keys)
+/// are serialized from their components instead. The original string plays no part in equality
+#[derive(Clone, Debug)]
+pub struct MaybeDuration(Option<Duration>, Option<String>);
 
 #[cfg(test)]
 impl MaybeDuration {
     pub(crate) fn from_secs(secs: impl Into<f32>) -> Self {
-        Self(Some(Duration::new(0., 0., 0., 0., 0., secs.into())))
+        Self(Some(Duration::new(0., 0., 0., 0., 0., secs.into())), None)
     }
 }
 
@@ -22,23 +28,388 @@ impl MaybeDuration {
         self.0.as_ref()
     }
 
+    /// The string this duration was read from, if it was read from one. This is kept even when
+    /// it didn't parse, in which case [`duration`](Self::duration) is `None`
+    pub fn original(&self) -> Option<&str> {
+        self.1.as_deref()
+    }
+
+    /// The same duration without its original string, so that it is serialized in the normalized
+    /// form reconstructed from its components
+    pub fn normalized(&self) -> Self {
+        Self(self.0, None)
+    }
+
+    /// The duration in whole minutes, rounded to the nearest minute. Durations involving years or
+    /// months (whose length varies) yield `None` rather than an approximation
+    pub fn total_minutes(&self) -> Option<u64> {
+        let minutes = self.0?.num_minutes()?.round();
+        (minutes.is_finite() && minutes >= 0.).then(|| minutes as u64)
+    }
+
+    /// The duration as whole `(hours, minutes, seconds)`, with days folded into the hours and
+    /// overflowing components carried (so "PT90M" is `(1, 30, 0)`). Like
+    /// [`total_minutes`](Self::total_minutes), durations involving years or months yield `None`
+    pub fn hms(&self) -> Option<(u32, u32, u32)> {
+        let d = self.0?;
+        if d.year != 0. || d.month != 0. {
+            return None;
+        }
+
+        let seconds = ((f64::from(d.day) * 24. + f64::from(d.hour)) * 60. + f64::from(d.minute))
+            * 60.
+            + f64::from(d.second);
+        let seconds = seconds.round();
+        if !seconds.is_finite() || seconds < 0. || seconds > f64::from(u32::MAX) {
+            return None;
+        }
+
+        let seconds = seconds as u32;
+        Some((seconds / 3600, seconds / 60 % 60, seconds % 60))
+    }
+
+    /// Strictly parse an ISO 8601 duration (such as "PT1H30M"), describing what was wrong with it
+    /// on failure. Deserialization is more forgiving, treating such strings as absent durations
+    pub fn try_parse(s: &str) -> Result<Self, crate::DurationError> {
+        let input = s.trim();
+        if input.is_empty() {
+            return Err(crate::DurationError::Empty);
+        }
+        if !input.starts_with('P') {
+            let input = input.to_string();
+            return Err(crate::DurationError::MissingDesignator { input });
+        }
+
+        parse_iso_duration(input)
+            .map(|d| Self(Some(d), Some(input.to_string())))
+            .map_err(|e| crate::DurationError::Invalid {
+                input: input.to_string(),
+                position: e.position,
+            })
+    }
+
+    /// The sum of both durations, component by component. A missing duration counts as zero, so
+    /// the result is only missing when both are
+    pub(crate) fn plus(&self, other: &Self) -> Self {
+        match (self.0, other.0) {
+            (Some(a), Some(b)) => Self(
+                Some(Duration::new(
+                    a.year + b.year,
+                    a.month + b.month,
+                    a.day + b.day,
+                    a.hour + b.hour,
+                    a.minute + b.minute,
+                    a.second + b.second,
+                )),
+                None,
+            ),
+            (a, b) => Self(a.or(b), None),
+        }
+    }
+
+    /// The duration as a [`std::time::Duration`], unless it involves years or months, or is
+    /// negative or too long to represent (where [`Duration::to_std`] would panic)
+    fn to_std(&self) -> Option<std::time::Duration> {
+        let seconds = f64::from(self.0?.num_seconds()?);
+        let representable = seconds.is_finite() && seconds >= 0. && seconds < u64::MAX as f64;
+        representable.then(|| std::time::Duration::from_secs_f64(seconds))
+    }
+
     pub fn human_readable(&self) -> Option<String> {
         let pp = |d| pretty_duration::pretty_duration(&d, None);
-        self.0.and_then(|d| d.to_std().map(pp))
+        self.to_std().map(pp)
+    }
+
+    /// The duration spelled out with full unit names in the language identified by `lang` (a
+    /// language tag such as "de" or "fr-CA", as found in `inLanguage`), like "30 Minuten". Only
+    /// English, German, French and Spanish are known; any other language uses English
+    pub fn human_readable_lang(&self, lang: &str) -> Option<String> {
+        use pretty_duration::{PrettyDurationOptions, PrettyDurationOutputFormat};
+
+        let primary = lang
+            .split(|c| c == '-' || c == '_')
+            .next()
+            .unwrap_or_default();
+        let (_, singular, plural) = DURATION_LABELS
+            .iter()
+            .find(|(code, _, _)| code.eq_ignore_ascii_case(primary))
+            .unwrap_or(&DURATION_LABELS[0]);
+        let pp = |d| {
+            let options = PrettyDurationOptions {
+                output_format: Some(PrettyDurationOutputFormat::Expanded),
+                singular_labels: Some(singular.clone()),
+                plural_labels: Some(plural.clone()),
+            };
+            pretty_duration::pretty_duration(&d, Some(options))
+        };
+        self.to_std().map(pp)
+    }
+}
+
+const fn duration_labels(
+    [year, month, day, hour, minute, second, millisecond]: [&'static str; 7],
+) -> pretty_duration::PrettyDurationLabels {
+    pretty_duration::PrettyDurationLabels {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        millisecond,
+    }
+}
+
+/// Singular and plural unit names by language code, as used by
+/// [`MaybeDuration::human_readable_lang`]. The first (English) is the fallback
+const DURATION_LABELS: &[(
+    &str,
+    pretty_duration::PrettyDurationLabels,
+    pretty_duration::PrettyDurationLabels,
+)] = &[
+    (
+        "en",
+        duration_labels([
+            "year",
+            "month",
+            "day",
+            "hour",
+            "minute",
+            "second",
+            "millisecond",
+        ]),
+        duration_labels([
+            "years",
+            "months",
+            "days",
+            "hours",
+            "minutes",
+            "seconds",
+            "milliseconds",
+        ]),
+    ),
+    (
+        "de",
+        duration_labels([
+            "Jahr",
+            "Monat",
+            "Tag",
+            "Stunde",
+            "Minute",
+            "Sekunde",
+            "Millisekunde",
+        ]),
+        duration_labels([
+            "Jahre",
+            "Monate",
+            "Tage",
+            "Stunden",
+            "Minuten",
+            "Sekunden",
+            "Millisekunden",
+        ]),
+    ),
+    (
+        "fr",
+        duration_labels([
+            "an",
+            "mois",
+            "jour",
+            "heure",
+            "minute",
+            "seconde",
+            "milliseconde",
+        ]),
+        duration_labels([
+            "ans",
+            "mois",
+            "jours",
+            "heures",
+            "minutes",
+            "secondes",
+            "millisecondes",
+        ]),
+    ),
+    (
+        "es",
+        duration_labels([
+            "año",
+            "mes",
+            "día",
+            "hora",
+            "minuto",
+            "segundo",
+            "milisegundo",
+        ]),
+        duration_labels([
+            "años",
+            "meses",
+            "días",
+            "horas",
+            "minutos",
+            "segundos",
+            "milisegundos",
+        ]),
+    ),
+];
+
+/// Parse an ISO 8601 duration, allowing fractional components (such as "PT0.5H") to use a decimal
+/// comma as ISO 8601 permits ("PT0,5H"), which [`Duration::parse`] rejects
+fn parse_iso_duration(s: &str) -> Result<Duration, iso8601_duration::ParseDurationError> {
+    Duration::parse(s).or_else(|e| match s.contains(',') {
+        true => Duration::parse(&s.replace(',', ".")).map_err(|_| e),
+        false => Err(e),
+    })
+}
+
+/// The forms a duration is found in: ISO 8601 strings, or objects with separate (numeric)
+/// `hours`, `minutes` and `seconds` keys
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DurationRepr {
+    Iso(String),
+    Components {
+        hours: Option<f32>,
+        minutes: Option<f32>,
+        seconds: Option<f32>,
+    },
+}
+
+impl DurationRepr {
+    fn into_duration(self) -> MaybeDuration {
+        match self {
+            Self::Iso(s) => match parse_iso_duration(&s) {
+                Ok(d) => MaybeDuration(Some(d), Some(s)),
+                Err(_) => MaybeDuration(None, Some(s)),
+            },
+            Self::Components {
+                hours: None,
+                minutes: None,
+                seconds: None,
+            } => MaybeDuration(None, None),
+            Self::Components {
+                hours,
+                minutes,
+                seconds,
+            } => {
+                let [h, m, s] = [hours, minutes, seconds].map(Option::unwrap_or_default);
+                MaybeDuration(Some(Duration::new(0., 0., 0., h, m, s)), None)
+            }
+        }
     }
 }
 
 impl<'de> Deserialize<'de> for MaybeDuration {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        Ok(Self(Deserialize::deserialize(deserializer).ok()))
+        let repr: Result<DurationRepr, _> = Deserialize::deserialize(deserializer);
+        Ok(repr.map_or(Self(None, None), DurationRepr::into_duration))
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+impl Serialize for MaybeDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (&self.0, &self.1) {
+            (_, Some(original)) => serializer.serialize_str(original),
+            (duration, None) => duration.serialize(serializer),
+        }
+    }
+}
+
+impl PartialEq for MaybeDuration {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum IngredientList {
     Single(String),
     Multi(Vec<String>),
+    /// A list containing structured (`HowToSupply`) ingredients, possibly mixed with plain text
+    Structured(Vec<Ingredient>),
+}
+
+/// A single entry of an ingredient list which contains structured ingredients
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Ingredient {
+    Text(String),
+    Supply(HowToSupply),
+}
+
+impl Ingredient {
+    /// The ingredient as a single line of text, e.g. "2 cups flour"
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(s) => s,
+            Self::Supply(s) => &s.text,
+        }
+    }
+}
+
+/// A [schema.org HowToSupply](https://schema.org/HowToSupply), as used for ingredients whose
+/// quantity is declared separately from their name
+#[derive(Clone, Debug, PartialEq, Accessors, Deserialize, Serialize)]
+#[access(get)]
+#[serde(from = "SupplyRepr", tag = "@type", rename = "HowToSupply")]
+pub struct HowToSupply {
+    name: String,
+    #[serde(rename = "requiredQuantity", skip_serializing_if = "Option::is_none")]
+    required_quantity: Option<Quantity>,
+    #[access(skip)]
+    #[serde(skip)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct SupplyRepr {
+    name: String,
+    #[serde(rename = "requiredQuantity", default, deserialize_with = "tolerant")]
+    required_quantity: Option<Quantity>,
+}
+
+impl From<SupplyRepr> for HowToSupply {
+    fn from(repr: SupplyRepr) -> Self {
+        let text = match &repr.required_quantity {
+            Some(q) => format!("{} {}", q, repr.name),
+            None => repr.name.clone(),
+        };
+        Self {
+            name: repr.name,
+            required_quantity: repr.required_quantity,
+            text,
+        }
+    }
+}
+
+impl HowToSupply {
+    fn scaled(&self, factor: f64) -> Self {
+        SupplyRepr {
+            name: self.name.clone(),
+            required_quantity: self.required_quantity.as_ref().map(|q| q.scaled(factor)),
+        }
+        .into()
+    }
+
+    /// Build the parsed form directly from the structured quantity where possible, only
+    /// falling back to parsing the text when the quantity was itself given as free text
+    fn parse_with(&self, units: &crate::UnitDictionary) -> crate::ParsedIngredient {
+        use crate::ParsedIngredient;
+
+        match &self.required_quantity {
+            Some(Quantity::Number(n)) => {
+                ParsedIngredient::from_parts(Some(*n), None, &self.name, units)
+            }
+            Some(Quantity::Value(v)) => ParsedIngredient::from_parts(
+                v.value.or(v.min_value),
+                v.unit_text.as_deref(),
+                &self.name,
+                units,
+            ),
+            Some(Quantity::String(_)) | None => ParsedIngredient::parse_with(&self.text, units),
+        }
+    }
 }
 
 impl IntoIterator for IngredientList {
@@ -49,6 +420,166 @@ impl IntoIterator for IngredientList {
         match self {
             Self::Single(s) => vec![s].into_iter(),
             Self::Multi(v) => v.into_iter(),
+            Self::Structured(v) => v
+                .iter()
+                .map(|i| i.text().to_string())
+                .collect::<Vec<_>>()
+                .into_iter(),
+        }
+    }
+}
+
+impl IngredientList {
+    /// Iterate over the individual ingredient lines, splitting a single ingredient string on
+    /// newlines and skipping blank entries
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        let entries = match self {
+            Self::Single(s) => s.lines().collect(),
+            Self::Multi(v) => v.iter().map(String::as_str).collect::<Vec<_>>(),
+            Self::Structured(v) => v.iter().map(Ingredient::text).collect(),
+        };
+        entries.into_iter().map(str::trim).filter(|s| !s.is_empty())
+    }
+
+    /// The ingredient lines split into groups at headings such as "For the dough:", each
+    /// alongside its name ("dough"). Lines before the first heading form a group with an empty
+    /// name, which is left out when empty
+    pub fn groups(&self) -> Vec<(&str, Vec<&str>)> {
+        let mut groups = vec![("", Vec::new())];
+        for entry in self.entries() {
+            match crate::ingredient::group_heading(entry) {
+                Some(name) => groups.push((name, Vec::new())),
+                // Unwrap is appropriate here as there is always at least one group
+                None => groups.last_mut().unwrap().1.push(entry),
+            }
+        }
+        if groups.len() > 1 && groups[0].1.is_empty() {
+            groups.remove(0);
+        }
+        groups
+    }
+
+    /// The number of ingredient lines, as given by [`entries`](Self::entries)
+    pub fn len(&self) -> usize {
+        self.entries().count()
+    }
+
+    /// Whether there are no ingredient lines, e.g. for a blank ingredient string
+    pub fn is_empty(&self) -> bool {
+        self.entries().next().is_none()
+    }
+
+    /// Whether both lists contain the same ingredient lines, regardless of their order (or of
+    /// whether they were given as a single string or a list). Unlike `==`, which is sensitive to
+    /// both
+    pub fn eq_unordered(&self, other: &Self) -> bool {
+        let (mut ours, mut theirs) = (
+            self.entries().collect::<Vec<_>>(),
+            other.entries().collect::<Vec<_>>(),
+        );
+        ours.sort_unstable();
+        theirs.sort_unstable();
+        ours == theirs
+    }
+
+    pub(crate) fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::Single(s) => Self::Single(f(s)),
+            Self::Multi(v) => Self::Multi(v.iter().map(|s| f(s)).collect()),
+            Self::Structured(v) => Self::Structured(
+                v.iter()
+                    .map(|i| match i {
+                        Ingredient::Text(s) => Ingredient::Text(f(s)),
+                        Ingredient::Supply(s) => Ingredient::Supply(HowToSupply {
+                            name: f(&s.name),
+                            text: f(&s.text),
+                            ..s.clone()
+                        }),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The list without repeated ingredient lines (ignoring surrounding whitespace), keeping the
+    /// first of each
+    pub(crate) fn deduped(&self) -> Self {
+        fn first_of_each<T: Clone>(items: &[T], text: impl Fn(&T) -> &str) -> Vec<T> {
+            let mut seen: Vec<&str> = Vec::new();
+            let mut kept = Vec::new();
+            for item in items {
+                let text = text(item).trim();
+                if !seen.contains(&text) {
+                    seen.push(text);
+                    kept.push(item.clone());
+                }
+            }
+            kept
+        }
+
+        match self {
+            Self::Single(s) => {
+                let lines = s.lines().collect::<Vec<_>>();
+                Self::Single(first_of_each(&lines, |l| l).join("\n"))
+            }
+            Self::Multi(v) => Self::Multi(first_of_each(v, |s| s.as_str())),
+            Self::Structured(v) => Self::Structured(first_of_each(v, Ingredient::text)),
+        }
+    }
+
+    /// The list with each ingredient's leading quantity multiplied by `factor`
+    pub(crate) fn scaled(&self, factor: f64) -> Self {
+        let scale = |s: &str| crate::ingredient::scale_line(s, factor);
+        match self {
+            Self::Single(s) => Self::Single(s.lines().map(scale).collect::<Vec<_>>().join("\n")),
+            Self::Multi(v) => Self::Multi(v.iter().map(|s| scale(s)).collect()),
+            Self::Structured(v) => Self::Structured(
+                v.iter()
+                    .map(|i| match i {
+                        Ingredient::Text(s) => Ingredient::Text(scale(s)),
+                        Ingredient::Supply(s) => Ingredient::Supply(s.scaled(factor)),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Parse each ingredient line into its quantity, unit and name. Structured ingredients take
+    /// their quantity and unit from their `requiredQuantity` rather than from parsing text
+    pub fn parse(&self) -> Vec<crate::ParsedIngredient> {
+        self.parse_with(&crate::UnitDictionary::default())
+    }
+
+    /// Like [`parse`](Self::parse), but recognizing the units in `units`
+    pub fn parse_with(&self, units: &crate::UnitDictionary) -> Vec<crate::ParsedIngredient> {
+        self.parsed_with_raw_with(units)
+            .into_iter()
+            .map(|(_, parsed)| parsed)
+            .collect()
+    }
+
+    /// Pair each ingredient line (as given by [`entries`](Self::entries)) with its
+    /// [parsed](Self::parse) form, in order, e.g. to display "2 cups flour" while filtering by
+    /// "flour"
+    pub fn parsed_with_raw(&self) -> Vec<(String, crate::ParsedIngredient)> {
+        self.parsed_with_raw_with(&crate::UnitDictionary::default())
+    }
+
+    fn parsed_with_raw_with(
+        &self,
+        units: &crate::UnitDictionary,
+    ) -> Vec<(String, crate::ParsedIngredient)> {
+        let parse = |s: &str| (s.to_string(), crate::ParsedIngredient::parse_with(s, units));
+        match self {
+            Self::Structured(v) => v
+                .iter()
+                .filter(|i| !i.text().trim().is_empty())
+                .map(|i| match i {
+                    Ingredient::Text(s) => parse(s.trim()),
+                    Ingredient::Supply(s) => (s.text.trim().to_string(), s.parse_with(units)),
+                })
+                .collect(),
+            _ => self.entries().map(parse).collect(),
         }
     }
 }
@@ -64,20 +595,214 @@ impl IngredientList {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Eq, Serialize)]
 #[serde(untagged)]
 pub enum Instruction {
     Simple(String),
-    Structured { text: String },
+    Structured {
+        text: String,
+        /// Any other properties of the step (`@type`, `position`, ...), kept so that they survive
+        /// being serialized back out
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+impl<'de> Deserialize<'de> for Instruction {
+    /// Structured steps (`HowToStep`, `HowToDirection`, ...) take their text from `text`, falling
+    /// back to `name` when there is none. Objects with an `itemListElement` are sections rather
+    /// than steps, and never fall back to their name
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        match Value::deserialize(deserializer)? {
+            Value::String(s) => Ok(Self::Simple(s)),
+            Value::Object(mut extra) => {
+                let text = match extra.remove("text") {
+                    Some(Value::String(text)) => text,
+                    Some(other) => {
+                        return Err(D::Error::custom(format!("unexpected step text: {}", other)))
+                    }
+                    None if extra.contains_key("itemListElement") => {
+                        return Err(D::Error::missing_field("text"))
+                    }
+                    None => match extra.get("name") {
+                        Some(Value::String(name)) => name.clone(),
+                        _ => return Err(D::Error::missing_field("text")),
+                    },
+                };
+                Ok(Self::Structured { text, extra })
+            }
+            other => Err(D::Error::custom(format!("unexpected step: {}", other))),
+        }
+    }
+}
+
+impl PartialEq for Instruction {
+    /// Steps are compared by their text alone: the extra properties of structured steps are
+    /// carried along for serialization, but don't make otherwise identical steps differ
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Simple(a), Self::Simple(b)) => a == b,
+            (Self::Structured { text: a, .. }, Self::Structured { text: b, .. }) => a == b,
+            _ => false,
+        }
+    }
 }
 
 impl Display for Instruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let text = match self {
+        Display::fmt(self.text(), f)
+    }
+}
+
+/// Steps injected by recipe plugins and page chrome rather than written by the author. A step
+/// is considered boilerplate when, ignoring case, surrounding whitespace and punctuation, it
+/// consists solely of one of these phrases
+pub const BOILERPLATE_STEPS: &[&str] = &[
+    "jump to recipe",
+    "print recipe",
+    "pin recipe",
+    "save recipe",
+    "rate this recipe",
+    "share this recipe",
+    "jump to video",
+    "watch the video",
+];
+
+impl Instruction {
+    /// The text of this step
+    pub fn text(&self) -> &str {
+        match self {
             Self::Simple(s) => s,
-            Self::Structured { text } => text,
+            Self::Structured { text, .. } => text,
+        }
+    }
+
+    /// Apply `f` to the URLs of the step's images (and their thumbnails), and to the URL of the
+    /// step itself
+    fn for_each_url(&mut self, f: &mut impl FnMut(&mut String)) {
+        fn images(image: &mut serde_json::Value, f: &mut impl FnMut(&mut String)) {
+            use serde_json::Value;
+
+            match image {
+                Value::String(url) => f(url),
+                Value::Array(v) => v.iter_mut().for_each(|i| images(i, f)),
+                Value::Object(o) => {
+                    for key in ["url", "contentUrl", "thumbnailUrl"] {
+                        if let Some(Value::String(url)) = o.get_mut(key) {
+                            f(url);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Self::Structured { extra, .. } = self {
+            if let Some(image) = extra.get_mut("image") {
+                images(image, f);
+            }
+            if let Some(serde_json::Value::String(url)) = extra.get_mut("url") {
+                f(url);
+            }
+        }
+    }
+
+    /// A copy of the step with `f` applied to its text and, for structured steps, its `name`
+    pub(crate) fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::Simple(s) => Self::Simple(f(s)),
+            Self::Structured { text, extra } => {
+                let mut extra = extra.clone();
+                if let Some(serde_json::Value::String(name)) = extra.get_mut("name") {
+                    *name = f(name);
+                }
+                Self::Structured {
+                    text: f(text),
+                    extra,
+                }
+            }
+        }
+    }
+
+    /// The URLs of the step's `image`s, in the order they were declared. Images may be given in
+    /// any of the forms accepted for a recipe's [`Image`]
+    pub fn image_urls(&self) -> Vec<&str> {
+        fn urls(image: &serde_json::Value) -> Vec<&str> {
+            use serde_json::Value;
+
+            match image {
+                Value::String(url) => vec![url.as_str()],
+                Value::Array(images) => images.iter().flat_map(urls).collect(),
+                Value::Object(o) => o
+                    .get("url")
+                    .or_else(|| o.get("contentUrl"))
+                    .and_then(Value::as_str)
+                    .into_iter()
+                    .collect(),
+                _ => Vec::new(),
+            }
+        }
+
+        match self {
+            Self::Simple(_) => Vec::new(),
+            Self::Structured { extra, .. } => extra.get("image").map(urls).unwrap_or_default(),
+        }
+    }
+
+    /// The step's declared `position` within its list, if any
+    pub fn position(&self) -> Option<u64> {
+        let position = match self {
+            Self::Simple(_) => None,
+            Self::Structured { extra, .. } => extra.get("position"),
+        };
+        match position? {
+            serde_json::Value::Number(n) => n.as_u64(),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The step's text broken into sentences, e.g. for reading aloud one at a time. Sentences
+    /// end at terminal punctuation followed by whitespace, except after common abbreviations
+    /// ("approx.", "tbsp.", ...) and single letters, so neither "350.5" nor "approx. 10 min."
+    /// is split
+    pub fn sentences(&self) -> Vec<String> {
+        let sentences = crate::text::sentences(self.text());
+        sentences.into_iter().map(str::to_string).collect()
+    }
+
+    /// The lengths of time mentioned in the step's text, such as "simmer for 20 minutes", in
+    /// order, e.g. to offer a timer for each. Ranges ("15-20 minutes") yield their upper bound,
+    /// and compound times ("1 hour 30 minutes") are added together
+    pub fn detected_timers(&self) -> Vec<std::time::Duration> {
+        crate::text::durations(self.text())
+    }
+
+    /// Whether this step is one of the [`BOILERPLATE_STEPS`]
+    pub fn is_boilerplate(&self) -> bool {
+        let text = self
+            .text()
+            .trim_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+            .to_lowercase();
+        BOILERPLATE_STEPS.contains(&text.as_str())
+    }
+
+    /// Whether this "step" is really the heading of a group of steps, such as "For the sauce:",
+    /// as found in flat lists of steps which were sections on the page. Headings are short (a few
+    /// words), end with a colon, and hold no other sentence punctuation
+    pub fn looks_like_header(&self) -> bool {
+        const MAX_WORDS: usize = 6;
+
+        let heading = match self.text().trim().strip_suffix(':') {
+            Some(heading) => heading.trim_end(),
+            None => return false,
         };
-        Display::fmt(text, f)
+        let words = heading.split_whitespace().count();
+        let punctuated = heading.contains(['.', '!', '?', ':', ';']);
+        (1..=MAX_WORDS).contains(&words) && !punctuated
     }
 }
 
@@ -89,18 +814,48 @@ impl Instruction {
 
     pub(crate) fn structured(instruction: impl Into<String>) -> Self {
         let text = instruction.into();
-        Self::Structured { text }
+        let extra = serde_json::Map::new();
+        Self::Structured { text, extra }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Accessors, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Accessors, Deserialize, Serialize)]
+#[serde(tag = "@type", rename = "HowToSection")]
 pub struct InstructionSection {
     #[access(get)]
     name: String,
-    #[serde(rename = "itemListElement")]
+    /// An introduction to the section as a whole, e.g. "In this stage we prep the dough"
+    #[access(get)]
+    #[serde(
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    description: Option<String>,
+    #[serde(rename = "itemListElement", deserialize_with = "positioned_steps")]
     directions: Vec<Instruction>,
 }
 
+/// Deserialize a list of steps, ordering them by their `position` when every step declares a
+/// distinct one. Otherwise, the steps are kept in the order they were listed
+fn positioned_steps<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Instruction>, D::Error> {
+    let mut steps = Vec::<Instruction>::deserialize(deserializer)?;
+    let mut positions = steps
+        .iter()
+        .map(Instruction::position)
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default();
+    positions.sort_unstable();
+    positions.dedup();
+
+    if positions.len() == steps.len() {
+        steps.sort_by_key(|s| s.position());
+    }
+    Ok(steps)
+}
+
 impl IntoIterator for InstructionSection {
     type Item = Instruction;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -118,15 +873,17 @@ impl InstructionSection {
     ) -> Self {
         Self {
             name: name.into(),
+            description: None,
             directions: Vec::from_iter(directions),
         }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum InstructionList {
     Single(Instruction),
+    #[serde(deserialize_with = "positioned_steps")]
     Multi(Vec<Instruction>),
     Sections(Vec<InstructionSection>),
 }
@@ -139,20 +896,168 @@ impl InstructionList {
         }
     }
 
+    /// Every step, in order, as [`steps`](Self::steps) gives them
+    #[deprecated(note = "use steps()")]
     pub fn directions(&self) -> Option<Vec<&Instruction>> {
+        Some(self.steps())
+    }
+
+    pub(crate) fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        let steps = |v: &[Instruction]| v.iter().map(|i| i.map_text(f)).collect();
         match self {
-            Self::Single(i) => Some(vec![i]),
-            Self::Multi(v) => Some(v.iter().collect()),
-            _ => None,
+            Self::Single(i) => Self::Single(i.map_text(f)),
+            Self::Multi(v) => Self::Multi(steps(v)),
+            Self::Sections(v) => Self::Sections(
+                v.iter()
+                    .map(|s| InstructionSection {
+                        name: f(&s.name),
+                        description: s.description.as_deref().map(f),
+                        directions: steps(&s.directions),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// A copy of the list with [boilerplate](Instruction::is_boilerplate) steps (and any sections
+    /// left empty by their removal) dropped, or `None` if no steps remain
+    pub(crate) fn without_boilerplate(&self) -> Option<Self> {
+        let keep = |steps: &[Instruction]| {
+            steps
+                .iter()
+                .filter(|i| !i.is_boilerplate())
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let cleaned = match self {
+            Self::Single(i) if i.is_boilerplate() => return None,
+            Self::Single(i) => Self::Single(i.clone()),
+            Self::Multi(v) => Self::Multi(keep(v)),
+            Self::Sections(v) => Self::Sections(
+                v.iter()
+                    .map(|s| InstructionSection {
+                        directions: keep(&s.directions),
+                        ..s.clone()
+                    })
+                    .filter(|s| !s.directions.is_empty())
+                    .collect(),
+            ),
+        };
+
+        Some(cleaned).filter(|l| !l.is_empty())
+    }
+
+    /// Every step, across all sections, in order
+    pub fn steps(&self) -> Vec<&Instruction> {
+        match self {
+            Self::Single(i) => vec![i],
+            Self::Multi(v) => v.iter().collect(),
+            Self::Sections(v) => v.iter().flat_map(|s| s.directions.iter()).collect(),
+        }
+    }
+
+    /// The list as sections, for rendering every list the same way: sectioned lists are returned
+    /// as-is, while other lists become a single section with an empty name
+    pub fn as_sections(&self) -> Vec<InstructionSection> {
+        match self {
+            Self::Sections(v) => v.clone(),
+            _ => vec![InstructionSection {
+                name: String::new(),
+                description: None,
+                directions: self.steps().into_iter().cloned().collect(),
+            }],
+        }
+    }
+
+    /// The list with sections recovered from a flat list of steps whose headings were kept as
+    /// steps, such as "For the sauce:" (per [`Instruction::looks_like_header`]). Each heading
+    /// starts a section named like [`IngredientList::groups`] names them ("sauce"), and steps
+    /// before the first heading form a section with an empty name. Lists already in sections,
+    /// and flat lists without any headings, are returned unchanged
+    pub fn resectionize(&self) -> InstructionList {
+        let steps = match self {
+            Self::Multi(steps) if steps.iter().any(Instruction::looks_like_header) => steps,
+            _ => return self.clone(),
+        };
+
+        let mut sections = Vec::new();
+        let mut current = InstructionSection {
+            name: String::new(),
+            description: None,
+            directions: Vec::new(),
+        };
+        for step in steps {
+            if !step.looks_like_header() {
+                current.directions.push(step.clone());
+                continue;
+            }
+
+            let text = step.text().trim();
+            let name = crate::ingredient::group_heading(text)
+                .unwrap_or_else(|| text.trim_end_matches(':').trim_end());
+            let next = InstructionSection {
+                name: name.to_string(),
+                description: None,
+                directions: Vec::new(),
+            };
+            let previous = std::mem::replace(&mut current, next);
+            if !previous.name.is_empty() || !previous.directions.is_empty() {
+                sections.push(previous);
+            }
+        }
+        sections.push(current);
+        Self::Sections(sections)
+    }
+
+    /// Every step's text alongside its section's name and its number, for rendering numbered
+    /// steps. With `continuous`, numbers run from 1 across every section; otherwise, each section
+    /// restarts at 1. Lists without sections are treated as a single section with an empty name
+    pub fn numbered(&self, continuous: bool) -> Vec<(String, usize, String)> {
+        let mut numbered = Vec::with_capacity(self.len());
+        for section in self.as_sections() {
+            let start = if continuous { numbered.len() } else { 0 };
+            for (idx, step) in section.directions.iter().enumerate() {
+                let text = step.text().to_string();
+                numbered.push((section.name.clone(), start + idx + 1, text));
+            }
+        }
+        numbered
+    }
+
+    /// Apply `f` to every step's URLs, per [`Instruction::for_each_url`]
+    fn for_each_url(&mut self, f: &mut impl FnMut(&mut String)) {
+        match self {
+            Self::Single(i) => i.for_each_url(f),
+            Self::Multi(v) => v.iter_mut().for_each(|i| i.for_each_url(f)),
+            Self::Sections(v) => v
+                .iter_mut()
+                .flat_map(|s| s.directions.iter_mut())
+                .for_each(|i| i.for_each_url(f)),
+        }
+    }
+
+    /// The number of steps, counted across sections as by [`steps`](Self::steps)
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Single(_) => 1,
+            Self::Multi(v) => v.len(),
+            Self::Sections(v) => v.iter().map(|s| s.directions.len()).sum(),
         }
     }
+
+    /// Whether there are no steps, e.g. for a list of empty sections
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Quantity {
     Number(f64),
     String(String),
+    Value(QuantitativeValue),
 }
 
 impl Default for Quantity {
@@ -166,6 +1071,61 @@ impl Display for Quantity {
         match self {
             Self::Number(x) => Display::fmt(x, f),
             Self::String(s) => Display::fmt(s, f),
+            Self::Value(v) => Display::fmt(v, f),
+        }
+    }
+}
+
+impl Quantity {
+    /// The quantity as a number, as found by [`Yield::value_and_unit`]: free text gives its first
+    /// number wherever it appears (including fractions like "1 1/2" or "½", and the lower bound of
+    /// ranges like "2-3"), so "Serves 4" is 4. Quantitative values yield their value, or else
+    /// their minimum
+    pub fn as_f64(&self) -> Option<f64> {
+        self.value_and_unit().map(|(n, _)| n)
+    }
+
+    /// A copy of the quantity with `f` applied to its free text or unit
+    fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::Number(n) => Self::Number(*n),
+            Self::String(s) => Self::String(f(s)),
+            Self::Value(v) => Self::Value(QuantitativeValue {
+                unit_text: v.unit_text.as_deref().map(f),
+                ..v.clone()
+            }),
+        }
+    }
+
+    /// The quantity multiplied by `factor`. Free text is scaled by its first number (or range),
+    /// wherever it appears, as for [`value_and_unit`](Self::value_and_unit)
+    fn scaled(&self, factor: f64) -> Self {
+        match self {
+            Self::Number(n) => Self::Number(n * factor),
+            Self::String(s) => Self::String(crate::ingredient::scale_first(s, factor)),
+            Self::Value(v) => Self::Value(QuantitativeValue {
+                value: v.value.map(|n| n * factor),
+                min_value: v.min_value.map(|n| n * factor),
+                max_value: v.max_value.map(|n| n * factor),
+                ..v.clone()
+            }),
+        }
+    }
+
+    /// Split the quantity into its first number and the (optional) unit text following it
+    fn value_and_unit(&self) -> Option<(f64, Option<String>)> {
+        match self {
+            Self::Number(n) => Some((*n, None)),
+            Self::String(s) => {
+                let (mut value, rest) = crate::ingredient::find_quantity(s)?;
+                let mut unit = rest.trim();
+                if let Some(rest) = unit.strip_prefix("dozen") {
+                    value *= 12.;
+                    unit = rest.trim();
+                }
+                Some((value, Some(unit.to_string()).filter(|u| !u.is_empty())))
+            }
+            Self::Value(v) => v.value.or(v.min_value).map(|n| (n, v.unit_text.clone())),
         }
     }
 }
@@ -181,361 +1141,6811 @@ impl Quantity {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum Yield {
-    Single(Quantity),
-    Multi(Vec<Quantity>),
+/// Deserialize a number which may have been given as a numeric string, such as `"200"`
+fn number_or_numeric_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(f64),
+        String(String),
+    }
+
+    Ok(match Option::<NumberOrString>::deserialize(deserializer)? {
+        Some(NumberOrString::Number(n)) => Some(n),
+        Some(NumberOrString::String(s)) => s.trim().parse().ok(),
+        None => None,
+    })
 }
 
-impl Display for Yield {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Single(q) => Display::fmt(q, f),
-            Self::Multi(v) => match v.first() {
-                Some(q) => Display::fmt(q, f),
-                None => Display::fmt(&Quantity::default(), f),
-            },
-        }
+/// A [schema.org QuantitativeValue](https://schema.org/QuantitativeValue)
+#[derive(Clone, Debug, PartialEq, Accessors, Deserialize, Serialize)]
+#[access(get)]
+pub struct QuantitativeValue {
+    #[access(get(cp))]
+    #[serde(
+        default,
+        deserialize_with = "number_or_numeric_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    value: Option<f64>,
+    #[access(get(cp))]
+    #[serde(
+        default,
+        rename = "minValue",
+        deserialize_with = "number_or_numeric_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    min_value: Option<f64>,
+    #[access(get(cp))]
+    #[serde(
+        default,
+        rename = "maxValue",
+        deserialize_with = "number_or_numeric_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    max_value: Option<f64>,
+    #[serde(rename = "unitText", skip_serializing_if = "Option::is_none")]
+    unit_text: Option<String>,
+}
+
+impl QuantitativeValue {
+    /// The `(minValue, maxValue)` bounds, when both are given
+    pub fn range(&self) -> Option<(f64, f64)> {
+        Some((self.min_value?, self.max_value?))
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Accessors, Deserialize)]
+/// Values are shown as given, ranges as "4-6", and ranges open at one end as "4+" or "up to 6"
+impl Display for QuantitativeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match (self.value, self.min_value, self.max_value) {
+            (Some(v), _, _) => v.to_string(),
+            (None, Some(min), Some(max)) => format!("{}-{}", min, max),
+            (None, Some(min), None) => format!("{}+", min),
+            (None, None, Some(max)) => format!("up to {}", max),
+            (None, None, None) => String::new(),
+        };
+
+        match (value.as_str(), &self.unit_text) {
+            ("", Some(u)) => Display::fmt(u, f),
+            ("", None) => Display::fmt(&Quantity::default(), f),
+            (v, Some(u)) => write!(f, "{} {}", v, u),
+            (v, None) => Display::fmt(v, f),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Yield {
+    // Multi must be attempted first, as serde will happily deserialize a `QuantitativeValue`
+    // from a sequence of its field values
+    Multi(Vec<Quantity>),
+    Single(Quantity),
+}
+
+impl Display for Yield {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(q) => Display::fmt(q, f),
+            Self::Multi(v) => match v.first() {
+                Some(q) => Display::fmt(q, f),
+                None => Display::fmt(&Quantity::default(), f),
+            },
+        }
+    }
+}
+
+impl Yield {
+    /// Every entry of the yield as displayed, e.g. both "4 servings" and "2 cups" for
+    /// `["4 servings", "2 cups"]`, where [`Display`] only shows the first
+    pub fn all(&self) -> Vec<String> {
+        match self {
+            Self::Single(q) => vec![q.to_string()],
+            Self::Multi(v) => v.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// A copy of the yield with `f` applied to its text, per [`Quantity::map_text`]
+    fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::Single(q) => Self::Single(q.map_text(f)),
+            Self::Multi(v) => Self::Multi(v.iter().map(|q| q.map_text(f)).collect()),
+        }
+    }
+
+    fn scaled(&self, factor: f64) -> Self {
+        match self {
+            Self::Single(q) => Self::Single(q.scaled(factor)),
+            Self::Multi(v) => Self::Multi(v.iter().map(|q| q.scaled(factor)).collect()),
+        }
+    }
+
+    /// Split the yield into its number and unit, e.g. `(4.0, Some("servings"))` for
+    /// "4 servings". A leading "dozen" multiplies the number, so "2 dozen cookies" becomes
+    /// `(24.0, Some("cookies"))`. The first number is used wherever it appears, so yields in any
+    /// language work: "Für 4 Personen" becomes `(4.0, Some("Personen"))`. For lists, the first
+    /// entry containing a number is used
+    pub fn value_and_unit(&self) -> Option<(f64, Option<String>)> {
+        match self {
+            Self::Single(q) => q.value_and_unit(),
+            Self::Multi(v) => v.iter().find_map(Quantity::value_and_unit),
+        }
+    }
+
+    /// The lower and upper bounds of a yield given as a range, e.g. `(4.0, 6.0)` for a
+    /// `QuantitativeValue` with a `minValue` of 4 and a `maxValue` of 6. For lists, the first
+    /// entry declaring a range is used
+    pub fn range(&self) -> Option<(f64, f64)> {
+        let range = |q: &Quantity| match q {
+            Quantity::Value(v) => v.range(),
+            _ => None,
+        };
+
+        match self {
+            Self::Single(q) => range(q),
+            Self::Multi(v) => v.iter().find_map(range),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Accessors, Deserialize, Serialize)]
+#[access(get)]
+pub struct ImageObject {
+    #[serde(alias = "contentUrl")]
+    url: String,
+    #[access(get(cp))]
+    #[serde(
+        default,
+        deserialize_with = "number_or_numeric_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    width: Option<f64>,
+    #[access(get(cp))]
+    #[serde(
+        default,
+        deserialize_with = "number_or_numeric_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    height: Option<f64>,
+    #[access(skip)]
+    #[serde(
+        default,
+        deserialize_with = "image_url",
+        skip_serializing_if = "Option::is_none"
+    )]
+    thumbnail: Option<String>,
+    #[access(skip)]
+    #[serde(
+        rename = "thumbnailUrl",
+        default,
+        deserialize_with = "image_url",
+        skip_serializing_if = "Option::is_none"
+    )]
+    thumbnail_url: Option<String>,
+}
+
+impl ImageObject {
+    /// The URL of a smaller version of the image, declared as a nested `thumbnail` object (or
+    /// URL) or, failing that, as `thumbnailUrl`
+    pub fn thumbnail(&self) -> Option<&str> {
+        self.thumbnail.as_deref().or(self.thumbnail_url.as_deref())
+    }
+}
+
+/// Deserialize a reference to an image, given as a URL or as an object with a `url` (or
+/// `contentUrl`), into its URL. Any other value is treated as absent
+fn image_url<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    use serde_json::Value;
+
+    let url = match Value::deserialize(deserializer)? {
+        Value::String(url) => Some(url),
+        Value::Object(o) => o
+            .get("url")
+            .or_else(|| o.get("contentUrl"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        Value::Array(v) => v.into_iter().find_map(|v| image_url(v).ok().flatten()),
+        _ => None,
+    };
+    Ok(url.filter(|u| !u.trim().is_empty()))
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Image {
+    Url(String),
+    // Multi must be attempted before Object, as serde will happily deserialize an `ImageObject`
+    // from a sequence of its field values
+    Multi(Vec<Image>),
+    Object(ImageObject),
+}
+
+impl Image {
+    /// All image URLs, in the order they were declared
+    pub fn urls(&self) -> Vec<&str> {
+        match self {
+            Self::Url(u) => vec![u.as_str()],
+            Self::Object(o) => vec![o.url.as_str()],
+            Self::Multi(v) => v.iter().flat_map(Image::urls).collect(),
+        }
+    }
+
+    /// The URL of the largest image (by declared width and height) or, when no sizes are
+    /// declared, of the first image
+    pub fn best_url(&self) -> Option<&str> {
+        let images = self.images();
+        let area = |o: &ImageObject| Some(o.width? * o.height?);
+        let largest = images
+            .iter()
+            .filter_map(|(url, object)| Some((*url, area(object.as_ref()?)?)))
+            .fold(None, |best: Option<(&str, f64)>, (url, area)| match best {
+                Some((_, b)) if b >= area => best,
+                _ => Some((url, area)),
+            });
+
+        largest
+            .map(|(url, _)| url)
+            .or_else(|| images.first().map(|(url, _)| *url))
+    }
+
+    /// The URL of the first declared thumbnail (per [`ImageObject::thumbnail`]), falling back to
+    /// the URL of the first image when none declares one
+    pub fn thumbnail_url(&self) -> Option<&str> {
+        let images = self.images();
+        let thumbnail = images
+            .iter()
+            .find_map(|(_, object)| object.as_ref()?.thumbnail());
+        thumbnail.or_else(|| images.first().map(|(url, _)| *url))
+    }
+
+    /// Every image URL, alongside the object it was declared by (if any)
+    fn images(&self) -> Vec<(&str, Option<&ImageObject>)> {
+        match self {
+            Self::Url(u) => vec![(u.as_str(), None)],
+            Self::Object(o) => vec![(o.url.as_str(), Some(o))],
+            Self::Multi(v) => v.iter().flat_map(Image::images).collect(),
+        }
+    }
+
+    /// Apply `f` to every image's URL, and to those of their thumbnails
+    fn for_each_url(&mut self, f: &mut impl FnMut(&mut String)) {
+        match self {
+            Self::Url(u) => f(u),
+            Self::Object(o) => {
+                f(&mut o.url);
+                for thumbnail in [&mut o.thumbnail, &mut o.thumbnail_url] {
+                    if let Some(thumbnail) = thumbnail.as_mut() {
+                        f(thumbnail);
+                    }
+                }
+            }
+            Self::Multi(v) => v.iter_mut().for_each(|i| i.for_each_url(f)),
+        }
+    }
+}
+
+/// Common ways of writing units in a nutrient's `unitCode`, which should hold (but doesn't
+/// always hold) a UN/CEFACT code, alongside the unit they stand for
+const NUTRIENT_UNIT_CODES: &[(&str, &str)] = &[
+    ("E14", "kcal"),
+    ("KCAL", "kcal"),
+    ("GRM", "g"),
+    ("G", "g"),
+    ("MGM", "mg"),
+    ("MG", "mg"),
+];
+
+/// Deserialize a nutrient amount given as text ("200 kcal"), a bare number or a
+/// `QuantitativeValue`-like object (`{"value": "200", "unitCode": "kcal"}`) into text. Objects
+/// take their unit from `unitText`, else `unitCode`, and any other shape is treated as absent
+fn nutrient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    use serde_json::Value;
+
+    let text = match Value::deserialize(deserializer)? {
+        Value::String(s) => s,
+        Value::Number(n) => n.to_string(),
+        Value::Object(o) => {
+            let value = match o.get("value") {
+                Some(Value::String(s)) => s.trim().to_string(),
+                Some(Value::Number(n)) => n.to_string(),
+                _ => return Ok(None),
+            };
+            let code = |c: &str| {
+                NUTRIENT_UNIT_CODES
+                    .iter()
+                    .find(|(code, _)| code.eq_ignore_ascii_case(c.trim()))
+                    .map_or_else(|| c.trim().to_string(), |(_, unit)| unit.to_string())
+            };
+            let unit = match (o.get("unitText"), o.get("unitCode")) {
+                (Some(Value::String(u)), _) => Some(u.trim().to_string()),
+                (_, Some(Value::String(c))) => Some(code(c)),
+                _ => None,
+            };
+            match unit.filter(|u| !u.is_empty()) {
+                Some(unit) => format!("{} {}", value, unit),
+                None => value,
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(text.trim().to_string()).filter(|t| !t.is_empty()))
+}
+
+/// A recipe's [schema.org NutritionInformation](https://schema.org/NutritionInformation), per
+/// serving. Each amount is kept as text including its unit (e.g. "200 kcal" or "5 g"), whether
+/// it was declared as text or as a structured value
+#[derive(Clone, Debug, Default, Eq, PartialEq, Accessors, Deserialize, Serialize)]
+#[access(get)]
+pub struct Nutrition {
+    #[serde(
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    calories: Option<String>,
+    #[serde(
+        rename = "servingSize",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    serving_size: Option<String>,
+    #[serde(
+        rename = "fatContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    fat: Option<String>,
+    #[serde(
+        rename = "saturatedFatContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    saturated_fat: Option<String>,
+    #[serde(
+        rename = "carbohydrateContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    carbohydrate: Option<String>,
+    #[serde(
+        rename = "sugarContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    sugar: Option<String>,
+    #[serde(
+        rename = "fiberContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    fiber: Option<String>,
+    #[serde(
+        rename = "proteinContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    protein: Option<String>,
+    #[serde(
+        rename = "sodiumContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    sodium: Option<String>,
+    #[serde(
+        rename = "cholesterolContent",
+        default,
+        deserialize_with = "nutrient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    cholesterol: Option<String>,
+}
+
+impl Nutrition {
+    /// A copy of the nutrition information with `f` applied to every amount
+    fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        let amount = |a: &Option<String>| a.as_deref().map(f);
+        Self {
+            calories: amount(&self.calories),
+            serving_size: amount(&self.serving_size),
+            fat: amount(&self.fat),
+            saturated_fat: amount(&self.saturated_fat),
+            carbohydrate: amount(&self.carbohydrate),
+            sugar: amount(&self.sugar),
+            fiber: amount(&self.fiber),
+            protein: amount(&self.protein),
+            sodium: amount(&self.sodium),
+            cholesterol: amount(&self.cholesterol),
+        }
+    }
+
+    /// The number of calories, e.g. `200.0` for "200 kcal" or "200 calories"
+    pub fn calories_numeric(&self) -> Option<f64> {
+        let calories = self.calories.as_deref()?.replace(',', "");
+        crate::ingredient::split_quantity(&calories).map(|(n, _)| n)
+    }
+}
+
+/// One or more short text values, such as keywords or categories
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StringList {
+    /// A single string, which may hold several comma-separated values
+    Single(String),
+    Multi(Vec<String>),
+}
+
+impl StringList {
+    pub(crate) fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::Single(s) => Self::Single(f(s)),
+            Self::Multi(v) => Self::Multi(v.iter().map(|s| f(s)).collect()),
+        }
+    }
+
+    /// The individual values, splitting a single string on commas and skipping blank entries
+    pub fn items(&self) -> Vec<&str> {
+        let items: Vec<&str> = match self {
+            Self::Single(s) => s.split(',').collect(),
+            Self::Multi(v) => v.iter().map(String::as_str).collect(),
+        };
+        items
+            .into_iter()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// Turn a `suitableForDiet` value such as `https://schema.org/GlutenFreeDiet` into "gluten free"
+fn diet_name(diet: &str) -> String {
+    let diet = diet.rsplit('/').next().unwrap_or(diet);
+    let diet = diet.strip_suffix("Diet").unwrap_or(diet);
+    let mut name = String::with_capacity(diet.len() + 4);
+    for (idx, c) in diet.char_indices() {
+        if idx > 0 && c.is_uppercase() {
+            name.push(' ');
+        }
+        name.push(c);
+    }
+    name
+}
+
+/// Deserialize a link given either as a plain string or as a `{"@id": ...}` node reference
+fn string_or_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Link {
+        Url(String),
+        Node {
+            #[serde(rename = "@id")]
+            id: String,
+        },
+    }
+
+    Ok(Option::<Link>::deserialize(deserializer)
+        .ok()
+        .flatten()
+        .map(|link| match link {
+            Link::Url(u) | Link::Node { id: u } => u,
+        }))
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Accessors, Deserialize, Serialize)]
+#[access(get)]
+pub struct AuthorDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "string_or_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    url: Option<String>,
+}
+
+/// The author of a recipe: a bare name, a `Person` or `Organization` object, or several of these
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Author {
+    Name(String),
+    Person(AuthorDetails),
+    Organization(AuthorDetails),
+    Multi(Vec<Author>),
+}
+
+impl<'de> Deserialize<'de> for Author {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        let is_organization = |v: &Value| match v {
+            Value::String(s) => s == "Organization",
+            Value::Array(v) => v.iter().any(|t| t == "Organization"),
+            _ => false,
+        };
+
+        match Value::deserialize(deserializer)? {
+            Value::String(s) => Ok(Self::Name(s)),
+            Value::Array(v) => v
+                .into_iter()
+                .map(serde_json::from_value)
+                .collect::<Result<_, _>>()
+                .map(Self::Multi)
+                .map_err(D::Error::custom),
+            Value::Object(o) => {
+                let organization = o.get("@type").map_or(false, is_organization);
+                let details = serde_json::from_value(Value::Object(o)).map_err(D::Error::custom)?;
+                Ok(if organization {
+                    Self::Organization(details)
+                } else {
+                    Self::Person(details)
+                })
+            }
+            other => Err(D::Error::custom(format!("unexpected author: {}", other))),
+        }
+    }
+}
+
+impl Serialize for Author {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let (kind, details) = match self {
+            Self::Name(name) => return serializer.serialize_str(name),
+            Self::Multi(authors) => return serializer.collect_seq(authors),
+            Self::Person(details) => ("Person", details),
+            Self::Organization(details) => ("Organization", details),
+        };
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("@type", kind)?;
+        if let Some(name) = &details.name {
+            map.serialize_entry("name", name)?;
+        }
+        if let Some(url) = &details.url {
+            map.serialize_entry("url", url)?;
+        }
+        map.end()
+    }
+}
+
+impl Author {
+    /// The first author's homepage or profile URL, if one is declared
+    pub fn first_url(&self) -> Option<&str> {
+        match self {
+            Self::Name(_) => None,
+            Self::Person(d) | Self::Organization(d) => d.url.as_deref(),
+            Self::Multi(v) => v.iter().find_map(Author::first_url),
+        }
+    }
+
+    /// Apply `f` to every author's URL
+    fn for_each_url(&mut self, f: &mut impl FnMut(&mut String)) {
+        match self {
+            Self::Name(_) => {}
+            Self::Person(d) | Self::Organization(d) => {
+                if let Some(url) = d.url.as_mut() {
+                    f(url);
+                }
+            }
+            Self::Multi(v) => v.iter_mut().for_each(|a| a.for_each_url(f)),
+        }
+    }
+
+    /// A copy of the author with `f` applied to every name
+    fn map_text(&self, f: &impl Fn(&str) -> String) -> Self {
+        let details = |d: &AuthorDetails| AuthorDetails {
+            name: d.name.as_deref().map(f),
+            ..d.clone()
+        };
+        match self {
+            Self::Name(name) => Self::Name(f(name)),
+            Self::Person(d) => Self::Person(details(d)),
+            Self::Organization(d) => Self::Organization(details(d)),
+            Self::Multi(v) => Self::Multi(v.iter().map(|a| a.map_text(f)).collect()),
+        }
+    }
+}
+
+/// The properties recipe plugins declare notes under, in order of preference. None is part of
+/// schema.org, so the first is treated as canonical. schema.org's `comment` is deliberately not
+/// among them, as it holds readers' comments rather than the cook's notes
+const NOTE_KEYS: &[&str] = &["recipeNotes", "recipeNote", "notes", "note"];
+
+/// Deserialize notes given as a string, a schema.org `Comment` (or other object with a `text`),
+/// or a list of either, skipping blank notes and any other values
+fn notes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    use serde_json::Value;
+
+    fn texts(value: &Value) -> Vec<&str> {
+        match value {
+            Value::String(s) => vec![s.as_str()],
+            Value::Object(o) => o.get("text").and_then(Value::as_str).into_iter().collect(),
+            Value::Array(v) => v.iter().flat_map(texts).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    let value = Value::deserialize(deserializer)?;
+    let notes = texts(&value).into_iter().map(str::trim);
+    Ok(notes
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Deserialize a boolean which may have been given as the string `"true"` or `"false"`
+fn bool_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    use serde_json::Value;
+
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Bool(b) => Some(b),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Deserialize an optional field, treating a value of an unexpected shape as absent rather than
+/// failing the whole recipe
+fn tolerant<'de, D: Deserializer<'de>, T: serde::de::DeserializeOwned>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Replace `url` with its absolute form, leaving it untouched when `base` can't be used
+fn resolve_url(url: &mut String, base: &str) {
+    if let Some(resolved) = crate::links::resolve(base, url) {
+        *url = resolved;
+    }
+}
+
+// The derived implementations are exposed as the inherent `Recipe::deserialize` and
+// `Recipe::serialize`, which the `Deserialize` impl below wraps in order to normalize commonly
+// observed non-standard keys
+#[derive(Clone, Debug, PartialEq, Accessors, Deserialize, Serialize)]
+#[serde(remote = "Self")]
 #[access(get)]
 pub struct Recipe {
     name: String,
     description: String,
-    #[serde(rename = "cookTime")]
+    #[serde(rename = "cookTime", skip_serializing_if = "Option::is_none")]
     cook_time: Option<MaybeDuration>,
-    #[serde(rename = "prepTime")]
+    #[serde(rename = "prepTime", skip_serializing_if = "Option::is_none")]
     prep_time: Option<MaybeDuration>,
-    #[serde(rename = "totalTime")]
+    #[serde(rename = "totalTime", skip_serializing_if = "Option::is_none")]
     total_time: Option<MaybeDuration>,
-    #[serde(rename = "recipeYield")]
+    #[serde(
+        default,
+        rename = "recipeYield",
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
     yields: Option<Yield>,
     #[serde(rename = "recipeIngredient")]
     ingredients: IngredientList,
-    #[serde(rename = "recipeInstructions")]
+    #[serde(rename = "recipeInstructions", skip_serializing_if = "Option::is_none")]
     directions: Option<InstructionList>,
+    #[serde(
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    image: Option<Image>,
+    #[serde(
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    url: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    author: Option<Author>,
+    #[serde(
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    keywords: Option<StringList>,
+    #[serde(
+        default,
+        rename = "recipeCategory",
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    category: Option<StringList>,
+    #[serde(
+        default,
+        rename = "recipeCuisine",
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    cuisine: Option<StringList>,
+    #[serde(
+        rename = "suitableForDiet",
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    diet: Option<StringList>,
+    #[access(get(cp))]
+    #[serde(
+        rename = "isAccessibleForFree",
+        default,
+        deserialize_with = "bool_or_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    accessible_for_free: Option<bool>,
+    #[serde(
+        rename = "cookingTechnique",
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    cooking_technique: Option<StringList>,
+    /// Free-text notes from the cook, such as substitutions or storage tips, declared under
+    /// one of the [`NOTE_KEYS`]
+    #[serde(
+        rename = "recipeNotes",
+        default,
+        deserialize_with = "notes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    notes: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    nutrition: Option<Nutrition>,
+    #[access(skip)]
+    #[serde(rename = "mainEntityOfPage", skip_serializing_if = "Option::is_none")]
+    main_entity_of_page: Option<serde_json::Value>,
+    /// The node identifier (`@id`) declared by the recipe, e.g. for recognizing copies of the
+    /// same recipe in several parts of a document
+    #[serde(
+        rename = "@id",
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    id: Option<String>,
+    #[access(skip)]
+    #[serde(
+        rename = "@type",
+        default,
+        deserialize_with = "tolerant",
+        skip_serializing_if = "Option::is_none"
+    )]
+    types: Option<StringList>,
+    /// The name of the site the recipe was scraped from, when known, as detected from the page's
+    /// `og:site_name` or `<title>`
+    #[serde(skip)]
+    site_name: Option<String>,
 }
 
-#[cfg(test)]
-impl Recipe {
-    pub(crate) fn new(
-        name: impl Into<String>,
-        description: impl Into<String>,
-        ingredients: IngredientList,
-    ) -> Self {
-        Self {
-            name: name.into(),
-            description: description.into(),
-            ingredients,
-            cook_time: None,
-            prep_time: None,
-            total_time: None,
-            yields: None,
-            directions: None,
+/// Separators commonly placed between a page's title and the name of its site
+const NAME_SEPARATORS: &[&str] = &[" | ", " - ", " – ", " — ", " :: ", " « ", " » "];
+
+/// Strip a trailing separator followed by `site_name` (ignoring case) from `name`
+fn strip_site_suffix<'a>(name: &'a str, site_name: &str) -> Option<&'a str> {
+    let site_name = site_name.trim();
+    NAME_SEPARATORS.iter().find_map(|sep| {
+        let idx = name.len().checked_sub(sep.len() + site_name.len())?;
+        let (head, suffix) = (name.get(..idx)?, name.get(idx..)?);
+        let matches =
+            suffix.starts_with(sep) && suffix[sep.len()..].eq_ignore_ascii_case(site_name);
+        matches.then(|| head.trim_end())
+    })
+}
+
+/// The site name declared by a document's `og:site_name`, falling back to the last
+/// separator-delimited part of its `<title>`
+fn site_name(html: &scraper::Html) -> Option<String> {
+    let select = |selector: &str| {
+        // Unwrap is appropriate here as these are static selectors
+        let selector = scraper::Selector::parse(selector).unwrap();
+        html.select(&selector).next()
+    };
+
+    let og = select(r#"meta[property="og:site_name"]"#)
+        .and_then(|el| el.value().attr("content"))
+        .map(str::to_string);
+    let title = || {
+        let title = select("title")?.text().collect::<String>();
+        let (_, site) = NAME_SEPARATORS
+            .iter()
+            .filter_map(|sep| title.rsplit_once(sep))
+            .max_by_key(|(head, _)| head.len())?;
+        Some(site.to_string())
+    };
+
+    og.or_else(title)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Non-standard property names seen in the wild, alongside the schema.org property they stand
+/// in for. `performTime` is borrowed from schema.org's `HowTo`, and `ingredients` is the
+/// (superseded) schema.org property `recipeIngredient` replaced
+const ALTERNATIVE_KEYS: &[(&str, &str)] = &[
+    ("steps", "recipeInstructions"),
+    ("performTime", "cookTime"),
+    ("ingredients", "recipeIngredient"),
+];
+
+/// The keys under which ingredients given as an object, rather than a list, hold their list
+const NESTED_INGREDIENT_KEYS: &[&str] = &["recipeIngredient", "ingredients", "itemListElement"];
+
+/// Non-standard properties under which some recipe plugins give each ingredient's amount, in a
+/// list running parallel to `recipeIngredient`'s names
+const INGREDIENT_AMOUNT_KEYS: &[&str] =
+    &["amounts", "ingredientAmounts", "recipeIngredientAmounts"];
+
+/// The properties a recipe must declare, which are emptied rather than dropped when blank
+const REQUIRED_KEYS: &[&str] = &["name", "description", "recipeIngredient"];
+
+/// Rewrite non-standard spellings of recipe properties into their schema.org form, in place.
+/// Canonical properties always take precedence over their alternatives
+///
+/// JSON-LD value objects (`{"@value": "Cake", "@language": "en"}`) are replaced by their value
+/// throughout. Blank strings are how many templates render a missing value (`"cookTime": ""`), so
+/// optional properties holding only whitespace are dropped, and required ones are emptied
+///
+/// Directions given as an object rather than a list are unwrapped where they hold a list of
+/// steps: an `ItemList`'s `itemListElement`, a `steps` key, or steps keyed by number. Likewise,
+/// ingredients wrapped in an object are unwrapped from any of the [`NESTED_INGREDIENT_KEYS`], and
+/// ingredient names with a parallel list of amounts (under one of the [`INGREDIENT_AMOUNT_KEYS`])
+/// are joined up with them
+fn normalize_recipe_keys(recipe: &mut serde_json::Map<String, serde_json::Value>) {
+    use serde_json::Value;
+
+    recipe
+        .iter_mut()
+        .filter(|(k, _)| !k.starts_with('@'))
+        .for_each(|(_, v)| unwrap_value_objects(v));
+
+    let is_blank = |v: &Value| v.as_str().map_or(false, |s| s.trim().is_empty());
+    recipe.retain(|k, v| !is_blank(v) || REQUIRED_KEYS.contains(&k.as_str()));
+    for key in REQUIRED_KEYS {
+        if let Some(value) = recipe.get_mut(*key).filter(|v| is_blank(v)) {
+            *value = Value::String(String::new());
         }
     }
 
-    pub(crate) fn with_directions(self, directions: InstructionList) -> Self {
-        Self {
-            directions: Some(directions),
-            ..self
+    let alternative_notes = NOTE_KEYS[1..].iter().map(|k| (*k, NOTE_KEYS[0]));
+    let alternatives = ALTERNATIVE_KEYS.iter().copied().chain(alternative_notes);
+    for (alternative, canonical) in alternatives {
+        if !recipe.contains_key(canonical) {
+            if let Some(value) = recipe.remove(alternative) {
+                recipe.insert(canonical.to_string(), value);
+            }
+        }
+    }
+
+    if let Some(Value::Object(ingredients)) = recipe.get_mut("recipeIngredient") {
+        let nested = NESTED_INGREDIENT_KEYS
+            .iter()
+            .find_map(|k| ingredients.remove(*k));
+        if let Some(nested) = nested {
+            recipe.insert("recipeIngredient".into(), nested);
+        }
+    }
+
+    if let Some(amounts) = INGREDIENT_AMOUNT_KEYS
+        .iter()
+        .find_map(|k| recipe.remove(*k))
+    {
+        if let Some(joined) = recipe
+            .get("recipeIngredient")
+            .and_then(|names| with_amounts(names, &amounts))
+        {
+            recipe.insert("recipeIngredient".into(), joined);
+        }
+    }
+
+    if let Some(Value::Object(instructions)) = recipe.get_mut("recipeInstructions") {
+        let structured = ["text", "itemListElement"]
+            .iter()
+            .any(|k| instructions.contains_key(*k));
+        let item_list = instructions.get("@type").map_or(false, |t| t == "ItemList");
+        if item_list && !instructions.contains_key("text") {
+            if let Some(steps) = instructions.remove("itemListElement") {
+                recipe.insert("recipeInstructions".into(), steps);
+            }
+        } else if !structured {
+            if let Some(steps) = instructions.remove("steps") {
+                recipe.insert("recipeInstructions".into(), steps);
+            } else if let Some(steps) = numbered_steps(instructions) {
+                recipe.insert("recipeInstructions".into(), steps);
+            }
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum GraphEntry {
-    Recipe(Box<Recipe>),
-    Nonsense {
-        #[serde(rename = "@id")]
-        id: String,
-    },
+/// Replace every JSON-LD value object within `value` (an object holding `@value` alongside only
+/// other keywords, such as `@language` or `@type`) with its plain value
+fn unwrap_value_objects(value: &mut serde_json::Value) {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(o) if o.contains_key("@value") && o.keys().all(|k| k.starts_with('@')) => {
+            *value = o.remove("@value").unwrap_or_default();
+        }
+        Value::Object(o) => o.values_mut().for_each(unwrap_value_objects),
+        Value::Array(v) => v.iter_mut().for_each(unwrap_value_objects),
+        _ => {}
+    }
 }
 
-impl GraphEntry {
-    pub fn recipe(&self) -> Option<&Recipe> {
-        match self {
-            Self::Recipe(r) => Some(r.as_ref()),
-            Self::Nonsense { .. } => None,
+/// Ingredient `names` prefixed by their corresponding `amounts` (e.g. `["2 cups"]` and
+/// `["flour"]` make `["2 cups flour"]`). `None` unless both are lists of the same length, with
+/// names as strings and amounts as strings, numbers or nulls (for ingredients without an amount)
+fn with_amounts(
+    names: &serde_json::Value,
+    amounts: &serde_json::Value,
+) -> Option<serde_json::Value> {
+    use serde_json::Value;
+
+    let (names, amounts) = (names.as_array()?, amounts.as_array()?);
+    if names.len() != amounts.len() {
+        return None;
+    }
+    names
+        .iter()
+        .zip(amounts)
+        .map(|(name, amount)| {
+            let name = name.as_str()?.trim();
+            let amount = match amount {
+                Value::String(s) => s.trim().to_string(),
+                Value::Number(n) => n.to_string(),
+                Value::Null => String::new(),
+                _ => return None,
+            };
+            let line = if amount.is_empty() {
+                name.to_string()
+            } else {
+                format!("{} {}", amount, name)
+            };
+            Some(Value::String(line))
+        })
+        .collect()
+}
+
+/// Steps given as an object keyed by step number, e.g. `{"1": "Mix", "2": "Bake"}`, as a list
+/// ordered by those numbers. `None` unless every key is a number and every value a string
+fn numbered_steps(
+    instructions: &serde_json::Map<String, serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let mut steps = instructions
+        .iter()
+        .map(|(k, v)| Some((k.trim().parse::<u64>().ok()?, v.as_str()?)))
+        .collect::<Option<Vec<_>>>()
+        .filter(|steps| !steps.is_empty())?;
+    steps.sort_by_key(|&(n, _)| n);
+    Some(steps.into_iter().map(|(_, step)| step).collect())
+}
+
+impl<'de> Deserialize<'de> for Recipe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        if let serde_json::Value::Object(recipe) = &mut value {
+            normalize_recipe_keys(recipe);
         }
+        Recipe::deserialize(value).map_err(D::Error::custom)
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum SchemaItem {
-    Recipe(Box<Recipe>),
-    Nonsense {
-        #[serde(rename = "@context")]
-        context: String,
-    },
+impl Serialize for Recipe {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Recipe::serialize(self, serializer)
+    }
+}
+
+/// A [`Recipe`] deserialized as tolerantly as possible, so that one malformed property (such as
+/// an unexpected instruction shape) doesn't sink the whole recipe. Any object deserializes:
+/// properties which don't parse are dropped (as though absent), and missing or malformed
+/// required properties (`name`, `description` and `recipeIngredient`) are left empty
+///
+/// ```
+/// use recipe_scraper::TolerantRecipe;
+///
+/// let json = r#"{"name": "Toast", "recipeIngredient": "bread", "recipeInstructions": 5}"#;
+/// let recipe = serde_json::from_str::<TolerantRecipe>(json).unwrap().into_recipe();
+/// assert_eq!("Toast", recipe.name());
+/// assert!(recipe.directions().is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TolerantRecipe(Recipe);
+
+impl TolerantRecipe {
+    pub fn recipe(&self) -> &Recipe {
+        &self.0
+    }
+
+    pub fn into_recipe(self) -> Recipe {
+        self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for TolerantRecipe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        let mut recipe = match Value::deserialize(deserializer)? {
+            Value::Object(recipe) => recipe,
+            other => return Err(D::Error::custom(format!("unexpected recipe: {}", other))),
+        };
+        normalize_recipe_keys(&mut recipe);
+        let parses = |recipe: &serde_json::Map<String, Value>| {
+            serde_json::from_value::<Recipe>(Value::Object(recipe.clone()))
+        };
+        if let Ok(parsed) = parses(&recipe) {
+            return Ok(Self(parsed));
+        }
+
+        // Try each property alone, alongside empty required ones, to find those which don't parse
+        let empty = REQUIRED_KEYS
+            .iter()
+            .map(|k| (k.to_string(), Value::String(String::new())))
+            .collect::<serde_json::Map<_, _>>();
+        recipe.retain(|key, value| {
+            let mut probe = empty.clone();
+            probe.insert(key.clone(), value.clone());
+            parses(&probe).is_ok()
+        });
+        for key in REQUIRED_KEYS {
+            recipe
+                .entry(key.to_string())
+                .or_insert_with(|| Value::String(String::new()));
+        }
+        parses(&recipe).map(Self).map_err(D::Error::custom)
+    }
 }
 
-impl SchemaItem {
-    pub fn recipe(&self) -> Option<&Recipe> {
-        match self {
-            Self::Recipe(r) => Some(r.as_ref()),
-            Self::Nonsense { .. } => None,
-        }
+/// A rough label for how demanding a recipe is, as computed by [`Recipe::difficulty`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Recipes with at most this many steps score as easy on step count
+    pub const EASY_MAX_STEPS: usize = 5;
+    /// Recipes with more than this many steps score as hard on step count
+    pub const HARD_ABOVE_STEPS: usize = 12;
+    /// Recipes with at most this many ingredients score as easy on ingredient count
+    pub const EASY_MAX_INGREDIENTS: usize = 7;
+    /// Recipes with more than this many ingredients score as hard on ingredient count
+    pub const HARD_ABOVE_INGREDIENTS: usize = 15;
+    /// Recipes taking at most this many minutes in total score as easy on time
+    pub const EASY_MAX_MINUTES: u64 = 30;
+    /// Recipes taking more than this many minutes in total score as hard on time
+    pub const HARD_ABOVE_MINUTES: u64 = 120;
+}
+
+/// The main ingredients [`Recipe::estimated_servings`] bases its estimate on, by the grams of
+/// each making up a typical serving. The first kind the recipe has any weighed amount of is used
+const SERVING_WEIGHTS: &[(&[&str], f64)] = &[
+    (
+        &[
+            "beef", "pork", "lamb", "chicken", "turkey", "duck", "veal", "mince", "sausage",
+            "steak", "fish", "salmon", "cod", "tuna", "shrimp", "prawn", "tofu", "tempeh",
+        ],
+        150.,
+    ),
+    (
+        &[
+            "pasta",
+            "spaghetti",
+            "noodle",
+            "rice",
+            "quinoa",
+            "couscous",
+            "lentil",
+            "bean",
+            "chickpea",
+            "potato",
+        ],
+        100.,
+    ),
+];
+
+/// Weight units understood by [`Recipe::estimated_servings`], in grams
+const GRAMS_PER_UNIT: &[(&str, f64)] = &[("g", 1.), ("kg", 1000.), ("oz", 28.35), ("lb", 453.6)];
+
+impl Display for Recipe {
+    /// A short, multi-line summary of the recipe: its name, yield, times and the number of
+    /// ingredients and steps
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        if let Some(yields) = &self.yields {
+            writeln!(f, "Yield: {}", yields)?;
+        }
+
+        let times = [
+            ("Prep time", &self.prep_time),
+            ("Cook time", &self.cook_time),
+            ("Total time", &self.total_time),
+        ];
+        for (label, time) in times {
+            if let Some(time) = time.as_ref().and_then(MaybeDuration::human_readable) {
+                writeln!(f, "{}: {}", label, time)?;
+            }
+        }
+
+        writeln!(f, "Ingredients: {}", self.ingredients.entries().count())?;
+        let steps = self.directions.as_ref().map_or(0, InstructionList::len);
+        write!(f, "Steps: {}", steps)
+    }
+}
+
+impl Recipe {
+    /// The time allowed for each step by [`total_time_or_estimate`](Self::total_time_or_estimate),
+    /// in minutes, when a recipe declares no times
+    pub const ESTIMATED_MINUTES_PER_STEP: u64 = 5;
+
+    /// The first recipe marked up with [microdata](https://schema.org/docs/gs.html) (an element
+    /// with `itemscope` and an `itemtype` of schema.org's Recipe) in `html`, or `None` when there
+    /// is none. Only microdata is searched, unlike [`Scraper`](crate::Scraper) with
+    /// [`Format::Microdata`](crate::Format::Microdata)
+    pub fn from_microdata_html(html: impl AsRef<str>) -> Option<Recipe> {
+        let document = scraper::Html::parse_document(html.as_ref());
+        crate::microdata::scrape(&document).into_iter().next()
+    }
+
+    /// The recipe's declared `@type`s, exactly as given, e.g. `["Recipe", "NewsArticle"]` for a
+    /// dual-typed recipe. Empty when the recipe declared no type
+    pub fn types(&self) -> Vec<String> {
+        match &self.types {
+            Some(StringList::Single(t)) => vec![t.clone()],
+            Some(StringList::Multi(types)) => types.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether the recipe declares itself the main entity of the page it was found on
+    pub fn is_main_entity_of_page(&self) -> bool {
+        use serde_json::Value;
+        !matches!(
+            self.main_entity_of_page,
+            None | Some(Value::Null) | Some(Value::Bool(false))
+        )
+    }
+
+    /// The fraction (from `0.0` to `1.0`) of the name, description, ingredients, directions,
+    /// yield, cook/prep/total times, image and URL which are populated
+    pub fn completeness(&self) -> f32 {
+        let has_time = |t: &Option<MaybeDuration>| t.as_ref().map_or(false, |t| t.0.is_some());
+        let populated = [
+            !self.name.trim().is_empty(),
+            !self.description.trim().is_empty(),
+            self.ingredients.entries().next().is_some(),
+            self.directions.is_some(),
+            self.yields.is_some(),
+            has_time(&self.cook_time),
+            has_time(&self.prep_time),
+            has_time(&self.total_time),
+            self.image.is_some(),
+            self.url.is_some(),
+        ];
+
+        let count = populated.iter().filter(|p| **p).count();
+        count as f32 / populated.len() as f32
+    }
+
+    /// Apply `f` to every URL of the recipe: those of its images (and their thumbnails), of the
+    /// recipe itself and the page it's the main entity of, of its authors, and of its steps and
+    /// their images
+    fn for_each_url(&mut self, f: &mut impl FnMut(&mut String)) {
+        use serde_json::Value;
+
+        if let Some(image) = self.image.as_mut() {
+            image.for_each_url(f);
+        }
+        if let Some(url) = self.url.as_mut() {
+            f(url);
+        }
+        match self.main_entity_of_page.as_mut() {
+            Some(Value::String(url)) => f(url),
+            Some(Value::Object(page)) => {
+                for key in ["@id", "url"] {
+                    if let Some(Value::String(url)) = page.get_mut(key) {
+                        f(url);
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(author) = self.author.as_mut() {
+            author.for_each_url(f);
+        }
+        if let Some(directions) = self.directions.as_mut() {
+            directions.for_each_url(f);
+        }
+    }
+
+    /// Resolve the recipe's relative URLs (per [`for_each_url`](Self::for_each_url)) against
+    /// `base`
+    pub(crate) fn resolve_urls(&mut self, base: &str) {
+        self.for_each_url(&mut |url| resolve_url(url, base));
+    }
+
+    /// A copy of the recipe with every relative URL resolved against `base`: those of its images
+    /// (and their thumbnails), of the recipe itself and its page, of its authors, and of its
+    /// steps and their images. URLs which are already absolute are kept as they are, as is the
+    /// whole recipe when `base` isn't an absolute URL (such as "https://example.com/recipes/")
+    pub fn with_base_url(&self, base: &str) -> Recipe {
+        let mut recipe = self.clone();
+        recipe.resolve_urls(base);
+        recipe
+    }
+
+    /// The name with any trailing site name (e.g. "Best Chocolate Cake - My Food Blog") removed.
+    /// When the site name is known, as it is for recipes scraped from HTML declaring one, a
+    /// suffix matching it is stripped after any common separator. Otherwise only a suffix
+    /// following " | " is treated as a site name. The raw [`name`](Self::name) is unchanged
+    pub fn clean_name(&self) -> String {
+        match &self.site_name {
+            Some(site_name) => self.clean_name_for_site(site_name),
+            None => {
+                let name = self.name.trim();
+                let head = name.rsplit_once(" | ").map(|(head, _)| head.trim_end());
+                head.filter(|h| !h.is_empty()).unwrap_or(name).to_string()
+            }
+        }
+    }
+
+    /// The name with a trailing `site_name` suffix, following any common separator, removed
+    pub fn clean_name_for_site(&self, site_name: &str) -> String {
+        let name = self.name.trim();
+        strip_site_suffix(name, site_name)
+            .filter(|h| !h.is_empty())
+            .unwrap_or(name)
+            .to_string()
+    }
+
+    /// A plain JSON form of the recipe for APIs, free of JSON-LD conventions. Its shape is:
+    ///
+    /// ```json
+    /// {
+    ///   "name": "Pancakes",
+    ///   "description": "Fluffy pancakes",
+    ///   "ingredients": ["1 cup flour", "2 eggs"],
+    ///   "steps": ["Mix", "Fry"],
+    ///   "prep_time_minutes": 10,
+    ///   "cook_time_minutes": 15,
+    ///   "total_time_minutes": 25,
+    ///   "yield": "4 servings",
+    ///   "image": "https://example.com/pancakes.jpg",
+    ///   "url": "https://example.com/pancakes",
+    ///   "tags": ["breakfast"]
+    /// }
+    /// ```
+    ///
+    /// Every key is always present: unknown values are `null` (or empty lists), steps are
+    /// flattened across sections and `image` is the first image URL
+    pub fn to_api_json(&self) -> serde_json::Value {
+        let steps = self.directions.as_ref().map(InstructionList::steps);
+        let image = self.image.as_ref().and_then(|i| i.urls().first().copied());
+        serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "ingredients": self.ingredients.entries().collect::<Vec<_>>(),
+            "steps": steps.unwrap_or_default().iter().map(|s| s.text()).collect::<Vec<_>>(),
+            "prep_time_minutes": self.prep_minutes(),
+            "cook_time_minutes": self.cook_minutes(),
+            "total_time_minutes": self.total_minutes(),
+            "yield": self.yields.as_ref().map(ToString::to_string),
+            "image": image,
+            "url": self.url,
+            "tags": self.all_tags(),
+        })
+    }
+
+    /// The recipe as a schema.org JSON-LD node, declaring its `@context` (and `@type`, when the
+    /// recipe didn't declare its own)
+    fn to_json_ld(&self) -> serde_json::Value {
+        // Unwrap is appropriate here as recipes have no maps with non-string keys, the only
+        // thing which could fail to serialize
+        let mut value = serde_json::to_value(self).unwrap();
+        if let serde_json::Value::Object(node) = &mut value {
+            node.insert("@context".into(), "https://schema.org".into());
+            node.entry("@type").or_insert_with(|| "Recipe".into());
+        }
+        value
+    }
+
+    /// The recipe as a schema.org JSON-LD value, as serialized for
+    /// [`to_json_ld_string_compact`](Self::to_json_ld_string_compact), e.g. for remapping it
+    /// into another crate's recipe model with [`serde_json::from_value`]
+    ///
+    /// ```
+    /// use recipe_scraper::{Extract, SchemaOrgEntry};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct MyRecipe {
+    ///     #[serde(rename = "name")]
+    ///     title: String,
+    ///     #[serde(rename = "recipeIngredient")]
+    ///     ingredients: Vec<String>,
+    ///     #[serde(rename = "totalTime")]
+    ///     total_time: Option<String>,
+    /// }
+    ///
+    /// let json = r#"{"name": "Toast", "description": "", "recipeIngredient": ["bread"],
+    ///     "totalTime": "PT5M"}"#;
+    /// let recipe = SchemaOrgEntry::from_json_str(json).unwrap().extract_recipes().remove(0);
+    /// let mine: MyRecipe = serde_json::from_value(recipe.into_value()).unwrap();
+    /// assert_eq!("Toast", mine.title);
+    /// assert_eq!(vec!["bread"], mine.ingredients);
+    /// assert_eq!(Some("PT5M"), mine.total_time.as_deref());
+    /// ```
+    pub fn into_value(self) -> serde_json::Value {
+        self.to_json_ld()
+    }
+
+    /// The recipe as a single line of schema.org JSON-LD, e.g. for embedding in a
+    /// `<script type="application/ld+json">` block of minified HTML
+    pub fn to_json_ld_string_compact(&self) -> String {
+        self.to_json_ld().to_string()
+    }
+
+    /// Like [`to_json_ld_string_compact`](Self::to_json_ld_string_compact), but indented over
+    /// several lines for readability
+    pub fn to_json_ld_string_pretty(&self) -> String {
+        // Unwrap is appropriate here as serializing a `Value` can't fail
+        serde_json::to_string_pretty(&self.to_json_ld()).unwrap()
+    }
+
+    /// The recipe as a fragment of semantic HTML: an `<article>` holding the name as an `<h1>`,
+    /// the description, a `<section>` listing the ingredients and another listing the steps in
+    /// an `<ol>` (per instruction section, each headed by an `<h3>`), followed by the recipe's
+    /// [JSON-LD](Self::to_json_ld_string_compact) in a `<script type="application/ld+json">`
+    /// block. All text is escaped, so markup within the recipe is shown rather than rendered
+    pub fn to_html(&self) -> String {
+        use crate::text::escape;
+
+        let mut html = String::from("<article class=\"recipe\">\n");
+        html.push_str(&format!("<h1>{}</h1>\n", escape(self.name.trim())));
+        if !self.description.trim().is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", escape(self.description.trim())));
+        }
+
+        html.push_str("<section class=\"ingredients\">\n<h2>Ingredients</h2>\n<ul>\n");
+        for ingredient in self.ingredients.entries() {
+            html.push_str(&format!("<li>{}</li>\n", escape(ingredient)));
+        }
+        html.push_str("</ul>\n</section>\n");
+
+        if let Some(directions) = &self.directions {
+            html.push_str("<section class=\"instructions\">\n<h2>Instructions</h2>\n");
+            for section in directions.as_sections() {
+                if !section.name.trim().is_empty() {
+                    html.push_str(&format!("<h3>{}</h3>\n", escape(section.name.trim())));
+                }
+                html.push_str("<ol>\n");
+                for step in &section.directions {
+                    html.push_str(&format!("<li>{}</li>\n", escape(step.text().trim())));
+                }
+                html.push_str("</ol>\n");
+            }
+            html.push_str("</section>\n");
+        }
+
+        // `<` only ever appears within JSON strings, where escaping it keeps "</script>" in the
+        // recipe's text from closing the block early
+        let json_ld = self.to_json_ld_string_compact().replace('<', "\\u003c");
+        html.push_str(&format!(
+            "<script type=\"application/ld+json\">{}</script>\n",
+            json_ld
+        ));
+        html.push_str("</article>\n");
+        html
+    }
+
+    /// An approximation of the recipe in [cooklang](https://cooklang.org) format: `>>` metadata
+    /// lines for the title, source URL and servings, then a paragraph listing every ingredient as
+    /// `@name{quantity%unit}` (per [`ParsedIngredient::parse`]), then each step as its own
+    /// paragraph of plain text, with sections introduced by `== name ==` lines
+    ///
+    /// Steps don't reference their ingredients inline as idiomatic cooklang would, so the output
+    /// is a starting point for hand-editing rather than a faithful conversion
+    ///
+    /// [`ParsedIngredient::parse`]: crate::ParsedIngredient::parse
+    pub fn to_cooklang(&self) -> String {
+        let mut lines = vec![format!(">> title: {}", self.name.trim())];
+        if let Some(url) = &self.url {
+            lines.push(format!(">> source: {}", url));
+        }
+        if let Some(yields) = &self.yields {
+            lines.push(format!(">> servings: {}", yields));
+        }
+
+        let ingredients = self.ingredients.parse();
+        if !ingredients.is_empty() {
+            lines.push(String::new());
+            lines.extend(ingredients.iter().map(crate::ParsedIngredient::to_cooklang));
+        }
+
+        let step = |lines: &mut Vec<String>, step: &Instruction| {
+            lines.push(String::new());
+            lines.push(step.text().split_whitespace().collect::<Vec<_>>().join(" "));
+        };
+        match &self.directions {
+            Some(InstructionList::Sections(sections)) => {
+                for section in sections {
+                    lines.push(String::new());
+                    lines.push(format!("== {} ==", section.name.trim()));
+                    section.directions.iter().for_each(|s| step(&mut lines, s));
+                }
+            }
+            Some(directions) => directions
+                .steps()
+                .into_iter()
+                .for_each(|s| step(&mut lines, s)),
+            None => {}
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// A stable 64-bit identifier for the recipe's content, for recognizing the same recipe
+    /// across crawls and sites. Only the name, the ingredient lines and the step texts (in
+    /// order, with sections flattened) are hashed, after lowercasing and collapsing runs of
+    /// whitespace; everything else, including times, images, URLs and section names, is ignored.
+    /// The hash is 64-bit FNV-1a, so it won't change between Rust versions or platforms
+    pub fn content_hash(&self) -> u64 {
+        const FIELD_SEPARATOR: char = '\u{1e}';
+        const ITEM_SEPARATOR: char = '\u{1f}';
+
+        let steps = self.directions.as_ref().map(InstructionList::steps);
+        let steps = steps.iter().flatten().map(|s| s.text());
+        let fields = [
+            vec![self.name.as_str()],
+            self.ingredients.entries().collect(),
+            steps.collect(),
+        ];
+
+        let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+        let mut feed = |c: char| {
+            let mut buf = [0; 4];
+            for &b in c.encode_utf8(&mut buf).as_bytes() {
+                hash = (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        for field in &fields {
+            for item in field {
+                crate::ingredient::normalized_chars(item).for_each(&mut feed);
+                feed(ITEM_SEPARATOR);
+            }
+            feed(FIELD_SEPARATOR);
+        }
+        hash
+    }
+
+    /// Whether the two recipes are equal in everything but their cook, prep and total times, e.g.
+    /// for recognizing copies of a recipe where only one source declared how long it takes
+    pub fn eq_ignoring_times(&self, other: &Recipe) -> bool {
+        let without_times = |recipe: &Recipe| Recipe {
+            cook_time: None,
+            prep_time: None,
+            total_time: None,
+            ..recipe.clone()
+        };
+        without_times(self) == without_times(other)
+    }
+
+    /// What changed from this recipe to `other`, e.g. between two crawls of the same page. Names
+    /// and descriptions are compared exactly, and times by their length (so "PT90M" and
+    /// "PT1H30M" are the same, as are a missing time and an unparseable one). Times involving
+    /// years or months, whose length varies, are compared component by component
+    pub fn diff(&self, other: &Recipe) -> RecipeDiff {
+        let changed = |a: &String, b: &String| (a != b).then(|| (a.clone(), b.clone()));
+        let time_changed = |a: &Option<MaybeDuration>, b: &Option<MaybeDuration>| {
+            let length = |t: &Option<MaybeDuration>| t.as_ref().and_then(MaybeDuration::to_std);
+            let duration = |t: &Option<MaybeDuration>| t.as_ref().and_then(|t| t.0);
+            let same = match (length(a), length(b)) {
+                (Some(a), Some(b)) => a == b,
+                _ => duration(a) == duration(b),
+            };
+            (!same).then(|| (a.clone(), b.clone()))
+        };
+        let missing_from = |entries: &[&str], others: &[&str]| {
+            let mut missing: Vec<String> = Vec::new();
+            for entry in entries {
+                if !others.contains(entry) && !missing.iter().any(|m| m == entry) {
+                    missing.push(entry.to_string());
+                }
+            }
+            missing
+        };
+        fn steps(recipe: &Recipe) -> Vec<&str> {
+            let steps = recipe.directions.as_ref().map(InstructionList::steps);
+            let steps = steps.unwrap_or_default().into_iter();
+            steps.map(|s| s.text().trim()).collect()
+        }
+
+        let (ours, theirs) = (
+            self.ingredients.entries().collect::<Vec<_>>(),
+            other.ingredients.entries().collect::<Vec<_>>(),
+        );
+        let (steps_added, steps_removed) = sequence_diff(&steps(self), &steps(other));
+        RecipeDiff {
+            name: changed(&self.name, &other.name),
+            description: changed(&self.description, &other.description),
+            ingredients_added: missing_from(&theirs, &ours),
+            ingredients_removed: missing_from(&ours, &theirs),
+            steps_added,
+            steps_removed,
+            cook_time: time_changed(&self.cook_time, &other.cook_time),
+            prep_time: time_changed(&self.prep_time, &other.prep_time),
+            total_time: time_changed(&self.total_time, &other.total_time),
+        }
+    }
+
+    /// Every timer mentioned by the steps (per [`Instruction::detected_timers`]), each alongside
+    /// the index of its step, in order, with sections flattened. Steps mentioning several lengths
+    /// of time appear once for each, and those mentioning none are skipped
+    pub fn timeline(&self) -> Vec<(usize, std::time::Duration)> {
+        let steps = self.directions.as_ref().map(InstructionList::steps);
+        steps
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(idx, step)| step.detected_timers().into_iter().map(move |t| (idx, t)))
+            .collect()
+    }
+
+    /// The image URLs of every step, in order, with sections flattened. Steps without images are
+    /// skipped
+    pub fn step_images(&self) -> Vec<&str> {
+        let steps = self.directions.as_ref().map(InstructionList::steps);
+        steps
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(Instruction::image_urls)
+            .collect()
+    }
+
+    /// The URL of the largest image, per [`Image::best_url`]
+    pub fn best_image_url(&self) -> Option<&str> {
+        self.image.as_ref()?.best_url()
+    }
+
+    /// The URL of the image's declared thumbnail, per [`Image::thumbnail_url`]
+    pub fn thumbnail_url(&self) -> Option<&str> {
+        self.image.as_ref()?.thumbnail_url()
+    }
+
+    /// Whether any ingredient's name matches `query`, per [`ParsedIngredient::matches`]
+    ///
+    /// [`ParsedIngredient::matches`]: crate::ParsedIngredient::matches
+    pub fn contains_ingredient(&self, query: &str) -> bool {
+        self.ingredients.parse().iter().any(|i| i.matches(query))
+    }
+
+    /// The number of ingredient lines, leaving out headings within the list such as "For the
+    /// glaze:" (as recognized by [`IngredientList::groups`]). Unlike
+    /// [`IngredientList::len`], this counts only actual ingredients
+    pub fn ingredient_count_excluding_headers(&self) -> usize {
+        let entries = self.ingredients.entries();
+        entries
+            .filter(|e| crate::ingredient::group_heading(e).is_none())
+            .count()
+    }
+
+    /// Every category (`recipeCategory`), cuisine (`recipeCuisine`), keyword (`keywords`) and
+    /// diet (`suitableForDiet`, with schema.org diet URLs reduced to a name like "vegan"), in that
+    /// order. Tags are trimmed and lowercased, and only the first occurrence of each is kept
+    pub fn all_tags(&self) -> Vec<String> {
+        let lists = [&self.category, &self.cuisine, &self.keywords];
+        let tags = lists
+            .iter()
+            .filter_map(|l| l.as_ref())
+            .flat_map(|l| l.items())
+            .map(str::to_string);
+        let diets = self.diet.iter().flat_map(|l| l.items()).map(diet_name);
+
+        let mut all: Vec<String> = Vec::new();
+        for tag in tags.chain(diets).map(|t| t.trim().to_lowercase()) {
+            if !tag.is_empty() && !all.contains(&tag) {
+                all.push(tag);
+            }
+        }
+        all
+    }
+
+    /// The names of the ingredients, with quantities and units removed per
+    /// [`ParsedIngredient`](crate::ParsedIngredient), e.g. "flour" for "2 cups flour". Names are
+    /// trimmed and lowercased, and only the first occurrence of each is kept
+    pub fn ingredient_names_only(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for ingredient in self.ingredients.parse() {
+            let name = ingredient.name().trim().to_lowercase();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Each ingredient line (as given by [`IngredientList::entries`]) alongside a normalized form
+    /// for search indexing, in order. The normalized form has its HTML character references
+    /// decoded, its unit [canonicalized](crate::ParsedIngredient::unit) and its quantity written
+    /// [as a fraction](crate::ParsedIngredient::display_fraction), and is lowercased with its
+    /// whitespace collapsed, so that "1 &frac12; Cups  Flour" and "1.5 cup flour" both become
+    /// "1 1/2 cup flour"
+    pub fn ingredients_indexed(&self) -> Vec<(String, String)> {
+        let pairs = self.ingredients.parsed_with_raw();
+        pairs
+            .into_iter()
+            .map(|(raw, parsed)| {
+                let parsed = match crate::text::decode_entities(&raw) {
+                    std::borrow::Cow::Owned(decoded) => crate::ParsedIngredient::parse(decoded),
+                    std::borrow::Cow::Borrowed(_) => parsed,
+                };
+                let normalized = parsed.display_fraction().to_lowercase();
+                let normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+                (raw, normalized)
+            })
+            .collect()
+    }
+
+    /// The recipe's stages, pairing its ingredient [groups](IngredientList::groups) with its
+    /// instruction sections by name (ignoring case, so "For the sauce:" pairs with a "Sauce"
+    /// section). Stages follow the order of the instruction sections, preceded by any ingredient
+    /// groups without a matching section. A recipe without any ingredient groups or instruction
+    /// sections has a single stage with an empty name holding everything
+    pub fn sections(&self) -> Vec<RecipeSection> {
+        let mut sections: Vec<RecipeSection> = self
+            .directions
+            .as_ref()
+            .map(InstructionList::as_sections)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| RecipeSection {
+                name: s.name.trim().to_string(),
+                ingredients: Vec::new(),
+                steps: s.directions,
+            })
+            .collect();
+
+        let mut unmatched = Vec::new();
+        for (name, ingredients) in self.ingredients.groups() {
+            if name.is_empty() && ingredients.is_empty() {
+                continue;
+            }
+            let ingredients = ingredients.into_iter().map(str::to_string).collect();
+            let section = sections.iter_mut().find(|s| {
+                let heading = format!("{}:", s.name);
+                let section_name = crate::ingredient::group_heading(&heading).unwrap_or("");
+                section_name.eq_ignore_ascii_case(name) && s.ingredients.is_empty()
+            });
+            match section {
+                Some(section) => section.ingredients = ingredients,
+                None => unmatched.push(RecipeSection {
+                    name: name.to_string(),
+                    ingredients,
+                    steps: Vec::new(),
+                }),
+            }
+        }
+
+        unmatched.extend(sections);
+        unmatched
+    }
+
+    /// The common allergens indicated by the ingredients' [parsed](IngredientList::parse) names,
+    /// such as [`Allergen::Dairy`](crate::Allergen::Dairy) for "1 cup milk" (but not for
+    /// "coconut milk"), matching keywords regardless of case and plurals
+    ///
+    /// This is a heuristic: allergens hidden within other ingredients (e.g. the wheat in soy
+    /// sauce, or a "pesto" containing pine nuts) are not detected, so it must not be relied upon
+    /// as a complete list
+    pub fn detected_allergens(&self) -> Vec<crate::Allergen> {
+        crate::allergen::detect(&self.ingredients.parse())
+    }
+
+    /// The parsed ingredients grouped by the [kind of measurement](crate::UnitType) their units
+    /// make, e.g. for organizing a shopping list. Each group keeps the ingredients' order, and
+    /// kinds without any ingredients are absent
+    pub fn ingredients_by_unit_type(
+        &self,
+    ) -> std::collections::HashMap<crate::UnitType, Vec<crate::ParsedIngredient>> {
+        let mut groups = std::collections::HashMap::new();
+        for ingredient in self.ingredients.parse() {
+            let group: &mut Vec<_> = groups.entry(ingredient.unit_type()).or_default();
+            group.push(ingredient);
+        }
+        groups
+    }
+
+    /// A rough guess at how many people the recipe serves, for when it declares no
+    /// [yield](Self::yields). The weighed amounts (in g, kg, oz or lb) of its main ingredients,
+    /// such as meat, fish or tofu, are totalled and divided by a typical serving's weight
+    /// (150 g), falling back to starches such as pasta, rice or potatoes (100 g) when it has
+    /// none of the former. The result is rounded, and at least 1
+    ///
+    /// This is only a heuristic: amounts given by count ("2 chicken breasts") or volume are
+    /// ignored, and side dishes or baking are poorly served by it. `None` when no main
+    /// ingredient has a weight
+    pub fn estimated_servings(&self) -> Option<u32> {
+        let ingredients = self.ingredients.parse();
+        let grams = |ingredient: &crate::ParsedIngredient| {
+            let unit = ingredient.unit()?;
+            let per_unit = GRAMS_PER_UNIT.iter().find(|(u, _)| *u == unit)?.1;
+            Some(ingredient.quantity()? * per_unit)
+        };
+
+        SERVING_WEIGHTS.iter().find_map(|(keywords, per_serving)| {
+            let total = ingredients
+                .iter()
+                .filter(|i| keywords.iter().any(|k| i.matches(k)))
+                .filter_map(grams)
+                .sum::<f64>();
+            let servings = (total / per_serving).round().max(1.);
+            (total > 0. && servings < f64::from(u32::MAX)).then(|| servings as u32)
+        })
+    }
+
+    /// The keywords as hashtags, e.g. "#dessert" for "dessert" and "#GlutenFree" for
+    /// "gluten-free". Spaces and punctuation are removed, with each word of a multi-word keyword
+    /// capitalized. Only the first of several hashtags differing only in case is kept
+    pub fn hashtags(&self) -> Vec<String> {
+        let keywords = self.keywords.as_ref().map(StringList::items);
+
+        let mut hashtags: Vec<String> = Vec::new();
+        for keyword in keywords.unwrap_or_default() {
+            let words = keyword
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>();
+            let tag: String = match words.as_slice() {
+                [] => continue,
+                [word] => word.to_string(),
+                words => words
+                    .iter()
+                    .flat_map(|w| {
+                        let mut chars = w.chars();
+                        chars
+                            .next()
+                            .into_iter()
+                            .flat_map(char::to_uppercase)
+                            .chain(chars)
+                    })
+                    .collect(),
+            };
+            let tag = format!("#{}", tag);
+            if !hashtags
+                .iter()
+                .any(|t| t.to_lowercase() == tag.to_lowercase())
+            {
+                hashtags.push(tag);
+            }
+        }
+        hashtags
+    }
+
+    /// A copy of the recipe with `f` applied to its free text: the name, description, yield,
+    /// ingredients, directions (including section and step names), author names, keywords,
+    /// categories, cuisines, diets, cooking techniques, notes, nutrition and site name
+    pub(crate) fn map_text(&self, f: impl Fn(&str) -> String) -> Recipe {
+        let list = |l: &Option<StringList>| l.as_ref().map(|l| l.map_text(&f));
+        Recipe {
+            name: f(&self.name),
+            description: f(&self.description),
+            yields: self.yields.as_ref().map(|y| y.map_text(&f)),
+            ingredients: self.ingredients.map_text(&f),
+            directions: self.directions.as_ref().map(|d| d.map_text(&f)),
+            author: self.author.as_ref().map(|a| a.map_text(&f)),
+            keywords: list(&self.keywords),
+            category: list(&self.category),
+            cuisine: list(&self.cuisine),
+            diet: list(&self.diet),
+            cooking_technique: list(&self.cooking_technique),
+            notes: self.notes.iter().map(|n| f(n)).collect(),
+            nutrition: self.nutrition.as_ref().map(|n| n.map_text(&f)),
+            site_name: self.site_name.as_deref().map(&f),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of the recipe with HTML character references (`&amp;`, `&#189;`) in its free text
+    /// decoded
+    pub fn decode_entities(&self) -> Recipe {
+        self.map_text(|s| crate::text::decode_entities(s).into_owned())
+    }
+
+    /// A copy of the recipe with the whitespace in its free text tidied: each line is trimmed,
+    /// and runs of spaces, tabs and similar within it collapsed to a single space
+    pub fn trimmed(&self) -> Recipe {
+        self.map_text(|s| {
+            let lines = s
+                .lines()
+                .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "));
+            lines.collect::<Vec<_>>().join("\n").trim().to_string()
+        })
+    }
+
+    /// A copy of the recipe with all HTML tags removed from its free text, keeping their text
+    /// content except for that of `<script>`, `<style>` and similar elements. Unlike
+    /// [`sanitized`](Self::sanitized), no formatting is kept, and the result is plain text rather
+    /// than HTML
+    pub fn strip_html(&self) -> Recipe {
+        self.map_text(|s| crate::text::strip_tags(s).into_owned())
+    }
+
+    /// A copy of the recipe without repeated ingredient lines, keeping the first of each
+    pub fn dedup_ingredients(&self) -> Recipe {
+        Recipe {
+            ingredients: self.ingredients.deduped(),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of the recipe with the usual cleanup applied, as plain text: HTML character
+    /// references [decoded](Self::decode_entities), then tags [stripped](Self::strip_html) (both
+    /// of which may leave stray whitespace), then whitespace [tidied](Self::trimmed), then
+    /// [boilerplate steps](Self::clean_instructions) and
+    /// [repeated ingredients](Self::dedup_ingredients) (which only match once tidied) removed
+    pub fn normalized(&self) -> Recipe {
+        self.decode_entities()
+            .strip_html()
+            .trimmed()
+            .clean_instructions()
+            .dedup_ingredients()
+    }
+
+    /// A copy of the recipe which is safe to render as HTML. Its free text (per
+    /// [`decode_entities`](Self::decode_entities)) is reduced to an allowlist of inline
+    /// formatting tags (`<b>`, `<em>`, `<a href>`, lists, ...) with all other attributes
+    /// removed, `<script>`, `<style>` and similar elements are dropped along with their content,
+    /// and any other text is HTML-escaped. This covers every field
+    /// [`map_text`](Self::map_text) does, from the name to the nutrition information
+    ///
+    /// URLs with a scheme other than `http`, `https` or `mailto` (such as `javascript:`), wherever
+    /// they're declared (per [`for_each_url`](Self::for_each_url)), are emptied, and the recipe's
+    /// own `url` removed
+    pub fn sanitized(&self) -> Recipe {
+        let mut recipe = self.map_text(|s| crate::text::sanitize(s).into_owned());
+        recipe.for_each_url(&mut |url| {
+            if !crate::text::is_safe_url(url) {
+                url.clear();
+            }
+        });
+        recipe.url = recipe.url.filter(|u| !u.is_empty());
+        recipe
+    }
+
+    /// A copy of the recipe with [boilerplate](Instruction::is_boilerplate) steps, such as
+    /// "Jump to Recipe", removed from its directions
+    pub fn clean_instructions(&self) -> Recipe {
+        Recipe {
+            directions: self
+                .directions
+                .as_ref()
+                .and_then(InstructionList::without_boilerplate),
+            ..self.clone()
+        }
+    }
+
+    /// Combine two partial copies of the same recipe (e.g. found in different formats on one
+    /// page), taking each field from `self` unless it is missing or empty there, in which case
+    /// `other`'s is used. Text is empty when blank, times when they couldn't be parsed, and lists
+    /// (ingredients, steps, images, keywords, ...) when they hold no entries. Since `self` wins
+    /// whenever it has a value, fields are never combined: two ingredient lists aren't joined
+    pub fn merge(self, other: Recipe) -> Recipe {
+        fn pick<T>(a: Option<T>, b: Option<T>, usable: impl Fn(&T) -> bool) -> Option<T> {
+            match a {
+                Some(a) if usable(&a) => Some(a),
+                a => b.filter(|b| usable(b)).or(a),
+            }
+        }
+        fn any<T>(_: &T) -> bool {
+            true
+        }
+        let text = |a: String, b: String| if a.trim().is_empty() { b } else { a };
+        let time = |t: &MaybeDuration| t.0.is_some();
+        let list = |l: &StringList| !l.items().is_empty();
+
+        let has_ingredients = self.ingredients.entries().next().is_some();
+        let ingredients = if has_ingredients {
+            self.ingredients
+        } else {
+            other.ingredients
+        };
+        Recipe {
+            name: text(self.name, other.name),
+            description: text(self.description, other.description),
+            cook_time: pick(self.cook_time, other.cook_time, time),
+            prep_time: pick(self.prep_time, other.prep_time, time),
+            total_time: pick(self.total_time, other.total_time, time),
+            yields: pick(self.yields, other.yields, |y| {
+                !y.to_string().trim().is_empty()
+            }),
+            ingredients,
+            directions: pick(self.directions, other.directions, |d| !d.is_empty()),
+            image: pick(self.image, other.image, |i| !i.urls().is_empty()),
+            url: pick(self.url, other.url, |u| !u.trim().is_empty()),
+            author: pick(self.author, other.author, any),
+            keywords: pick(self.keywords, other.keywords, list),
+            category: pick(self.category, other.category, list),
+            cuisine: pick(self.cuisine, other.cuisine, list),
+            diet: pick(self.diet, other.diet, list),
+            accessible_for_free: pick(self.accessible_for_free, other.accessible_for_free, any),
+            cooking_technique: pick(self.cooking_technique, other.cooking_technique, list),
+            notes: if self.notes.is_empty() {
+                other.notes
+            } else {
+                self.notes
+            },
+            nutrition: pick(self.nutrition, other.nutrition, any),
+            main_entity_of_page: pick(self.main_entity_of_page, other.main_entity_of_page, |v| {
+                !v.is_null()
+            }),
+            id: pick(self.id, other.id, any),
+            types: pick(self.types, other.types, list),
+            site_name: pick(self.site_name, other.site_name, any),
+        }
+    }
+
+    /// A copy of the recipe with every ingredient quantity and the yield multiplied by `factor`.
+    /// Quantities are scaled wherever an ingredient line (or yield) starts with one, so
+    /// "1 1/2 cups flour" scaled by 2 becomes "3 cups flour", while "salt to taste" is kept
+    /// as-is. Quantities mentioned within the directions are not scaled
+    pub fn scale(&self, factor: f64) -> Recipe {
+        Recipe {
+            ingredients: self.ingredients.scaled(factor),
+            yields: self.yields.as_ref().map(|y| y.scaled(factor)),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of the recipe [scaled](Self::scale) to make `target` of its yield, e.g. 8 servings
+    /// of a recipe which serves 4. `None` when the yield has no (positive) number, per
+    /// [`Yield::value_and_unit`]
+    pub fn scale_to_yield(&self, target: f64) -> Option<Recipe> {
+        let (current, _) = self.yields.as_ref()?.value_and_unit()?;
+        (current > 0. && target.is_finite()).then(|| self.scale(target / current))
+    }
+
+    /// A copy of the recipe keeping only its first `max_ingredients` ingredients and first
+    /// `max_steps` steps, e.g. for a preview card. Steps are counted across sections, which are
+    /// flattened away, and truncated lists end with an extra "…and N more" entry counting what
+    /// was left out. Lists within the limits are kept as they were
+    pub fn preview(&self, max_ingredients: usize, max_steps: usize) -> Recipe {
+        fn truncated<T>(mut items: Vec<T>, max: usize, more: impl Fn(String) -> T) -> Vec<T> {
+            let omitted = items.len().saturating_sub(max);
+            items.truncate(max);
+            if omitted > 0 {
+                items.push(more(format!("…and {} more", omitted)));
+            }
+            items
+        }
+
+        let ingredients = match &self.ingredients {
+            list if list.entries().count() <= max_ingredients => list.clone(),
+            IngredientList::Structured(v) => {
+                let entries = v.iter().filter(|i| !i.text().trim().is_empty()).cloned();
+                let entries = truncated(entries.collect(), max_ingredients, Ingredient::Text);
+                IngredientList::Structured(entries)
+            }
+            list => {
+                let entries = list.entries().map(str::to_string).collect();
+                IngredientList::Multi(truncated(entries, max_ingredients, |s| s))
+            }
+        };
+        let directions = match &self.directions {
+            Some(list) if list.len() > max_steps => {
+                let steps = list.steps().into_iter().cloned().collect();
+                let steps = truncated(steps, max_steps, Instruction::Simple);
+                Some(InstructionList::Multi(steps))
+            }
+            directions => directions.clone(),
+        };
+
+        Recipe {
+            ingredients,
+            directions,
+            ..self.clone()
+        }
+    }
+
+    /// The total time in whole minutes, per [`MaybeDuration::total_minutes`]
+    pub fn total_minutes(&self) -> Option<u64> {
+        self.total_time.as_ref()?.total_minutes()
+    }
+
+    /// The prep time in whole minutes, per [`MaybeDuration::total_minutes`]
+    pub fn prep_minutes(&self) -> Option<u64> {
+        self.prep_time.as_ref()?.total_minutes()
+    }
+
+    /// The first time declared with a usable duration, preferring the total time, then the cook
+    /// time, then the prep time
+    pub fn any_time(&self) -> Option<&MaybeDuration> {
+        [&self.total_time, &self.cook_time, &self.prep_time]
+            .iter()
+            .filter_map(|t| t.as_ref())
+            .find(|t| t.0.is_some())
+    }
+
+    /// The hands-on time, i.e. the prep and cook times added together, in human readable form
+    /// per [`MaybeDuration::human_readable`]. Either time alone is used when only one is known,
+    /// and `None` is returned when neither is
+    pub fn active_time_human(&self) -> Option<String> {
+        let none = MaybeDuration(None, None);
+        let prep = self.prep_time.as_ref().unwrap_or(&none);
+        let cook = self.cook_time.as_ref().unwrap_or(&none);
+        prep.plus(cook).human_readable()
+    }
+
+    /// The total time if declared, else the prep and cook times added together (or whichever of
+    /// them is known). `None` when no time could be parsed
+    pub fn computed_total_time(&self) -> Option<MaybeDuration> {
+        if let Some(total) = self.total_time.as_ref().filter(|t| t.0.is_some()) {
+            return Some(total.clone());
+        }
+        let none = MaybeDuration(None, None);
+        let prep = self.prep_time.as_ref().unwrap_or(&none);
+        let cook = self.cook_time.as_ref().unwrap_or(&none);
+        Some(prep.plus(cook)).filter(|t| t.0.is_some())
+    }
+
+    /// Whether the [computed total time](Self::computed_total_time) is at most `under`. Recipes
+    /// without any usable time (including those with times in months or years) are never quick
+    pub fn is_quick(&self, under: std::time::Duration) -> bool {
+        self.computed_total_duration()
+            .map_or(false, |total| total.as_secs() <= under.as_secs())
+    }
+
+    /// The [computed total time](Self::computed_total_time) to the second, when it has no years
+    /// or months
+    fn computed_total_duration(&self) -> Option<std::time::Duration> {
+        let (h, m, s) = self.computed_total_time()?.hms()?;
+        let seconds = u64::from(h) * 3600 + u64::from(m) * 60 + u64::from(s);
+        Some(std::time::Duration::from_secs(seconds))
+    }
+
+    /// The [computed total time](Self::computed_total_time) when known, or else a heuristic
+    /// estimate of [`ESTIMATED_MINUTES_PER_STEP`](Self::ESTIMATED_MINUTES_PER_STEP) minutes for
+    /// each step (counting a recipe without directions as a single step), e.g. for sorting
+    /// recipes by time. Estimates are rough by nature: use
+    /// [`computed_total_time`](Self::computed_total_time) to tell whether the recipe declared a
+    /// time at all
+    pub fn total_time_or_estimate(&self) -> std::time::Duration {
+        self.computed_total_duration().unwrap_or_else(|| {
+            let steps = self.directions.as_ref().map_or(0, InstructionList::len);
+            let minutes = steps.max(1) as u64 * Self::ESTIMATED_MINUTES_PER_STEP;
+            std::time::Duration::from_secs(minutes * 60)
+        })
+    }
+
+    /// The cook time in whole minutes, per [`MaybeDuration::total_minutes`]
+    pub fn cook_minutes(&self) -> Option<u64> {
+        self.cook_time.as_ref()?.total_minutes()
+    }
+
+    /// A heuristic difficulty rating. Step count, ingredient count and total time are each scored
+    /// as easy (0), medium (1) or hard (2) against the `EASY_MAX_*` and `HARD_ABOVE_*` thresholds
+    /// of [`Difficulty`], with missing times scoring as medium. A total score of up to 1 is easy,
+    /// 2 to 3 is medium and 4 or more is hard
+    pub fn difficulty(&self) -> Difficulty {
+        let score = |value: u64, easy_max: u64, hard_above: u64| match value {
+            v if v <= easy_max => 0,
+            v if v > hard_above => 2,
+            _ => 1,
+        };
+
+        let steps = self.directions.as_ref().map_or(0, InstructionList::len);
+        let ingredients = self.ingredients.entries().count();
+        let total = score(
+            steps as u64,
+            Difficulty::EASY_MAX_STEPS as u64,
+            Difficulty::HARD_ABOVE_STEPS as u64,
+        ) + score(
+            ingredients as u64,
+            Difficulty::EASY_MAX_INGREDIENTS as u64,
+            Difficulty::HARD_ABOVE_INGREDIENTS as u64,
+        ) + self.total_minutes().map_or(1, |m| {
+            score(
+                m,
+                Difficulty::EASY_MAX_MINUTES,
+                Difficulty::HARD_ABOVE_MINUTES,
+            )
+        });
+
+        match total {
+            0..=1 => Difficulty::Easy,
+            2..=3 => Difficulty::Medium,
+            _ => Difficulty::Hard,
+        }
+    }
+
+    /// The URL of the first author declaring one
+    pub fn first_author_url(&self) -> Option<&str> {
+        self.author.as_ref().and_then(Author::first_url)
+    }
+}
+
+#[cfg(test)]
+impl Recipe {
+    pub(crate) fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        ingredients: IngredientList,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            ingredients,
+            cook_time: None,
+            prep_time: None,
+            total_time: None,
+            yields: None,
+            directions: None,
+            image: None,
+            url: None,
+            author: None,
+            keywords: None,
+            category: None,
+            cuisine: None,
+            diet: None,
+            accessible_for_free: None,
+            cooking_technique: None,
+            notes: Vec::new(),
+            nutrition: None,
+            main_entity_of_page: None,
+            id: None,
+            types: None,
+            site_name: None,
+        }
+    }
+
+    pub(crate) fn with_directions(self, directions: InstructionList) -> Self {
+        Self {
+            directions: Some(directions),
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum GraphEntry {
+    Recipe(Box<Recipe>),
+    /// A restaurant or meal kit `Menu` (or one of its sections or items), holding the recipes
+    /// nested within it
+    Menu(#[serde(deserialize_with = "menu_recipes")] Vec<Recipe>),
+    Nonsense {
+        #[serde(rename = "@id")]
+        id: String,
+    },
+}
+
+impl GraphEntry {
+    /// The entry's recipe or, for a menu, the first recipe within it
+    pub fn recipe(&self) -> Option<&Recipe> {
+        self.recipes().into_iter().next()
+    }
+
+    fn recipes(&self) -> Vec<&Recipe> {
+        match self {
+            Self::Recipe(r) => vec![r.as_ref()],
+            Self::Menu(recipes) => recipes.iter().collect(),
+            Self::Nonsense { .. } => Vec::new(),
+        }
+    }
+
+    fn recipes_mut(&mut self) -> Vec<&mut Recipe> {
+        match self {
+            Self::Recipe(r) => vec![r.as_mut()],
+            Self::Menu(recipes) => recipes.iter_mut().collect(),
+            Self::Nonsense { .. } => Vec::new(),
+        }
+    }
+
+    fn into_recipes(self) -> Vec<Recipe> {
+        match self {
+            Self::Recipe(r) => vec![*r],
+            Self::Menu(recipes) => recipes,
+            Self::Nonsense { .. } => Vec::new(),
+        }
+    }
+}
+
+/// The types of the objects a `Menu` is built from, which are searched for nested recipes
+const MENU_TYPES: &[&str] = &["Menu", "MenuSection", "MenuItem"];
+
+fn is_menu_node(json: &serde_json::Value) -> bool {
+    MENU_TYPES.iter().any(|ty| has_type(json, ty))
+}
+
+/// Every recipe within `json`, descending only through menu objects (and lists of them), so
+/// that a recipe is found as a `MenuItem`'s property or among a `MenuSection`'s items but not
+/// within unrelated objects
+fn menu_recipe_nodes(json: &serde_json::Value) -> Vec<&serde_json::Value> {
+    use serde_json::Value;
+
+    match json {
+        _ if is_recipe_node(json) => vec![json],
+        Value::Array(items) => items.iter().flat_map(menu_recipe_nodes).collect(),
+        Value::Object(o) if is_menu_node(json) => o.values().flat_map(menu_recipe_nodes).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Deserialize a `Menu`, `MenuSection` or `MenuItem` into the recipes nested within it, per
+/// [`menu_recipe_nodes`], skipping any which don't parse. Anything else is an error
+fn menu_recipes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Recipe>, D::Error> {
+    use serde::de::Error;
+
+    let menu = serde_json::Value::deserialize(deserializer)?;
+    if !is_menu_node(&menu) {
+        return Err(D::Error::custom("not a menu"));
+    }
+    let nodes = menu_recipe_nodes(&menu).into_iter();
+    Ok(nodes
+        .filter_map(|node| serde_json::from_value(node.clone()).ok())
+        .collect())
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaItem {
+    Recipe(Box<Recipe>),
+    /// An object carrying its own `@graph` (typically alongside `@context`), found as one item of
+    /// a top-level array
+    Graph {
+        #[serde(rename = "@graph")]
+        graph: Vec<GraphEntry>,
+    },
+    /// A page (typically a `WebPage`) declaring its recipes as its `mainEntity`, either as a
+    /// single object or a list
+    Page {
+        #[serde(rename = "mainEntity", deserialize_with = "main_entities")]
+        main_entity: Vec<SchemaItem>,
+    },
+    /// A restaurant or meal kit `Menu` (or one of its sections or items), holding the recipes
+    /// nested within it
+    Menu(#[serde(deserialize_with = "menu_recipes")] Vec<Recipe>),
+    Nonsense {
+        #[serde(rename = "@context", deserialize_with = "context_vocabulary")]
+        context: String,
+    },
+}
+
+/// Deserialize a JSON-LD `@context` into the vocabulary it declares, which may be given as a
+/// string, an object with `@vocab`, or a list of either (e.g. `["https://schema.org", {...}]`).
+/// Where a list declares several, schema.org is preferred
+fn context_vocabulary<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    use serde::de::Error;
+    use serde_json::Value;
+
+    fn vocabularies(context: &Value) -> Vec<&str> {
+        match context {
+            Value::String(s) => vec![s.as_str()],
+            Value::Object(o) => o
+                .get("@vocab")
+                .and_then(Value::as_str)
+                .into_iter()
+                .collect(),
+            Value::Array(v) => v.iter().flat_map(vocabularies).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    let context = Value::deserialize(deserializer)?;
+    let vocabularies = vocabularies(&context);
+    vocabularies
+        .iter()
+        .find(|v| v.contains("schema.org"))
+        .or_else(|| vocabularies.first())
+        .map(|v| v.to_string())
+        .ok_or_else(|| D::Error::custom(format!("unexpected context: {}", context)))
+}
+
+/// Deserialize a `mainEntity`, which may be a single item or a list of them. As with
+/// [`valid_items`], list items which don't parse are skipped
+fn main_entities<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<SchemaItem>, D::Error> {
+    use serde::de::Error;
+
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Array(v) => {
+            valid_items(serde_json::Value::Array(v)).map_err(D::Error::custom)
+        }
+        single => serde_json::from_value(single)
+            .map(|item| vec![item])
+            .map_err(D::Error::custom),
+    }
+}
+
+impl SchemaItem {
+    /// The item's recipe or, for a nested graph, the first recipe within it
+    pub fn recipe(&self) -> Option<&Recipe> {
+        self.recipes().into_iter().next()
+    }
+
+    /// Every recipe held by this item, in document order
+    pub fn recipes(&self) -> Vec<&Recipe> {
+        match self {
+            Self::Recipe(r) => vec![r.as_ref()],
+            Self::Graph { graph } => graph.iter().flat_map(GraphEntry::recipes).collect(),
+            Self::Page { main_entity } => main_entity.iter().flat_map(Self::recipes).collect(),
+            Self::Menu(recipes) => recipes.iter().collect(),
+            Self::Nonsense { .. } => Vec::new(),
+        }
+    }
+
+    fn recipes_mut(&mut self) -> Vec<&mut Recipe> {
+        match self {
+            Self::Recipe(r) => vec![r.as_mut()],
+            Self::Graph { graph } => graph.iter_mut().flat_map(GraphEntry::recipes_mut).collect(),
+            Self::Page { main_entity } => {
+                main_entity.iter_mut().flat_map(Self::recipes_mut).collect()
+            }
+            Self::Menu(recipes) => recipes.iter_mut().collect(),
+            Self::Nonsense { .. } => Vec::new(),
+        }
+    }
+
+    fn into_recipes(self) -> Vec<Recipe> {
+        match self {
+            Self::Recipe(r) => vec![*r],
+            Self::Graph { graph } => graph
+                .into_iter()
+                .flat_map(GraphEntry::into_recipes)
+                .collect(),
+            Self::Page { main_entity } => main_entity
+                .into_iter()
+                .flat_map(Self::into_recipes)
+                .collect(),
+            Self::Menu(recipes) => recipes,
+            Self::Nonsense { .. } => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaEntry {
+    // Multi is attempted first so that a bare top-level array is never mistaken for one of the
+    // struct-like variants, which serde would otherwise try to fill positionally
+    #[serde(deserialize_with = "valid_items")]
+    Multi(Vec<SchemaItem>),
+    Graph {
+        #[serde(rename = "@graph")]
+        graph: Vec<GraphEntry>,
+    },
+    Single(SchemaItem),
+}
+
+/// Deserialize a sequence, skipping any items which don't parse as a `SchemaItem` rather than
+/// failing the whole sequence
+fn valid_items<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<SchemaItem>, D::Error> {
+    let values = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect())
+}
+
+/// The text of every `ld+json` script block in the document
+fn script_bodies(html: &scraper::Html) -> Vec<Cow<'_, str>> {
+    scripts_of_type(html, "application/ld+json")
+}
+
+/// Whether an `ld+json` script body could hold a recipe, checked without parsing it so that
+/// blocks which can't (breadcrumbs, site search boxes, publishers and the like) are skipped
+/// cheaply when only recipes are wanted
+///
+/// This errs on the side of keeping blocks: any mention of a recipe or its ingredients (in any
+/// case, for recipes which omit `@type`) is enough, as is an `@id` (whose node may be merged into
+/// a recipe declared by another block) or a `\u` escape (which could spell either)
+fn may_hold_recipe(body: &str) -> bool {
+    let mentions = |needle: &str| {
+        body.as_bytes()
+            .windows(needle.len())
+            .any(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+    };
+    body.contains("@id") || body.contains("\\u") || mentions("recipe") || mentions("ingredient")
+}
+
+/// The text of `el`, borrowed from the document when it's a single text node (as script bodies
+/// almost always are) rather than copied
+fn element_text(el: scraper::ElementRef<'_>) -> Cow<'_, str> {
+    let mut chunks = el.text();
+    match (chunks.next(), chunks.next()) {
+        (None, _) => Cow::Borrowed(""),
+        (Some(text), None) => Cow::Borrowed(text),
+        (Some(first), Some(second)) => {
+            let mut text = String::from(first);
+            text.push_str(second);
+            text.extend(chunks);
+            Cow::Owned(text)
+        }
+    }
+}
+
+/// How deeply `<noscript>` blocks are searched for scripts when nested within one another. Real
+/// pages rarely nest them at all, and each level is parsed afresh (recursively), so hostile
+/// documents nesting thousands of them are cut off here rather than overflowing the stack
+const MAX_NOSCRIPT_DEPTH: usize = 4;
+
+/// The text of every script block in the document with the given `type`, in document order
+///
+/// Scripts within `<noscript>` are included too. With scripting enabled (as when parsing), the
+/// HTML parser keeps a `<noscript>`'s content as raw text, so that text is parsed separately
+fn scripts_of_type<'a>(html: &'a scraper::Html, script_type: &str) -> Vec<Cow<'a, str>> {
+    nested_scripts_of_type(html, script_type, 0)
+}
+
+fn nested_scripts_of_type<'a>(
+    html: &'a scraper::Html,
+    script_type: &str,
+    depth: usize,
+) -> Vec<Cow<'a, str>> {
+    // Unwrap is appropriate here as the selector is built from a fixed set of MIME types, which
+    // we can reasonably expect to parse successfully
+    let selector = format!(r#"script[type="{}"], noscript"#, script_type);
+    let selector = scraper::Selector::parse(&selector).unwrap();
+    html.select(&selector)
+        .flat_map(|el| {
+            let text = element_text(el);
+            match el.value().name() {
+                "noscript" if depth < MAX_NOSCRIPT_DEPTH && text.contains("<script") => {
+                    let fragment = scraper::Html::parse_fragment(&text);
+                    let scripts = nested_scripts_of_type(&fragment, script_type, depth + 1);
+                    let owned = scripts.into_iter().map(|s| Cow::Owned(s.into_owned()));
+                    owned.collect()
+                }
+                "noscript" => Vec::new(),
+                _ => vec![text],
+            }
+        })
+        .collect()
+}
+
+/// Parse a script body into a JSON value, relaxing it first if it fails strict parsing and the
+/// `lenient` feature is enabled
+fn script_value(body: &str) -> Result<serde_json::Value, serde_json::Error> {
+    let value = serde_json::from_str(body);
+    #[cfg(feature = "lenient")]
+    let value =
+        value.or_else(|e| serde_json::from_str(&crate::lenient::relax(body)).map_err(|_| e));
+    value
+}
+
+/// Every JSON value in a script body, as by [`script_value`]. Some broken templates emit several
+/// values back-to-back in one script (`{...}{...}`), so when the body doesn't parse as a single
+/// value, as many values as can be read from its start are returned instead
+fn script_values(body: &str) -> Vec<serde_json::Value> {
+    match script_value(body) {
+        Ok(value) => vec![value],
+        Err(_) => serde_json::Deserializer::from_str(body)
+            .into_iter()
+            .take_while(Result::is_ok)
+            .flatten()
+            .collect(),
+    }
+}
+
+/// The objects of a JSON-LD document which may hold a recipe, in document order: the items of a
+/// top-level array or `@graph` (including graphs nested within array items), or the document
+/// itself
+fn recipe_nodes(json: &serde_json::Value) -> Vec<&serde_json::Value> {
+    use serde_json::Value;
+
+    match json {
+        Value::Array(items) => items
+            .iter()
+            .flat_map(|item| match item.get("@graph") {
+                Some(Value::Array(graph)) => graph.iter().collect(),
+                _ => vec![item],
+            })
+            .collect(),
+        Value::Object(o) => match o.get("@graph") {
+            Some(Value::Array(graph)) => graph.iter().collect(),
+            _ => vec![json],
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// The objects within a JSON-LD document which a [`SchemaEntry`] may take a recipe from, in
+/// document order: the document itself, the items of arrays and `@graph`s, a page's
+/// `mainEntity` and the recipes nested within menus
+fn candidate_recipe_nodes(json: &serde_json::Value) -> Vec<&serde_json::Value> {
+    use serde_json::Value;
+
+    match json {
+        Value::Array(items) => items.iter().flat_map(candidate_recipe_nodes).collect(),
+        Value::Object(o) => {
+            let mut nodes = vec![json];
+            for key in ["@graph", "mainEntity"] {
+                nodes.extend(o.get(key).into_iter().flat_map(candidate_recipe_nodes));
+            }
+            if is_menu_node(json) {
+                nodes.extend(menu_recipe_nodes(json));
+            }
+            nodes
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The largest generic `application/json` script block which will be searched for recipes, as
+/// these frequently hold an entire page's application state
+pub(crate) const MAX_JSON_SCRIPT_BYTES: usize = 2 * 1024 * 1024;
+
+fn is_recipe_node(json: &serde_json::Value) -> bool {
+    has_type(json, "Recipe")
+}
+
+/// Whether a JSON object's `@type` is `ty`, alone or among other types
+pub(crate) fn has_type(json: &serde_json::Value, ty: &str) -> bool {
+    use serde_json::Value;
+
+    match json.get("@type") {
+        Some(Value::String(t)) => t == ty,
+        Some(Value::Array(types)) => types.iter().any(|t| t == ty),
+        _ => false,
+    }
+}
+
+/// Every object nested anywhere within `json` whose `@type` is `Recipe`, in document order.
+/// Recipes are not searched for further recipes
+fn find_all_recipes(json: &serde_json::Value) -> Vec<&serde_json::Value> {
+    use serde_json::Value;
+
+    match json {
+        _ if is_recipe_node(json) => vec![json],
+        Value::Array(items) => items.iter().flat_map(find_all_recipes).collect(),
+        Value::Object(o) => o.values().flat_map(find_all_recipes).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every recipe nested within the document's generic `application/json` script blocks, such as
+/// Next.js's `__NEXT_DATA__`. Blocks over [`MAX_JSON_SCRIPT_BYTES`] are skipped unparsed
+pub(crate) fn json_script_recipes(html: &scraper::Html) -> Vec<Recipe> {
+    let bodies = scripts_of_type(html, "application/json");
+    nested_recipes(html, bodies.iter().map(AsRef::as_ref))
+}
+
+/// Every recipe nested within JSON held by the given attributes of any element, such as
+/// `data-recipe`. Values which aren't valid JSON are retried with HTML character references
+/// decoded, for pages which encode them twice. Like script blocks, values over
+/// [`MAX_JSON_SCRIPT_BYTES`] are skipped
+pub(crate) fn attribute_recipes(html: &scraper::Html, attributes: &[String]) -> Vec<Recipe> {
+    let values = html
+        .root_element()
+        .descendants()
+        .filter_map(scraper::ElementRef::wrap)
+        .flat_map(|el| attributes.iter().filter_map(move |a| el.value().attr(a)))
+        .collect::<Vec<_>>();
+    let decoded = values
+        .iter()
+        .map(|v| match serde_json::from_str::<serde::de::IgnoredAny>(v) {
+            Ok(_) => std::borrow::Cow::Borrowed(*v),
+            Err(_) => crate::text::decode_entities(v),
+        })
+        .collect::<Vec<_>>();
+    nested_recipes(html, decoded.iter().map(|v| v.as_ref()))
+}
+
+/// Every recipe nested anywhere within each of the JSON `values` found in `html`
+fn nested_recipes<'a>(
+    html: &scraper::Html,
+    values: impl IntoIterator<Item = &'a str>,
+) -> Vec<Recipe> {
+    let site_name = site_name(html);
+
+    let mut recipes = Vec::new();
+    for value in values {
+        if value.len() > MAX_JSON_SCRIPT_BYTES {
+            continue;
+        }
+        let mut json = match serde_json::from_str(value.trim()) {
+            Ok(json) => json,
+            Err(_) => continue,
+        };
+        crate::graph::resolve_references(&mut json, crate::ResolveOptions::default());
+
+        for node in find_all_recipes(&json) {
+            if let Ok(mut recipe) = serde_json::from_value::<Recipe>(node.clone()) {
+                recipe.site_name = site_name.clone();
+                recipes.push(recipe);
+            }
+        }
+    }
+    recipes
+}
+
+/// One stage of a recipe, as returned by [`Recipe::sections`]: a named group of ingredients
+/// alongside the steps which use them, e.g. a "Dough" stage and a "Filling" stage. Either may be
+/// empty, and the name is empty for a recipe without any grouping
+#[derive(Clone, Debug, Eq, PartialEq, Accessors)]
+#[access(get)]
+pub struct RecipeSection {
+    name: String,
+    ingredients: Vec<String>,
+    steps: Vec<Instruction>,
+}
+
+/// A time before and after a change, as described by [`RecipeDiff`]
+pub type TimeChange = (Option<MaybeDuration>, Option<MaybeDuration>);
+
+/// What changed between two versions of a recipe, as returned by [`Recipe::diff`]. Ingredients
+/// are compared as sets, so reordering them isn't a change, while steps are compared in order
+/// (with sections flattened), each added step given by its index in the new recipe and each
+/// removed one by its index in the old
+#[derive(Clone, Debug, Default, PartialEq, Accessors)]
+#[access(get)]
+pub struct RecipeDiff {
+    name: Option<(String, String)>,
+    description: Option<(String, String)>,
+    ingredients_added: Vec<String>,
+    ingredients_removed: Vec<String>,
+    steps_added: Vec<(usize, String)>,
+    steps_removed: Vec<(usize, String)>,
+    cook_time: Option<TimeChange>,
+    prep_time: Option<TimeChange>,
+    total_time: Option<TimeChange>,
+}
+
+impl RecipeDiff {
+    /// Whether nothing compared changed
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Entries of a sequence, each alongside its index
+type Indexed = Vec<(usize, String)>;
+
+/// The most cells [`sequence_diff`]'s table may hold (about 8 MB), so that hostile pages with
+/// thousands of entries on each side can't exhaust memory
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// The entries of `old` and `new` outside their longest common subsequence, as
+/// `(added, removed)`. Entries common to the start and end of both are skipped first; when what
+/// remains would still need a table over [`MAX_DIFF_CELLS`], entries are instead compared as
+/// sets, so that moved entries are no longer reported
+fn sequence_diff(old: &[&str], new: &[&str]) -> (Indexed, Indexed) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let (old_rest, new_rest) = (&old[prefix..], &new[prefix..]);
+    let suffix = old_rest
+        .iter()
+        .rev()
+        .zip(new_rest.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old_rest[..old_rest.len() - suffix];
+    let new_mid = &new_rest[..new_rest.len() - suffix];
+    let offset = |entries: Indexed| entries.into_iter().map(|(i, e)| (i + prefix, e)).collect();
+
+    let cells = (old_mid.len() + 1).saturating_mul(new_mid.len() + 1);
+    let (added, removed) = if cells > MAX_DIFF_CELLS {
+        set_diff(old_mid, new_mid)
+    } else {
+        table_diff(old_mid, new_mid)
+    };
+    (offset(added), offset(removed))
+}
+
+/// The entries of `new` missing from `old`, and of `old` missing from `new`, as
+/// `(added, removed)`
+fn set_diff(old: &[&str], new: &[&str]) -> (Indexed, Indexed) {
+    use std::collections::HashSet;
+
+    let missing = |entries: &[&str], others: &[&str]| {
+        let others = others.iter().collect::<HashSet<_>>();
+        let entries = entries.iter().enumerate();
+        let missing = entries.filter(|(_, e)| !others.contains(e));
+        missing.map(|(i, e)| (i, e.to_string())).collect()
+    };
+    (missing(new, old), missing(old, new))
+}
+
+/// [`sequence_diff`] by a full longest common subsequence table
+fn table_diff(old: &[&str], new: &[&str]) -> (Indexed, Indexed) {
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut added, mut removed) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || common[i][j + 1] >= common[i + 1][j]) {
+            added.push((j, new[j].to_string()));
+            j += 1;
+        } else {
+            removed.push((i, old[i].to_string()));
+            i += 1;
+        }
+    }
+    (added, removed)
+}
+
+/// A recipe alongside the JSON-LD object it was parsed from, as returned by
+/// [`SchemaEntry::scrape_html_with_source`]
+#[derive(Clone, Debug, PartialEq, Accessors)]
+#[access(get)]
+pub struct SourcedRecipe {
+    recipe: Recipe,
+    source: serde_json::Value,
+}
+
+impl SourcedRecipe {
+    pub fn into_parts(self) -> (Recipe, serde_json::Value) {
+        (self.recipe, self.source)
+    }
+}
+
+/// A schema.org recipe object kept exactly as declared, for archiving the source data while
+/// still reading its most common properties. Accessors parse only the property they read, on
+/// each call, and [`as_recipe`](Self::as_recipe) parses the whole [`Recipe`]
+///
+/// ```
+/// use recipe_scraper::RawRecipe;
+///
+/// let raw = RawRecipe::from(serde_json::json!({
+///     "name": "Toast",
+///     "description": "",
+///     "recipeIngredient": ["1 slice bread"],
+///     "nutrition": {"calories": "80 kcal"},
+/// }));
+/// assert_eq!(Some("Toast"), raw.name());
+/// assert_eq!("80 kcal", raw.value()["nutrition"]["calories"]);
+/// assert_eq!("Toast", raw.as_recipe().unwrap().name());
+/// ```
+#[derive(Clone, Debug, PartialEq, Accessors, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct RawRecipe {
+    /// The recipe object, as declared
+    #[access(get)]
+    value: serde_json::Value,
+}
+
+impl From<serde_json::Value> for RawRecipe {
+    fn from(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl RawRecipe {
+    pub fn into_value(self) -> serde_json::Value {
+        self.value
+    }
+
+    /// The recipe's `name`, if it is a string
+    pub fn name(&self) -> Option<&str> {
+        self.value.get("name")?.as_str()
+    }
+
+    /// The ingredient lines, as for [`Recipe::ingredients`]. Empty when there are none, or they
+    /// don't match any known structure
+    pub fn ingredients(&self) -> Vec<String> {
+        let ingredients = self.value.get("recipeIngredient").cloned();
+        ingredients
+            .and_then(|i| serde_json::from_value::<IngredientList>(i).ok())
+            .map(|i| i.entries().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// The steps, as for [`Recipe::directions`], including its alternative spellings. `None`
+    /// when there are none, or they don't match any known structure
+    pub fn instructions(&self) -> Option<InstructionList> {
+        let mut fields = ["recipeInstructions", "steps"]
+            .iter()
+            .filter_map(|&k| Some((k.to_string(), self.value.get(k)?.clone())))
+            .collect::<serde_json::Map<_, _>>();
+        normalize_recipe_keys(&mut fields);
+        serde_json::from_value(fields.remove("recipeInstructions")?).ok()
+    }
+
+    /// Parse the whole object into a [`Recipe`]
+    pub fn as_recipe(&self) -> Result<Recipe, serde_json::Error> {
+        serde_json::from_value(self.value.clone())
+    }
+}
+
+/// A lightweight summary of a recipe listed by an [`ItemList`], such as a roundup page linking
+/// to recipes published elsewhere
+#[derive(Clone, Debug, Eq, PartialEq, Accessors, Deserialize)]
+#[access(get)]
+#[serde(from = "ListItemRepr")]
+pub struct RecipeStub {
+    name: Option<String>,
+    url: Option<String>,
+    /// The URL of the recipe's image, per [`Image::best_url`]
+    image: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StubFields {
+    #[serde(default, deserialize_with = "tolerant")]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "tolerant")]
+    url: Option<String>,
+    #[serde(rename = "@id", default, deserialize_with = "tolerant")]
+    id: Option<String>,
+    #[serde(default, deserialize_with = "tolerant")]
+    image: Option<Image>,
+}
+
+/// A `ListItem`'s `item`: the listed thing itself, or just its URL
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ListedItem {
+    Url(String),
+    Object(StubFields),
+}
+
+#[derive(Deserialize)]
+struct ListItemRepr {
+    #[serde(flatten)]
+    fields: StubFields,
+    #[serde(default, deserialize_with = "tolerant")]
+    item: Option<ListedItem>,
+}
+
+impl From<ListItemRepr> for RecipeStub {
+    /// Properties of the listed `item` take precedence over those of the `ListItem` wrapping it,
+    /// except for the wrapper's `@id`, which usually identifies the list entry rather than the
+    /// recipe
+    fn from(repr: ListItemRepr) -> Self {
+        let list = repr.fields;
+        let (item, item_url) = match repr.item {
+            Some(ListedItem::Object(item)) => (Some(item), None),
+            Some(ListedItem::Url(url)) => (None, Some(url)),
+            None => (None, None),
+        };
+        let item_url = item_url.or_else(|| {
+            let item = item.as_ref()?;
+            item.url.clone().or_else(|| item.id.clone())
+        });
+        let (item_name, item_image) = item.map_or((None, None), |i| (i.name, i.image));
+
+        let image = item_image.or(list.image);
+        Self {
+            name: item_name.or(list.name),
+            url: item_url.or(list.url),
+            image: image.as_ref().and_then(Image::best_url).map(str::to_string),
+        }
+    }
+}
+
+/// A schema.org [ItemList](https://schema.org/ItemList), as used by roundup pages to summarize
+/// the recipes they link to. Its [`extract_recipes`](crate::Extract::extract_recipes) yields a
+/// [`RecipeStub`] per list entry declaring a name or URL, without fetching the linked pages
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct ItemList {
+    #[serde(rename = "itemListElement", deserialize_with = "valid_stubs")]
+    items: Vec<RecipeStub>,
+}
+
+/// Deserialize the entries of an `ItemList`, skipping any which don't name or link to anything
+fn valid_stubs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<RecipeStub>, D::Error> {
+    let values = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<RecipeStub>(v).ok())
+        .filter(|s| s.name.is_some() || s.url.is_some())
+        .collect())
+}
+
+impl ItemList {
+    /// Borrow the list's entries, in the order they were listed
+    pub fn items(&self) -> &[RecipeStub] {
+        &self.items
+    }
+}
+
+impl crate::Extract for ItemList {
+    type Output = RecipeStub;
+    type Collection = Vec<Self::Output>;
+
+    fn extract_recipes(&self) -> Self::Collection {
+        self.items.clone()
+    }
+}
+
+impl crate::Scrape for ItemList {
+    type Output = Self;
+    type Collection = Vec<Self::Output>;
+
+    /// Every `ItemList` declared by the document's `ld+json` script blocks, including within a
+    /// `@graph`
+    fn scrape_html(html: impl AsRef<str>) -> Self::Collection {
+        let html = scraper::Html::parse_document(html.as_ref());
+
+        let mut lists = Vec::new();
+        for mut json in script_bodies(&html)
+            .iter()
+            .flat_map(|body| script_values(clean_script_body(body)))
+        {
+            crate::graph::resolve_references(&mut json, crate::ResolveOptions::default());
+
+            let nodes = recipe_nodes(&json);
+            let nodes = nodes.into_iter().filter(|n| has_type(n, "ItemList"));
+            lists.extend(nodes.filter_map(|n| serde_json::from_value(n.clone()).ok()));
+        }
+        lists
+    }
+}
+
+/// Strip the leading byte-order mark and HTML comment markers which some sites wrap around their
+/// JSON-LD script bodies
+fn clean_script_body(body: &str) -> &str {
+    let body = body.trim().trim_start_matches('\u{feff}').trim_start();
+    let body = body.strip_prefix("<!--").unwrap_or(body);
+    let body = body.trim_end();
+    body.strip_suffix("-->").unwrap_or(body).trim()
+}
+
+impl crate::Extract for SchemaEntry {
+    type Output = Recipe;
+    type Collection = Vec<Self::Output>;
+
+    /// Recipes are always returned in the order they appear in the source document. Recipes
+    /// sharing an `@id` (such as one declared both in a `@graph` and at the top level) are
+    /// returned once, in place of the first, with any [missing](Recipe::merge) fields filled in
+    /// from the later ones. Recipes without an `@id` are all kept
+    fn extract_recipes(&self) -> Self::Collection {
+        let mut recipes: Vec<Recipe> = Vec::new();
+        for recipe in self.recipes() {
+            let existing = recipe
+                .id
+                .as_ref()
+                .and_then(|id| recipes.iter_mut().find(|r| r.id.as_ref() == Some(id)));
+            match existing {
+                Some(existing) => *existing = existing.clone().merge(recipe.clone()),
+                None => recipes.push(recipe.clone()),
+            }
+        }
+        recipes
+    }
+
+    /// Selects, in order of preference: the first recipe declaring `mainEntityOfPage`, the most
+    /// complete recipe (per [`Recipe::completeness`]) or, on a tie, the first recipe. Recipes
+    /// are chosen from those of [`extract_recipes`](crate::Extract::extract_recipes), so that
+    /// recipes sharing an `@id` are judged merged rather than by their fragments
+    fn primary_recipe(&self) -> Option<Self::Output> {
+        let recipes = self.extract_recipes();
+        let main = recipes.iter().find(|r| r.is_main_entity_of_page());
+        let best = || {
+            recipes
+                .iter()
+                .fold(None, |best: Option<&Recipe>, r| match best {
+                    Some(b) if b.completeness() >= r.completeness() => Some(b),
+                    _ => Some(r),
+                })
+        };
+
+        main.or_else(best).cloned()
+    }
+}
+
+impl std::str::FromStr for SchemaEntry {
+    type Err = crate::ScrapeError;
+
+    /// Parse a JSON-LD document, as by [`from_json_str`](Self::from_json_str)
+    ///
+    /// ```
+    /// use recipe_scraper::{Extract, SchemaOrgEntry};
+    ///
+    /// let json = r#"{"name": "Toast", "description": "", "recipeIngredient": "1 slice bread"}"#;
+    /// let entry: SchemaOrgEntry = json.parse()?;
+    /// assert_eq!("Toast", entry.extract_recipes()[0].name());
+    /// # Ok::<(), recipe_scraper::ScrapeError>(())
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_json_str(s)?)
+    }
+}
+
+impl crate::Scrape for SchemaEntry {
+    type Output = Self;
+    type Collection = Vec<Self::Output>;
+
+    fn scrape_html(html: impl AsRef<str>) -> Self::Collection {
+        Self::scrape_html_with_stats(html).0
+    }
+}
+
+impl SchemaEntry {
+    /// Borrow the recipes contained in this entry (in document order), rather than cloning them
+    /// as [`Extract::extract_recipes`](crate::Extract::extract_recipes) does
+    pub fn recipes(&self) -> Vec<&Recipe> {
+        match self {
+            Self::Graph { graph } => graph.iter().flat_map(GraphEntry::recipes).collect(),
+            Self::Single(r) => r.recipes(),
+            Self::Multi(entries) => entries.iter().flat_map(SchemaItem::recipes).collect(),
+        }
+    }
+
+    /// Like [`Scrape::scrape_html`](crate::Scrape::scrape_html), but resolves relative image and
+    /// recipe URLs against `base_url` (the address the page was fetched from). Absolute URLs are
+    /// left unchanged, as are all URLs if `base_url` is not itself an absolute URL
+    pub fn scrape_html_with_base_url(html: impl AsRef<str>, base_url: &str) -> Vec<Self> {
+        let mut entries = <Self as crate::Scrape>::scrape_html(html);
+        entries.iter_mut().for_each(|entry| {
+            entry
+                .recipes_mut()
+                .into_iter()
+                .for_each(|r| r.resolve_urls(base_url))
+        });
+        entries
+    }
+
+    /// Consume the entry, moving its recipes out without cloning them
+    pub fn into_recipes(self) -> Vec<Recipe> {
+        match self {
+            Self::Graph { graph } => graph
+                .into_iter()
+                .flat_map(GraphEntry::into_recipes)
+                .collect(),
+            Self::Single(r) => r.into_recipes(),
+            Self::Multi(entries) => entries
+                .into_iter()
+                .flat_map(SchemaItem::into_recipes)
+                .collect(),
+        }
+    }
+
+    fn recipes_mut(&mut self) -> Vec<&mut Recipe> {
+        match self {
+            Self::Graph { graph } => graph.iter_mut().flat_map(GraphEntry::recipes_mut).collect(),
+            Self::Single(r) => r.recipes_mut(),
+            Self::Multi(entries) => entries.iter_mut().flat_map(|e| e.recipes_mut()).collect(),
+        }
+    }
+
+    /// Like [`Scrape::scrape_html`](crate::Scrape::scrape_html), but for a page's raw bytes,
+    /// which are decoded according to the charset declared by `content_type` (the response's
+    /// `Content-Type` header, when known), a byte-order mark or the page's `<meta>` tags. UTF-8,
+    /// UTF-16 and windows-1252 (including `iso-8859-1`) are supported; other encodings are decoded
+    /// as UTF-8
+    pub fn scrape_bytes(bytes: &[u8], content_type: Option<&str>) -> Vec<Self> {
+        <Self as crate::Scrape>::scrape_html(crate::charset::decode(bytes, content_type))
+    }
+
+    /// Like [`Scrape::scrape_html`](crate::Scrape::scrape_html), but also reports how many
+    /// `ld+json` script blocks were seen, parsed and turned into recipes along the way
+    pub fn scrape_html_with_stats(html: impl AsRef<str>) -> (Vec<Self>, crate::ScrapeStats) {
+        Self::scrape_document(&scraper::Html::parse_document(html.as_ref()))
+    }
+
+    /// Scrape the `ld+json` script blocks of an already-parsed document
+    pub(crate) fn scrape_document(html: &scraper::Html) -> (Vec<Self>, crate::ScrapeStats) {
+        Self::scrape_document_with(html, &[], crate::ResolveOptions::default(), false)
+    }
+
+    /// Every recipe in an already-parsed document's `ld+json` script blocks, as by
+    /// [`scrape_document`](Self::scrape_document), but without parsing the blocks which can't
+    /// hold one
+    pub(crate) fn scrape_document_recipes(html: &scraper::Html) -> Vec<Recipe> {
+        let options = crate::ResolveOptions::default();
+        let (entries, _) = Self::scrape_document_with(html, &[], options, true);
+        entries.into_iter().flat_map(Self::into_recipes).collect()
+    }
+
+    /// Like [`Scrape::scrape_html`](crate::Scrape::scrape_html), but running each of the
+    /// [`PreProcessor`](crate::PreProcessor)s over every script block's JSON first
+    pub fn scrape_html_with_preprocessors(
+        html: impl AsRef<str>,
+        preprocessors: &[&dyn crate::PreProcessor],
+    ) -> Vec<Self> {
+        let document = scraper::Html::parse_document(html.as_ref());
+        let options = crate::ResolveOptions::default();
+        Self::scrape_document_with(&document, preprocessors, options, false).0
+    }
+
+    /// Like [`Scrape::scrape_html`](crate::Scrape::scrape_html), but resolving each script
+    /// block's references with the given [`ResolveOptions`](crate::ResolveOptions) rather than
+    /// the defaults. With [`breadcrumbs_as_category`](crate::ResolveOptions::breadcrumbs_as_category),
+    /// the page's first `BreadcrumbList` categorizes recipes in any of its blocks
+    pub fn scrape_html_with_options(
+        html: impl AsRef<str>,
+        options: crate::ResolveOptions,
+    ) -> Vec<Self> {
+        let document = scraper::Html::parse_document(html.as_ref());
+        Self::scrape_document_with(&document, &[], options, false).0
+    }
+
+    /// Scrape the document's script blocks, running `preprocessors` over each and resolving
+    /// them with `options`. With `recipes_only`, blocks which can't hold a recipe are skipped
+    /// unparsed, so that only the returned recipes (not the other entries, nor the stats) are as
+    /// without it
+    fn scrape_document_with(
+        html: &scraper::Html,
+        preprocessors: &[&dyn crate::PreProcessor],
+        options: crate::ResolveOptions,
+        recipes_only: bool,
+    ) -> (Vec<Self>, crate::ScrapeStats) {
+        let bodies = script_bodies(html);
+        let mut stats = crate::ScrapeStats {
+            scripts_found: bodies.len(),
+            ..Default::default()
+        };
+
+        // Preprocessors may add recipes to any block, and breadcrumbs are needed from blocks
+        // without any, so none can be skipped for either
+        let skip = |body: &&Cow<'_, str>| {
+            recipes_only
+                && preprocessors.is_empty()
+                && !options.breadcrumbs_as_category
+                && !may_hold_recipe(body)
+        };
+        let mut values = bodies
+            .iter()
+            .filter(|body| !skip(body))
+            .flat_map(|body| script_values(clean_script_body(body)))
+            .collect::<Vec<_>>();
+        for value in values.iter_mut() {
+            preprocessors.iter().for_each(|p| p.fix(value));
+        }
+        crate::graph::merge_duplicate_nodes(&mut values);
+        if options.breadcrumbs_as_category {
+            crate::graph::categorize_from_breadcrumbs(&mut values);
+        }
+        // Breadcrumbs have been applied page-wide, so aren't applied again to each block
+        let block_options = crate::ResolveOptions {
+            breadcrumbs_as_category: false,
+            ..options
+        };
+
+        let mut entries = values
+            .into_iter()
+            .filter_map(|json| Self::from_json_value_with_options(json, block_options).ok())
+            .inspect(|entry| {
+                stats.scripts_parsed += 1;
+                stats.recipes_extracted += entry.recipes().len();
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(site_name) = site_name(html) {
+            for recipe in entries.iter_mut().flat_map(Self::recipes_mut) {
+                recipe.site_name = Some(site_name.clone());
+            }
+        }
+
+        (entries, stats)
+    }
+
+    /// Like [`Scrape::scrape_html`](crate::Scrape::scrape_html), but returns each recipe
+    /// alongside the JSON object it was parsed from (after `@id` references are resolved), for
+    /// comparing what was modeled against what the page declared
+    pub fn scrape_html_with_source(html: impl AsRef<str>) -> Vec<SourcedRecipe> {
+        let html = scraper::Html::parse_document(html.as_ref());
+        let site_name = site_name(&html);
+
+        let mut values = script_bodies(&html)
+            .iter()
+            .filter(|body| may_hold_recipe(body))
+            .flat_map(|body| script_values(clean_script_body(body)))
+            .collect::<Vec<_>>();
+        crate::graph::merge_duplicate_nodes(&mut values);
+
+        let mut sourced = Vec::new();
+        for mut json in values {
+            crate::graph::resolve_references(&mut json, crate::ResolveOptions::default());
+            let entry = match serde_json::from_value::<Self>(json.clone()) {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+
+            // The entry decides which recipes there are, and in what order, so that these are
+            // exactly those of `scrape_html`: each is then matched to the node it was parsed from
+            let mut candidates = candidate_recipe_nodes(&json)
+                .into_iter()
+                .filter_map(|node| {
+                    Some((serde_json::from_value::<Recipe>(node.clone()).ok()?, node))
+                })
+                .collect::<Vec<_>>();
+            let mut entry_sourced: Vec<SourcedRecipe> = Vec::new();
+            for mut recipe in entry.into_recipes() {
+                let source = match candidates.iter().position(|(r, _)| *r == recipe) {
+                    Some(idx) => candidates.remove(idx).1.clone(),
+                    None => continue,
+                };
+                recipe.site_name = site_name.clone();
+
+                // As for `extract_recipes`, recipes sharing an `@id` are returned once
+                let existing = recipe.id.as_ref().and_then(|id| {
+                    entry_sourced
+                        .iter_mut()
+                        .find(|s| s.recipe.id.as_ref() == Some(id))
+                });
+                match existing {
+                    Some(existing) => existing.recipe = existing.recipe.clone().merge(recipe),
+                    None => entry_sourced.push(SourcedRecipe { recipe, source }),
+                }
+            }
+            sourced.extend(entry_sourced);
+        }
+        sourced
+    }
+
+    /// Every recipe in the document keyed by its declared language (`inLanguage`, such as "en" or
+    /// "fr-CA"), for sites embedding translations of a recipe in separate blocks. Recipes which
+    /// don't declare a language are keyed by an empty string
+    pub fn scrape_html_by_language(
+        html: impl AsRef<str>,
+    ) -> std::collections::HashMap<String, Vec<Recipe>> {
+        use serde_json::Value;
+
+        let mut by_language = std::collections::HashMap::new();
+        for sourced in Self::scrape_html_with_source(html) {
+            let language = match &sourced.source["inLanguage"] {
+                Value::String(lang) => Some(lang.as_str()),
+                // A schema.org Language, or a JSON-LD value object
+                Value::Object(o) => ["alternateName", "name", "@value"]
+                    .iter()
+                    .find_map(|k| o.get(*k).and_then(Value::as_str)),
+                _ => None,
+            };
+            let language = language.unwrap_or_default().trim().to_string();
+            let (recipe, _) = sourced.into_parts();
+            by_language
+                .entry(language)
+                .or_insert_with(Vec::new)
+                .push(recipe);
+        }
+        by_language
+    }
+
+    pub fn from_json_bytes(b: impl AsRef<[u8]>) -> Result<Self, serde_json::Error> {
+        Self::from_json_value(serde_json::from_slice(b.as_ref())?)
+    }
+
+    pub fn from_json_reader(r: impl std::io::Read) -> Result<Self, serde_json::Error> {
+        Self::from_json_value(serde_json::from_reader(r)?)
+    }
+
+    pub fn from_json_str(json: impl AsRef<str>) -> Result<Self, serde_json::Error> {
+        Self::from_json_value(serde_json::from_str(json.as_ref())?)
+    }
+
+    /// Parse JSON which may contain trailing commas or single-quoted strings, as commonly
+    /// produced by broken templates. Strict parsing is attempted first, and this may accept input
+    /// which is not standard JSON
+    #[cfg(feature = "lenient")]
+    pub fn from_json_str_lenient(json: impl AsRef<str>) -> Result<Self, serde_json::Error> {
+        let json = json.as_ref();
+        Self::from_json_str(json)
+            .or_else(|e| Self::from_json_str(crate::lenient::relax(json)).map_err(|_| e))
+    }
+
+    /// Parse a JSON value, first resolving `@id` node references with the default
+    /// [`ResolveOptions`](crate::ResolveOptions). All other constructors share this behavior
+    pub fn from_json_value(json: serde_json::Value) -> Result<Self, serde_json::Error> {
+        Self::from_json_value_with_options(json, crate::ResolveOptions::default())
+    }
+
+    /// Like [`from_json_value`](Self::from_json_value), but running each of the
+    /// [`PreProcessor`](crate::PreProcessor)s over `json` first
+    pub fn from_json_value_with_preprocessors(
+        mut json: serde_json::Value,
+        preprocessors: &[&dyn crate::PreProcessor],
+    ) -> Result<Self, serde_json::Error> {
+        preprocessors.iter().for_each(|p| p.fix(&mut json));
+        Self::from_json_value(json)
+    }
+
+    /// Parse a JSON value, resolving `@id` node references within the given limits
+    pub fn from_json_value_with_options(
+        mut json: serde_json::Value,
+        options: crate::ResolveOptions,
+    ) -> Result<Self, serde_json::Error> {
+        crate::graph::resolve_references(&mut json, options);
+        serde_json::from_value(json)
+    }
+
+    /// Every object nested anywhere within `json` whose `@type` is `Recipe`, in document order,
+    /// each parsed as a [`TolerantRecipe`] so that none is lost to a single malformed property.
+    /// `@id` node references are resolved first, as by [`from_json_value`](Self::from_json_value)
+    pub fn extract_recipes_tolerant(mut json: serde_json::Value) -> Vec<Recipe> {
+        crate::graph::resolve_references(&mut json, crate::ResolveOptions::default());
+        find_all_recipes(&json)
+            .into_iter()
+            .filter_map(|node| serde_json::from_value::<TolerantRecipe>(node.clone()).ok())
+            .map(TolerantRecipe::into_recipe)
+            .collect()
+    }
+}
+
+/// Back-schedule a meal: when to start each recipe so that all of them are ready at `serve_at`,
+/// alongside the recipe's name, in the order given. Each recipe takes its
+/// [`total_time_or_estimate`](Recipe::total_time_or_estimate), so recipes without times are
+/// given a step-based estimate
+///
+/// Any point in time which a [`std::time::Duration`] can be subtracted from will do, such as
+/// [`SystemTime`](std::time::SystemTime) or a date-time type of a calendar crate
+pub fn plan_timeline<T>(recipes: &[Recipe], serve_at: T) -> Vec<(String, T)>
+where
+    T: Copy + std::ops::Sub<std::time::Duration, Output = T>,
+{
+    recipes
+        .iter()
+        .map(|r| (r.name.clone(), serve_at - r.total_time_or_estimate()))
+        .collect()
+}
+
+/// The best version of each distinct recipe among `recipes` (e.g. the same recipe aggregated
+/// from several sources), in the order each was first seen. Versions are the same recipe when
+/// their [`content_hash`](Recipe::content_hash)es match, and the best is the most
+/// [complete](Recipe::completeness), with ties going to whichever came first. Gaps in the best
+/// version are then [filled](Recipe::merge) from the others, most complete first
+pub fn dedup_best(recipes: Vec<Recipe>) -> Vec<Recipe> {
+    let mut groups: Vec<(u64, Vec<Recipe>)> = Vec::new();
+    for recipe in recipes {
+        let hash = recipe.content_hash();
+        match groups.iter_mut().find(|(h, _)| *h == hash) {
+            Some((_, versions)) => versions.push(recipe),
+            None => groups.push((hash, vec![recipe])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter_map(|(_, mut versions)| {
+            // Stable, so equally complete versions keep their order
+            versions.sort_by(|a, b| {
+                let (a, b) = (a.completeness(), b.completeness());
+                b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            let mut versions = versions.into_iter();
+            let best = versions.next()?;
+            Some(versions.fold(best, Recipe::merge))
+        })
+        .collect()
+}
+
+/// The single best recipe among `recipes`: the most [complete](Recipe::completeness) of those
+/// returned by [`dedup_best`], with ties going to whichever was seen first
+pub fn pick_best(recipes: Vec<Recipe>) -> Option<Recipe> {
+    dedup_best(recipes)
+        .into_iter()
+        .fold(None, |best, recipe| match best {
+            Some(best) if best.completeness() >= recipe.completeness() => Some(best),
+            _ => Some(recipe),
+        })
+}
+
+/// Lazily parse newline-delimited JSON, where each (non-blank) line holds a recipe or schema
+/// entry. Lines which fail to parse yield an error without ending the iteration, and lines whose
+/// entry contains several recipes yield each of them in turn
+pub fn recipes_from_ndjson_reader(
+    r: impl std::io::BufRead,
+) -> impl Iterator<Item = Result<Recipe, crate::ScrapeError>> {
+    r.lines().flat_map(|line| {
+        let recipes = match line {
+            Ok(line) if line.trim().is_empty() => Vec::new(),
+            Ok(line) => match SchemaEntry::from_json_str(line) {
+                Ok(entry) => entry.into_recipes().into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e.into())],
+            },
+            Err(e) => vec![Err(e.into())],
+        };
+        recipes
+    })
+}
+
+/// Every `@type` declared anywhere within the document's `ld+json` blocks (such as "Recipe",
+/// "Article" or "BreadcrumbList"), at any depth, in the order they are first encountered and
+/// without duplicates. Useful for seeing what a page is made of, or why no recipe was found on it
+pub fn list_schema_types(html: &str) -> Vec<String> {
+    use serde_json::Value;
+
+    fn collect(json: &Value, types: &mut Vec<String>) {
+        match json {
+            Value::Array(items) => items.iter().for_each(|i| collect(i, types)),
+            Value::Object(o) => {
+                let declared = match o.get("@type") {
+                    Some(Value::String(t)) => vec![t.as_str()],
+                    Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+                    _ => Vec::new(),
+                };
+                for ty in declared {
+                    if !types.iter().any(|t| t == ty) {
+                        types.push(ty.to_string());
+                    }
+                }
+                o.values().for_each(|v| collect(v, types));
+            }
+            _ => {}
+        }
+    }
+
+    let document = scraper::Html::parse_document(html);
+    let mut types = Vec::new();
+    for body in script_bodies(&document) {
+        for value in script_values(clean_script_body(&body)) {
+            collect(&value, &mut types);
+        }
+    }
+    types
+}
+
+/// A cheap check for whether `html` likely contains a JSON-LD recipe, for prioritizing pages
+/// before scraping them in full. Nothing is parsed: the document only needs to mention
+/// `application/ld+json` and have a `"@type"` whose value mentions `Recipe`, so false positives
+/// are possible (and recipes which omit `@type` are missed)
+pub fn has_recipe(html: &str) -> bool {
+    // Long enough to cover a `@type` array listing a few other types ahead of `Recipe`
+    const TYPE_WINDOW: usize = 96;
+
+    if !html.contains("application/ld+json") {
+        return false;
+    }
+
+    html.match_indices("\"@type\"").any(|(idx, m)| {
+        let value = &html[idx + m.len()..];
+        let end = value
+            .char_indices()
+            .map(|(i, _)| i)
+            .find(|&i| i >= TYPE_WINDOW)
+            .unwrap_or(value.len());
+        let value = value[..end]
+            .trim_start()
+            .trim_start_matches(':')
+            .trim_start();
+        let value = match value.strip_prefix('[') {
+            Some(types) => &types[..types.find(']').unwrap_or(types.len())],
+            None => &value[..value.find([',', '}']).unwrap_or(value.len())],
+        };
+        value.contains("Recipe")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A minimal valid recipe, with `extra` merged over its fields
+    fn recipe_json(extra: serde_json::Value) -> serde_json::Value {
+        let mut value = json!({"name": "A recipe", "description": "", "recipeIngredient": ""});
+        let extra = extra.as_object().cloned().unwrap_or_default();
+        value.as_object_mut().unwrap().extend(extra);
+        value
+    }
+
+    fn recipe(extra: serde_json::Value) -> Recipe {
+        serde_json::from_value(recipe_json(extra)).unwrap()
+    }
+
+    #[test]
+    fn test_maybe_duration() {
+        assert_eq!(
+            MaybeDuration::from_secs(2i8),
+            serde_json::from_value(json!("PT2S")).unwrap()
+        );
+
+        assert_eq!(
+            MaybeDuration(None, None),
+            serde_json::from_value(json!("PTnullH")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_maybe_duration_round_trip() {
+        let duration: MaybeDuration = serde_json::from_value(json!("PT0H30M")).unwrap();
+        assert_eq!(Some("PT0H30M"), duration.original());
+        assert_eq!(json!("PT0H30M"), serde_json::to_value(&duration).unwrap());
+        assert_eq!(
+            json!("PT30M"),
+            serde_json::to_value(duration.normalized()).unwrap()
+        );
+
+        let components: MaybeDuration = serde_json::from_value(json!({"minutes": 30})).unwrap();
+        assert_eq!(None, components.original());
+        assert_eq!(duration, components);
+        assert_eq!(json!("PT30M"), serde_json::to_value(components).unwrap());
+        let invalid: MaybeDuration = serde_json::from_value(json!("PT30X")).unwrap();
+        assert_eq!(None, invalid.duration());
+        assert_eq!(Some("PT30X"), invalid.original());
+        assert_eq!(json!("PT30X"), serde_json::to_value(&invalid).unwrap());
+        assert_eq!(
+            json!(null),
+            serde_json::to_value(invalid.normalized()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_maybe_duration_fractional() {
+        let duration = |iso| serde_json::from_value::<MaybeDuration>(json!(iso)).unwrap();
+
+        assert_eq!(Some(30), duration("PT0.5H").total_minutes());
+        assert_eq!(Some((0, 30, 0)), duration("PT0.5H").hms());
+        assert_eq!(Some(90), duration("PT1,5H").total_minutes());
+        assert_eq!(Some((0, 2, 30)), duration("PT2.5M").hms());
+        assert_eq!(Some(36 * 60), duration("P1.5D").total_minutes());
+        assert_eq!(
+            Some("30 minutes".to_string()),
+            duration("PT0.5H").human_readable_lang("en")
+        );
+        assert_eq!(
+            json!("PT0.5H"),
+            serde_json::to_value(duration("PT0.5H")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_human_readable_lang() {
+        let duration = |iso| serde_json::from_value::<MaybeDuration>(json!(iso)).unwrap();
+        let half_hour = duration("PT30M");
+        assert_eq!(
+            Some("30 Minuten".to_string()),
+            half_hour.human_readable_lang("de")
+        );
+        assert_eq!(
+            Some("30 minutos".to_string()),
+            half_hour.human_readable_lang("es-MX")
+        );
+        assert_eq!(
+            Some("30 minutes".to_string()),
+            half_hour.human_readable_lang("xx")
+        );
+        assert_eq!(
+            Some("1 heure 15 minutes".to_string()),
+            duration("PT1H15M").human_readable_lang("FR")
+        );
+        assert_eq!(None, MaybeDuration(None, None).human_readable_lang("de"));
+    }
+
+    #[test]
+    fn test_maybe_duration_components() {
+        assert_eq!(
+            MaybeDuration(Some(Duration::new(0., 0., 0., 0., 30., 0.)), None),
+            serde_json::from_value(json!({"minutes": 30})).unwrap()
+        );
+
+        assert_eq!(
+            MaybeDuration(Some(Duration::new(0., 0., 0., 1., 15., 0.)), None),
+            serde_json::from_value(json!({"@type": "Duration", "hours": 1, "minutes": 15}))
+                .unwrap()
+        );
+
+        assert_eq!(
+            MaybeDuration(None, None),
+            serde_json::from_value(json!({"@type": "Duration"})).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_any_time() {
+        let any_time = |extra| {
+            serde_json::from_value::<Recipe>(recipe_json(extra))
+                .unwrap()
+                .any_time()
+                .and_then(MaybeDuration::total_minutes)
+        };
+
+        assert_eq!(
+            Some(45),
+            any_time(json!({"prepTime": "PT10M", "totalTime": "PT45M"}))
+        );
+        assert_eq!(
+            Some(10),
+            any_time(json!({"prepTime": "PT10M", "totalTime": ""}))
+        );
+        assert_eq!(None, any_time(json!({})));
+    }
+
+    #[test]
+    fn test_duration_try_parse() {
+        use crate::DurationError;
+
+        assert_eq!(
+            Ok(MaybeDuration::from_secs(90u8)),
+            MaybeDuration::try_parse(" PT90S ")
+        );
+        assert_eq!(Err(DurationError::Empty), MaybeDuration::try_parse(""));
+        assert_eq!(
+            Err(DurationError::MissingDesignator {
+                input: "30 minutes".into()
+            }),
+            MaybeDuration::try_parse("30 minutes")
+        );
+
+        let err = MaybeDuration::try_parse("PT30X").unwrap_err();
+        assert!(matches!(err, DurationError::Invalid { .. }));
+        assert!(err.to_string().starts_with("invalid duration \"PT30X\""));
+    }
+
+    #[test]
+    fn test_hms() {
+        let hms = |v| serde_json::from_value::<MaybeDuration>(v).unwrap().hms();
+        assert_eq!(Some((1, 30, 0)), hms(json!("PT1H30M")));
+        assert_eq!(Some((0, 45, 0)), hms(json!("PT45M")));
+        assert_eq!(Some((1, 30, 5)), hms(json!("PT90M5S")));
+        assert_eq!(Some((26, 0, 0)), hms(json!("P1DT2H")));
+        assert_eq!(None, hms(json!("P1M")));
+        assert_eq!(None, hms(json!("")));
+    }
+
+    #[test]
+    fn test_total_minutes() {
+        let minutes = |v| {
+            serde_json::from_value::<MaybeDuration>(v)
+                .unwrap()
+                .total_minutes()
+        };
+        assert_eq!(Some(90), minutes(json!("PT1H30M")));
+        assert_eq!(Some(2), minutes(json!("PT1M31S")));
+        assert_eq!(Some(1440), minutes(json!("P1D")));
+        assert_eq!(None, minutes(json!("P1M")));
+        assert_eq!(None, minutes(json!("")));
+
+        let recipe: Recipe = serde_json::from_value(json!({
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": "An ingredient",
+            "prepTime": "PT10M",
+            "totalTime": "PT45M"
+        }))
+        .unwrap();
+        assert_eq!(
+            (Some(45), Some(10), None),
+            (
+                recipe.total_minutes(),
+                recipe.prep_minutes(),
+                recipe.cook_minutes()
+            )
+        );
+    }
+
+    #[test]
+    fn test_ingredient_list_single() {
+        let data = json!("The first ingredient.\nThe second one.");
+        let result = serde_json::from_value(data);
+        assert!(result.is_ok());
+        assert_eq!(
+            IngredientList::single("The first ingredient.\nThe second one."),
+            result.unwrap()
+        )
+    }
+
+    #[test]
+    fn test_ingredient_list_multi() {
+        let data = json!(["The first ingredient", "The second one"]);
+        let result = serde_json::from_value(data);
+        assert!(result.is_ok());
+        assert_eq!(
+            IngredientList::multi(["The first ingredient", "The second one"]),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ingredient_list_eq_unordered() {
+        let list = IngredientList::multi(["Flour", "Sugar", "Sugar"]);
+        assert!(list.eq_unordered(&IngredientList::multi(["Sugar", "Flour", "Sugar"])));
+        assert!(list.eq_unordered(&IngredientList::single("Sugar\nSugar\nFlour")));
+        assert!(!list.eq_unordered(&IngredientList::multi(["Sugar", "Flour"])));
+        assert_ne!(list, IngredientList::multi(["Sugar", "Flour", "Sugar"]));
+    }
+
+    #[test]
+    fn test_instruction_simple() {
+        let data = json!("Do a thing");
+        let result = serde_json::from_value(data);
+        assert!(result.is_ok());
+        assert_eq!(Instruction::simple("Do a thing"), result.unwrap());
+    }
+
+    #[test]
+    fn test_instruction_structured() {
+        let data = json!({"name": "Do a thing", "text": "Do a thing"});
+        let result = serde_json::from_value(data);
+        assert!(result.is_ok());
+        assert_eq!(Instruction::structured("Do a thing"), result.unwrap());
+    }
+
+    #[test]
+    fn test_instruction_looks_like_header() {
+        let header = |text| Instruction::simple(text).looks_like_header();
+        assert!(header("For the sauce:"));
+        assert!(header(" Assembly : "));
+        assert!(!header("For the sauce"));
+        assert!(!header(":"));
+        assert!(!header(
+            "Whisk together the following until smooth and glossy:"
+        ));
+        assert!(!header("Preheat the oven. Then add:"));
+    }
+
+    #[test]
+    fn test_instruction_image_urls() {
+        let images = |image| {
+            let step = json!({"@type": "HowToStep", "text": "Fold", "image": image});
+            let step = serde_json::from_value::<Instruction>(step).unwrap();
+            step.image_urls()
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["before.jpg", "after.jpg"],
+            images(json!([
+                {"@type": "ImageObject", "url": "before.jpg"},
+                {"@type": "ImageObject", "contentUrl": "after.jpg"},
+            ]))
+        );
+        assert_eq!(
+            vec!["before.jpg", "after.jpg"],
+            images(json!(["before.jpg", "after.jpg"]))
+        );
+        assert_eq!(vec!["step.jpg"], images(json!("step.jpg")));
+    }
+
+    #[test]
+    fn test_instruction_name_only() {
+        let data = json!({"@type": "HowToDirection", "name": "Do a thing"});
+        assert_eq!(
+            Instruction::structured("Do a thing"),
+            serde_json::from_value(data).unwrap()
+        );
+
+        let data = json!({"name": "Prep the thing", "itemListElement": ["Do a thing"]});
+        assert!(serde_json::from_value::<Instruction>(data).is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_instruction_list_directions() {
+        let data = json!([
+            {"name": "Prep", "itemListElement": ["Chop", "Mix"]},
+            {"name": "Cook", "itemListElement": [{"text": "Fry"}]},
+        ]);
+        let list: InstructionList = serde_json::from_value(data).unwrap();
+
+        assert_eq!(
+            Some(vec![
+                &Instruction::simple("Chop"),
+                &Instruction::simple("Mix"),
+                &Instruction::structured("Fry"),
+            ]),
+            list.directions()
+        );
+        assert_eq!(2, list.sections().unwrap().count());
+    }
+
+    #[test]
+    fn test_instruction_positions() {
+        let steps = |data| {
+            let list: InstructionList = serde_json::from_value(data).unwrap();
+            let steps = list.steps();
+            steps
+                .iter()
+                .map(|s| s.text().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["First", "Second", "Third"],
+            steps(json!([
+                {"text": "Third", "position": 3},
+                {"text": "First", "position": "1"},
+                {"text": "Second", "position": 2},
+            ]))
+        );
+        assert_eq!(
+            vec!["First", "Second"],
+            steps(json!([{"name": "Section", "itemListElement": [
+                {"text": "Second", "position": 2},
+                {"text": "First", "position": 1},
+            ]}]))
+        );
+        assert_eq!(
+            vec!["B", "A", "C"],
+            steps(json!([
+                {"text": "B", "position": 1},
+                {"text": "A", "position": 1},
+                {"text": "C"},
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_instruction_round_trip() {
+        let data = json!({
+            "@type": "HowToStep",
+            "position": 1,
+            "text": "Do a thing",
+            "x-plugin": {"timer": 60},
+        });
+        let instruction: Instruction = serde_json::from_value(data.clone()).unwrap();
+        assert_eq!(data, serde_json::to_value(&instruction).unwrap());
+
+        let data = json!("Do a thing");
+        let instruction: Instruction = serde_json::from_value(data.clone()).unwrap();
+        assert_eq!(data, serde_json::to_value(&instruction).unwrap());
+    }
+
+    #[test]
+    fn test_instruction_section() {
+        let data = json!({"name": "Prep the thing", "itemListElement": [{"name": "Do a thing", "text": "Do a thing"}]});
+        let result = serde_json::from_value(data);
+        assert!(result.is_ok());
+        assert_eq!(
+            InstructionSection::new("Prep the thing", [Instruction::structured("Do a thing")]),
+            result.unwrap()
+        );
+
+        let data = json!({
+            "name": "Dough",
+            "description": "In this stage we prep the dough",
+            "itemListElement": ["Knead"],
+        });
+        let section: InstructionSection = serde_json::from_value(data).unwrap();
+        assert_eq!(
+            Some("In this stage we prep the dough"),
+            section.description().as_deref()
+        );
+        assert_eq!(
+            vec!["Knead"],
+            section
+                .into_iter()
+                .map(|s| s.text().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_instruction_list_single() {
+        assert_eq!(
+            InstructionList::Single(Instruction::simple("Do a thing")),
+            serde_json::from_value(json!("Do a thing")).unwrap(),
+        );
+
+        assert_eq!(
+            InstructionList::Single(Instruction::structured("Do a thing")),
+            serde_json::from_value(json!({"name": "Do a thing", "text": "Do a thing"})).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_instruction_list_multi() {
+        assert_eq!(
+            InstructionList::Multi(vec![
+                Instruction::simple("Do a thing"),
+                Instruction::simple("Do another thing")
+            ]),
+            serde_json::from_value(json!(["Do a thing", "Do another thing"])).unwrap(),
+        );
+
+        assert_eq!(
+            InstructionList::Multi(vec![
+                Instruction::structured("Do a thing"),
+                Instruction::structured("Do another thing"),
+            ]),
+            serde_json::from_value(json!([
+                    {"name": "Do a thing", "text": "Do a thing"},
+                    {"name": "Do another thing", "text": "Do another thing"}
+            ]))
+            .unwrap()
+        );
+
+        assert_eq!(
+            InstructionList::Multi(vec![
+                Instruction::simple("Do a thing"),
+                Instruction::structured("Do another thing")
+            ]),
+            serde_json::from_value(json!([
+                    "Do a thing",
+                    {"name": "Do another thing", "text": "Do another thing"}
+            ]))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_instruction_list_as_sections() {
+        let list: InstructionList = serde_json::from_value(json!(["Mix", "Bake"])).unwrap();
+        assert_eq!(
+            vec![InstructionSection::new(
+                "",
+                [Instruction::simple("Mix"), Instruction::simple("Bake")]
+            )],
+            list.as_sections()
+        );
+
+        let list: InstructionList = serde_json::from_value(json!([
+            {"name": "Prep", "itemListElement": ["Mix"]},
+            {"name": "Cook", "itemListElement": ["Bake"]},
+        ]))
+        .unwrap();
+        assert_eq!(
+            vec!["Prep", "Cook"],
+            list.as_sections()
+                .iter()
+                .map(|s| s.name().as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_instruction_list_sections() {
+        assert_eq!(
+            InstructionList::Sections(vec![
+                InstructionSection::new(
+                    "Prep the thing",
+                    [Instruction::structured("Do the thing")]
+                ),
+                InstructionSection::new(
+                    "Cook the thing",
+                    [Instruction::structured("Do the other thing")]
+                )
+            ]),
+            serde_json::from_value(json!([
+                {"name": "Prep the thing", "itemListElement": [{"name": "Do the thing", "text": "Do the thing"}]},
+                {"name": "Cook the thing", "itemListElement": [{"name": "Do the other thing", "text": "Do the other thing"}]},
+            ]))
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_quantity() {
+        assert_eq!(
+            Quantity::number(2),
+            serde_json::from_value(json!(2)).unwrap()
+        );
+
+        assert_eq!(
+            Quantity::string("2 cups"),
+            serde_json::from_value(json!("2 cups")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_quantity_as_f64() {
+        let as_f64 = |q| serde_json::from_value::<Quantity>(q).unwrap().as_f64();
+        assert_eq!(Some(2.), as_f64(json!(2)));
+        assert_eq!(Some(2.), as_f64(json!("2")));
+        assert_eq!(Some(1.5), as_f64(json!("1 1/2 cups")));
+        assert_eq!(Some(0.5), as_f64(json!("½ loaf")));
+        assert_eq!(Some(4.), as_f64(json!("4-6 servings")));
+        assert_eq!(
+            Some(12.),
+            as_f64(json!({"@type": "QuantitativeValue", "value": 12}))
+        );
+        assert_eq!(Some(4.), as_f64(json!("Serves 4")));
+        assert_eq!(Some(24.), as_f64(json!("2 dozen cookies")));
+        assert_eq!(None, as_f64(json!("N/A")));
+    }
+
+    #[test]
+    fn test_yield() {
+        assert_eq!(
+            Yield::Single(Quantity::number(2)),
+            serde_json::from_value(json!(2)).unwrap()
+        );
+
+        assert_eq!(
+            Yield::Multi(vec![Quantity::string("2"), Quantity::string("2 cups"),]),
+            serde_json::from_value(json!(["2", "2 cups"])).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_len() {
+        assert_eq!(3, IngredientList::single("flour\n\n  eggs\nmilk\n").len());
+        assert!(IngredientList::single(" \n").is_empty());
+        assert!(IngredientList::multi(["", " "]).is_empty());
+
+        let sections = InstructionList::Sections(vec![
+            InstructionSection::new("One", [Instruction::simple("Mix")]),
+            InstructionSection::new("Two", []),
+            InstructionSection::new("Three", [Instruction::simple("Bake")]),
+        ]);
+        assert_eq!(2, sections.len());
+        assert!(InstructionList::Sections(vec![InstructionSection::new("", [])]).is_empty());
+    }
+
+    #[test]
+    fn test_instruction_list_resectionize() {
+        let flat = InstructionList::Multi(
+            [
+                "Preheat the oven",
+                "For the dough:",
+                "Mix",
+                "Knead",
+                "Filling:",
+                "Stir",
+            ]
+            .iter()
+            .map(|s| Instruction::simple(*s))
+            .collect(),
+        );
+        assert_eq!(
+            InstructionList::Sections(vec![
+                InstructionSection::new("", [Instruction::simple("Preheat the oven")]),
+                InstructionSection::new(
+                    "dough",
+                    [Instruction::simple("Mix"), Instruction::simple("Knead")]
+                ),
+                InstructionSection::new("Filling", [Instruction::simple("Stir")]),
+            ]),
+            flat.resectionize()
+        );
+
+        let headed = InstructionList::Multi(vec![
+            Instruction::simple("Sauce:"),
+            Instruction::simple("Simmer"),
+        ]);
+        assert_eq!(
+            InstructionList::Sections(vec![InstructionSection::new(
+                "Sauce",
+                [Instruction::simple("Simmer")]
+            )]),
+            headed.resectionize()
+        );
+
+        let plain = InstructionList::Multi(vec![Instruction::simple("Mix")]);
+        assert_eq!(plain, plain.resectionize());
+        let sectioned = InstructionList::Sections(vec![InstructionSection::new(
+            "Dough:",
+            [Instruction::simple("Mix")],
+        )]);
+        assert_eq!(sectioned, sectioned.resectionize());
+    }
+
+    #[test]
+    fn test_numbered_steps() {
+        let sections = InstructionList::Sections(vec![
+            InstructionSection::new(
+                "Dough",
+                [Instruction::simple("Mix"), Instruction::simple("Knead")],
+            ),
+            InstructionSection::new("Empty", []),
+            InstructionSection::new("Filling", [Instruction::simple("Stir")]),
+        ]);
+        let numbered = |list: &InstructionList, continuous| {
+            list.numbered(continuous)
+                .into_iter()
+                .map(|(name, n, text)| format!("{} {}. {}", name, n, text))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["Dough 1. Mix", "Dough 2. Knead", "Filling 3. Stir"],
+            numbered(&sections, true)
+        );
+        assert_eq!(
+            vec!["Dough 1. Mix", "Dough 2. Knead", "Filling 1. Stir"],
+            numbered(&sections, false)
+        );
+
+        let steps = InstructionList::Multi(vec![
+            Instruction::simple("Mix"),
+            Instruction::simple("Bake"),
+        ]);
+        assert_eq!(vec![" 1. Mix", " 2. Bake"], numbered(&steps, false));
+    }
+
+    #[test]
+    fn test_yield_all() {
+        let all = |v| serde_json::from_value::<Yield>(v).unwrap().all();
+
+        assert_eq!(
+            vec!["4 servings", "2 cups"],
+            all(json!(["4 servings", "2 cups"]))
+        );
+        assert_eq!(
+            vec!["6 slices"],
+            all(json!({"value": 6, "unitText": "slices"}))
+        );
+        assert_eq!(vec!["12"], all(json!(12)));
+    }
+
+    #[test]
+    fn test_yield_value_and_unit() {
+        let value_and_unit = |v| serde_json::from_value::<Yield>(v).unwrap().value_and_unit();
+
+        assert_eq!(
+            Some((4., Some("servings".into()))),
+            value_and_unit(json!("4 servings"))
+        );
+        assert_eq!(
+            Some((24., Some("cookies".into()))),
+            value_and_unit(json!("2 dozen cookies"))
+        );
+        assert_eq!(Some((6., None)), value_and_unit(json!(["6"])));
+        assert_eq!(
+            Some((8., Some("slices".into()))),
+            value_and_unit(
+                json!({"@type": "QuantitativeValue", "value": "8", "unitText": "slices"})
+            )
+        );
+        assert_eq!(None, value_and_unit(json!("Serves a crowd")));
+        assert_eq!(
+            Some((4., Some("Portionen".into()))),
+            value_and_unit(json!("4 Portionen"))
+        );
+        assert_eq!(
+            Some((4., Some("Personen".into()))),
+            value_and_unit(json!("Für 4 Personen"))
+        );
+        assert_eq!(
+            Some((6., Some("personnes".into()))),
+            value_and_unit(json!("Pour 6 personnes"))
+        );
+        assert_eq!(Some((8., None)), value_and_unit(json!("Serves 8")));
+    }
+
+    #[test]
+    fn test_yield_range() {
+        let yields = serde_json::from_value::<Yield>(json!({
+            "@type": "QuantitativeValue",
+            "minValue": 4,
+            "maxValue": "6",
+            "unitText": "servings",
+        }))
+        .unwrap();
+
+        assert_eq!(Some((4., 6.)), yields.range());
+        assert_eq!(Some((4., Some("servings".into()))), yields.value_and_unit());
+        assert_eq!("4-6 servings", yields.to_string());
+        let open = |bounds: serde_json::Value| {
+            serde_json::from_value::<Yield>(bounds).unwrap().to_string()
+        };
+        assert_eq!(
+            "4+ servings",
+            open(json!({"@type": "QuantitativeValue", "minValue": 4, "unitText": "servings"}))
+        );
+        assert_eq!(
+            "up to 6",
+            open(json!({"@type": "QuantitativeValue", "maxValue": 6}))
+        );
+        assert_eq!(
+            None,
+            serde_json::from_value::<Yield>(json!("4 servings"))
+                .unwrap()
+                .range()
+        );
+    }
+
+    #[test]
+    fn test_author() {
+        let details = |name: &str, url: Option<&str>| AuthorDetails {
+            name: Some(name.into()),
+            url: url.map(Into::into),
+        };
+
+        assert_eq!(
+            Author::Multi(vec![
+                Author::Name("Jane".into()),
+                Author::Person(details("John", Some("https://example.com/john"))),
+                Author::Organization(details("Test Kitchen", Some("https://example.com"))),
+                Author::Person(details("Anonymous", None)),
+            ]),
+            serde_json::from_value(json!([
+                "Jane",
+                {"@type": "Person", "name": "John", "url": "https://example.com/john"},
+                {"@type": "Organization", "name": "Test Kitchen", "url": {"@id": "https://example.com"}},
+                {"name": "Anonymous"},
+            ]))
+            .unwrap()
+        );
+
+        let recipe: Recipe = serde_json::from_value(json!({
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": "An ingredient",
+            "author": ["Jane", {"@type": "Person", "name": "John", "url": "https://example.com/john"}]
+        }))
+        .unwrap();
+        assert_eq!(Some("https://example.com/john"), recipe.first_author_url());
+
+        let recipe: Recipe = serde_json::from_value(json!({
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": "An ingredient",
+            "author": 42
+        }))
+        .unwrap();
+        assert_eq!(&None, recipe.author());
+    }
+
+    #[test]
+    fn test_all_tags() {
+        let recipe: Recipe = serde_json::from_value(json!({
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": "An ingredient",
+            "recipeCategory": "Dessert",
+            "recipeCuisine": ["French", " Italian "],
+            "keywords": "cake, dessert,Easy",
+            "suitableForDiet": ["https://schema.org/GlutenFreeDiet", "VegetarianDiet"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                "dessert",
+                "french",
+                "italian",
+                "cake",
+                "easy",
+                "gluten free",
+                "vegetarian"
+            ],
+            recipe.all_tags()
+        );
+    }
+
+    #[test]
+    fn test_ingredient_names_only() {
+        let recipe = Recipe::new(
+            "A recipe",
+            "",
+            IngredientList::Multi(
+                ["2 cups Flour", "1 tsp salt", "a pinch of salt", "3 eggs"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            ),
+        );
+
+        assert_eq!(
+            vec!["flour", "salt", "a pinch of salt", "eggs"],
+            recipe.ingredient_names_only()
+        );
+    }
+
+    #[test]
+    fn test_clean_instructions() {
+        let recipe = Recipe::new("A recipe", "", IngredientList::single("An ingredient"));
+        let sections = InstructionList::Sections(vec![
+            InstructionSection::new("", [Instruction::simple("Jump to Recipe")]),
+            InstructionSection::new(
+                "Prep",
+                [
+                    Instruction::structured("Print recipe!"),
+                    Instruction::structured("Do a thing"),
+                ],
+            ),
+        ]);
+
+        assert_eq!(
+            Some(&InstructionList::Sections(vec![InstructionSection::new(
+                "Prep",
+                [Instruction::structured("Do a thing")]
+            )])),
+            recipe
+                .clone()
+                .with_directions(sections)
+                .clean_instructions()
+                .directions()
+                .as_ref()
+        );
+
+        let boilerplate = InstructionList::Single(Instruction::simple("  PRINT RECIPE "));
+        assert_eq!(
+            &None,
+            recipe
+                .with_directions(boilerplate)
+                .clean_instructions()
+                .directions()
+        );
+    }
+
+    #[test]
+    fn test_recipe_notes() {
+        let notes = |extra| {
+            serde_json::from_value::<Recipe>(recipe_json(extra))
+                .unwrap()
+                .notes()
+                .clone()
+        };
+
+        assert_eq!(
+            vec!["Keeps for 3 days"],
+            notes(json!({"recipeNotes": " Keeps for 3 days "}))
+        );
+        assert_eq!(
+            vec!["Use ripe bananas", "Freezes well"],
+            notes(json!({"notes": ["Use ripe bananas", "", "Freezes well"]}))
+        );
+        assert_eq!(
+            vec!["Tastes better the next day"],
+            notes(json!({
+                "note": {"@type": "Comment", "text": "Tastes better the next day"},
+                "recipeNote": "Tastes better the next day",
+            }))
+        );
+        assert!(notes(json!({"note": 4})).is_empty());
+        // Readers' comments aren't notes
+        let comment = json!({"@type": "Comment", "text": "Loved it!"});
+        assert!(notes(json!({ "comment": comment })).is_empty());
+        assert!(notes(json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_accessible_for_free_and_cooking_technique() {
+        assert_eq!(None, recipe(json!({})).accessible_for_free());
+        assert_eq!(
+            Some(false),
+            recipe(json!({"isAccessibleForFree": false})).accessible_for_free()
+        );
+        assert_eq!(
+            Some(true),
+            recipe(json!({"isAccessibleForFree": "True"})).accessible_for_free()
+        );
+
+        let technique = recipe(json!({"cookingTechnique": ["Baking", "Frying"]}));
+        assert_eq!(
+            vec!["Baking", "Frying"],
+            technique.cooking_technique().as_ref().unwrap().items()
+        );
+
+        let malformed = json!({"cookingTechnique": [{"@type": "DefinedTerm", "name": "Baking"}]});
+        let malformed = recipe(malformed);
+        assert_eq!(&None, malformed.cooking_technique());
+        assert_eq!("A recipe", malformed.name());
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        let recipe = serde_json::from_value::<Recipe>(recipe_json(json!({
+            "name": "Mac &amp; Cheese",
+            "recipeIngredient": ["&frac12; cup milk"],
+            "recipeInstructions": [{"text": "Stir &#38; serve"}],
+            "recipeNotes": "Keeps &lt; 3 days",
+        })))
+        .unwrap()
+        .decode_entities();
+
+        assert_eq!("Mac & Cheese", recipe.name());
+        assert_eq!(&vec!["Keeps < 3 days".to_string()], recipe.notes());
+        assert_eq!(&IngredientList::multi(["½ cup milk"]), recipe.ingredients());
+        assert_eq!(
+            Some(&InstructionList::Multi(vec![Instruction::structured(
+                "Stir & serve"
+            )])),
+            recipe.directions().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        let recipe = serde_json::from_value::<Recipe>(recipe_json(json!({
+            "name": "  Mac &amp; <b>Cheese</b> ",
+            "recipeIngredient": ["&frac12; cup  milk", "1 cup pasta", " &#189; cup   milk"],
+            "recipeInstructions": [
+                "<p>Boil the\tpasta</p>",
+                "Jump to Recipe",
+                {"text": "&lt;em&gt;Stir&lt;/em&gt; &amp; serve"},
+            ],
+        })))
+        .unwrap();
+
+        let normalized = recipe.normalized();
+        assert_eq!("Mac & Cheese", normalized.name());
+        assert_eq!(
+            &IngredientList::multi(["½ cup milk", "1 cup pasta"]),
+            normalized.ingredients()
+        );
+        let steps = normalized.directions().as_ref().unwrap().steps();
+        assert_eq!(
+            vec!["Boil the pasta", "Stir & serve"],
+            steps.iter().map(|s| s.text()).collect::<Vec<_>>()
+        );
+
+        // Each step alone leaves the others' work undone
+        assert_eq!("Mac &amp; <b>Cheese</b>", recipe.trimmed().name());
+        assert_eq!("  Mac & Cheese ", recipe.strip_html().name());
+        assert_eq!(3, recipe.dedup_ingredients().ingredients().len());
+    }
+
+    #[test]
+    fn test_recipe_steps() {
+        let steps = InstructionList::Multi(vec![Instruction::simple("Do a thing")]);
+
+        assert_eq!(
+            Some(&steps),
+            recipe(json!({"steps": ["Do a thing"]}))
+                .directions()
+                .as_ref()
+        );
+        assert_eq!(
+            Some(&steps),
+            recipe(json!({"recipeInstructions": {"steps": ["Do a thing"]}}))
+                .directions()
+                .as_ref()
+        );
+        assert_eq!(
+            Some(&steps),
+            recipe(json!({"recipeInstructions": ["Do a thing"], "steps": ["Ignore me"]}))
+                .directions()
+                .as_ref()
+        );
+        assert_eq!(
+            Some(&steps),
+            recipe(json!({"recipeInstructions": {
+                "@type": "ItemList",
+                "itemListElement": ["Do a thing"],
+            }}))
+            .directions()
+            .as_ref()
+        );
+    }
+
+    #[test]
+    fn test_recipe_nested_ingredients() {
+        let ingredients = |json: serde_json::Value| {
+            let mut recipe = json!({"name": "A recipe", "description": ""});
+            recipe
+                .as_object_mut()
+                .unwrap()
+                .extend(json.as_object().cloned().unwrap());
+            let recipe = serde_json::from_value::<Recipe>(recipe).unwrap();
+            let entries = recipe.ingredients().entries().map(str::to_string);
+            entries.collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["flour", "eggs"],
+            ingredients(json!({"ingredients": ["flour", "eggs"]}))
+        );
+        assert_eq!(
+            vec!["flour", "eggs"],
+            ingredients(json!({"ingredients": {"recipeIngredient": ["flour", "eggs"]}}))
+        );
+        assert_eq!(
+            vec!["flour"],
+            ingredients(json!({
+                "recipeIngredient": {"@type": "ItemList", "itemListElement": ["flour"]},
+                "ingredients": ["ignored"],
+            }))
+        );
+    }
+
+    #[test]
+    fn test_recipe_ingredient_amounts() {
+        let ingredients = |extra| {
+            let recipe = serde_json::from_value::<Recipe>(recipe_json(extra)).unwrap();
+            let entries = recipe.ingredients().entries().map(str::to_string);
+            entries.collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["2 cups flour", "3 eggs", "salt"],
+            ingredients(json!({
+                "recipeIngredient": ["flour", "eggs", "salt"],
+                "amounts": ["2 cups", 3, null],
+            }))
+        );
+        assert_eq!(
+            vec!["1/2 tsp vanilla"],
+            ingredients(json!({"recipeIngredient": ["vanilla"], "ingredientAmounts": ["1/2 tsp"]}))
+        );
+        assert_eq!(
+            vec!["flour", "eggs"],
+            ingredients(json!({"recipeIngredient": ["flour", "eggs"], "amounts": ["2 cups"]}))
+        );
+    }
+
+    #[test]
+    fn test_hashtags() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "keywords": ["dessert", "gluten-free", "quick & easy", "Dessert", "!!", "crème brûlée"]
+        })))
+        .unwrap();
+
+        assert_eq!(
+            vec!["#dessert", "#GlutenFree", "#QuickEasy", "#CrèmeBrûlée"],
+            recipe.hashtags()
+        );
+    }
+
+    #[test]
+    fn test_nutrition() {
+        let nutrition = |nutrition| {
+            serde_json::from_value::<Recipe>(recipe_json(json!({ "nutrition": nutrition })))
+                .unwrap()
+                .nutrition()
+                .clone()
+                .unwrap()
+        };
+
+        let text = nutrition(json!({"calories": "1,200 calories", "fatContent": "5 g"}));
+        assert_eq!(Some("1,200 calories"), text.calories().as_deref());
+        assert_eq!(Some(1200.), text.calories_numeric());
+        assert_eq!(Some("5 g"), text.fat().as_deref());
+
+        let structured = nutrition(json!({
+            "@type": "NutritionInformation",
+            "calories": {"@type": "QuantitativeValue", "value": "200", "unitCode": "E14"},
+            "proteinContent": {"value": 12.5, "unitText": "grams"},
+            "sugarContent": 3,
+            "sodiumContent": {"unitCode": "MGM"},
+        }));
+        assert_eq!(Some("200 kcal"), structured.calories().as_deref());
+        assert_eq!(Some(200.), structured.calories_numeric());
+        assert_eq!(Some("12.5 grams"), structured.protein().as_deref());
+        assert_eq!(Some("3"), structured.sugar().as_deref());
+        assert_eq!(None, structured.sodium().as_deref());
+        assert_eq!(None, nutrition(json!({})).calories_numeric());
+    }
+
+    #[test]
+    fn test_scale_to_yield() {
+        let pancakes = recipe(json!({
+            "recipeYield": "4 servings",
+            "recipeIngredient": ["1 1/2 cups flour", "2 eggs", "salt to taste"],
+        }));
+        let scaled = pancakes.scale_to_yield(6.).unwrap();
+
+        assert_eq!(
+            vec!["2 1/4 cups flour", "3 eggs", "salt to taste"],
+            scaled.ingredients().entries().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some("6 servings".to_string()),
+            scaled.yields().as_ref().map(ToString::to_string)
+        );
+
+        let supply = recipe(json!({
+            "recipeYield": 2,
+            "recipeIngredient": [{"@type": "HowToSupply", "name": "flour",
+                "requiredQuantity": {"value": 100, "unitText": "g"}}],
+        }));
+        assert_eq!(
+            vec!["300 g flour"],
+            supply
+                .scale_to_yield(6.)
+                .unwrap()
+                .ingredients()
+                .entries()
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            None,
+            recipe(json!({"recipeYield": "a crowd"})).scale_to_yield(8.)
+        );
+
+        let yields = |value, target| {
+            let scaled = recipe(json!({"recipeYield": value, "recipeIngredient": ["2 eggs"]}))
+                .scale_to_yield(target)
+                .unwrap();
+            let ingredients = scaled.ingredients().entries().map(str::to_string).collect();
+            (scaled.yields().as_ref().unwrap().to_string(), ingredients)
+        };
+        assert_eq!(
+            ("Serves 8".to_string(), vec!["4 eggs".to_string()]),
+            yields("Serves 4", 8.)
+        );
+        assert_eq!(
+            ("Makes 24 cookies".to_string(), vec!["4 eggs".to_string()]),
+            yields("Makes 12 cookies", 24.)
+        );
+        assert_eq!(None, recipe(json!({})).scale_to_yield(8.));
+    }
+
+    #[test]
+    fn test_recipe_merge() {
+        let partial = recipe(json!({
+            "name": "Cake",
+            "recipeIngredient": ["flour", "eggs"],
+            "cookTime": "soon",
+            "keywords": "",
+        }));
+        let other = recipe(json!({
+            "name": "Chocolate cake",
+            "description": "Rich",
+            "recipeIngredient": ["cocoa"],
+            "recipeInstructions": ["Mix", "Bake"],
+            "cookTime": "PT40M",
+            "keywords": "dessert",
+        }));
+        let merged = partial.merge(other);
+
+        assert_eq!("Cake", merged.name());
+        assert_eq!("Rich", merged.description());
+        assert_eq!(
+            vec!["flour", "eggs"],
+            merged.ingredients().entries().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(2),
+            merged.directions().as_ref().map(InstructionList::len)
+        );
+        assert_eq!(Some(40), merged.cook_minutes());
+        assert_eq!(vec!["dessert"], merged.all_tags());
+    }
+
+    #[test]
+    fn test_recipe_blank_fields() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "description": "  ",
+            "cookTime": "",
+            "prepTime": " ",
+            "recipeYield": "",
+            "url": "",
+            "keywords": "",
+        })))
+        .unwrap();
+
+        assert_eq!("", recipe.description());
+        assert_eq!(&None, recipe.cook_time());
+        assert_eq!(&None, recipe.prep_time());
+        assert_eq!(&None, recipe.yields());
+        assert_eq!(&None, recipe.url());
+        assert_eq!(None, recipe.active_time_human());
+
+        let api = recipe.to_api_json();
+        assert_eq!(serde_json::Value::Null, api["yield"]);
+        assert_eq!(serde_json::Value::Null, api["cook_time_minutes"]);
+        assert!(!recipe.to_string().contains("Yield"));
+    }
+
+    #[test]
+    fn test_recipe_perform_time() {
+        assert_eq!(
+            Some(25),
+            recipe(json!({"performTime": "PT25M"})).cook_minutes()
+        );
+        assert_eq!(
+            Some(10),
+            recipe(json!({"performTime": "PT25M", "cookTime": "PT10M"})).cook_minutes()
+        );
+    }
+
+    #[test]
+    fn test_recipe_numbered_steps() {
+        assert_eq!(
+            Some(&InstructionList::Multi(vec![
+                Instruction::simple("Do a thing"),
+                Instruction::simple("Do another"),
+                Instruction::simple("Finish"),
+            ])),
+            recipe(json!({"recipeInstructions": {
+                "2": "Do another",
+                "10": "Finish",
+                "1": "Do a thing",
+            }}))
+            .directions()
+            .as_ref()
+        );
+
+        let mixed = json!({"recipeInstructions": {"1": "Do a thing", "note": "Enjoy"}});
+        assert!(serde_json::from_value::<Recipe>(recipe_json(mixed)).is_err());
+    }
+
+    #[test]
+    fn test_structured_ingredients() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "recipeIngredient": [
+                "1 egg",
+                {
+                    "@type": "HowToSupply",
+                    "name": "flour",
+                    "requiredQuantity": {
+                        "@type": "QuantitativeValue",
+                        "value": 2,
+                        "unitText": "Cups"
+                    }
+                },
+                {"@type": "HowToSupply", "name": "saffron", "requiredQuantity": "a pinch of"},
+            ]
+        })))
+        .unwrap();
+
+        assert_eq!(
+            vec!["1 egg", "2 Cups flour", "a pinch of saffron"],
+            recipe.ingredients().entries().collect::<Vec<_>>()
+        );
+
+        let parsed = recipe.ingredients().parse();
+        assert_eq!(Some(2.), parsed[1].quantity());
+        assert_eq!(Some("cup"), parsed[1].unit());
+        assert_eq!("flour", parsed[1].name());
+        assert_eq!(None, parsed[2].quantity());
+
+        let pairs = recipe.ingredients().parsed_with_raw();
+        assert_eq!(
+            vec![
+                ("1 egg", "egg"),
+                ("2 Cups flour", "flour"),
+                ("a pinch of saffron", "a pinch of saffron")
+            ],
+            pairs
+                .iter()
+                .map(|(raw, parsed)| (raw.as_str(), parsed.name().as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            parsed,
+            pairs.into_iter().map(|(_, p)| p).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_to_api_json() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "@context": "https://schema.org",
+            "@type": "Recipe",
+            "recipeIngredient": ["1 cup flour", "2 eggs"],
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Batter", "itemListElement": ["Mix"]},
+                {"@type": "HowToSection", "name": "Cook", "itemListElement": ["Fry"]},
+            ],
+            "prepTime": "PT10M",
+            "recipeYield": "4 servings",
+            "image": ["https://example.com/a.jpg", "https://example.com/b.jpg"],
+            "keywords": "Breakfast",
+        })))
+        .unwrap();
+
+        assert_eq!(
+            json!({
+                "name": "A recipe",
+                "description": "",
+                "ingredients": ["1 cup flour", "2 eggs"],
+                "steps": ["Mix", "Fry"],
+                "prep_time_minutes": 10,
+                "cook_time_minutes": null,
+                "total_time_minutes": null,
+                "yield": "4 servings",
+                "image": "https://example.com/a.jpg",
+                "url": null,
+                "tags": ["breakfast"],
+            }),
+            recipe.to_api_json()
+        );
+    }
+
+    #[test]
+    fn test_contains_ingredient() {
+        let recipe = Recipe::new(
+            "Salsa",
+            "",
+            IngredientList::multi(["4 ripe tomatoes", "1 onion", "Juice of 2 limes"]),
+        );
+        assert!(recipe.contains_ingredient("tomato"));
+        assert!(recipe.contains_ingredient("lime"));
+        assert!(!recipe.contains_ingredient("garlic"));
+    }
+
+    #[test]
+    fn test_ingredient_count_excluding_headers() {
+        let recipe = Recipe::new(
+            "Cake",
+            "",
+            IngredientList::multi([
+                "For the cake:",
+                "2 cups flour",
+                "3 eggs",
+                "For the glaze:",
+                "1 cup sugar",
+            ]),
+        );
+        assert_eq!(5, recipe.ingredients().len());
+        assert_eq!(3, recipe.ingredient_count_excluding_headers());
+
+        let recipe = Recipe::new("Toast", "", IngredientList::multi(["1 slice bread"]));
+        assert_eq!(1, recipe.ingredient_count_excluding_headers());
+    }
+
+    #[test]
+    fn test_yield_malformed() {
+        for malformed in [json!(null), json!(false), json!(true)] {
+            let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+                "recipeYield": malformed,
+                "totalTime": "PT10M",
+            })))
+            .unwrap();
+            assert_eq!(&None, recipe.yields());
+            assert_eq!(Some(10), recipe.total_minutes());
+        }
+    }
+
+    #[test]
+    fn test_ingredients_indexed() {
+        let recipe = Recipe::new(
+            "Pancakes",
+            "",
+            IngredientList::multi(["1 &frac12; Cups  Flour", "1.5 cup flour", "2 Eggs", "Salt"]),
+        );
+        assert_eq!(
+            vec![
+                ("1 &frac12; Cups  Flour", "1 1/2 cup flour"),
+                ("1.5 cup flour", "1 1/2 cup flour"),
+                ("2 Eggs", "2 eggs"),
+                ("Salt", "salt"),
+            ],
+            recipe
+                .ingredients_indexed()
+                .iter()
+                .map(|(raw, normalized)| (raw.as_str(), normalized.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_tags_malformed() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "keywords": 42,
+            "recipeCategory": {"name": "Dessert"},
+            "recipeCuisine": "French",
+        })))
+        .unwrap();
+
+        assert_eq!(&None, recipe.keywords());
+        assert_eq!(&None, recipe.category());
+        assert_eq!(vec!["french"], recipe.all_tags());
+        assert_eq!("A recipe", recipe.name());
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let recipe = |ingredients: usize, steps: usize, time: &str| {
+            let value = recipe_json(json!({
+                "recipeIngredient": vec!["An ingredient"; ingredients],
+                "recipeInstructions": vec!["Do a thing"; steps],
+                "totalTime": time,
+            }));
+            serde_json::from_value::<Recipe>(value)
+                .unwrap()
+                .difficulty()
+        };
+
+        assert_eq!(Difficulty::Easy, recipe(4, 3, "PT20M"));
+        assert_eq!(Difficulty::Easy, recipe(4, 3, ""));
+        assert_eq!(Difficulty::Medium, recipe(10, 8, "PT20M"));
+        assert_eq!(Difficulty::Hard, recipe(20, 15, "PT3H"));
+    }
+
+    #[test]
+    fn test_recipe_display() {
+        let recipe: Recipe = serde_json::from_value(json!({
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeYield": "4 servings",
+            "totalTime": "PT30M",
+            "recipeIngredient": ["An ingredient", "Another one"],
+            "recipeInstructions": ["Do a thing", "Do another thing", "Eat"]
+        }))
+        .unwrap();
+
+        let total = recipe
+            .total_time()
+            .as_ref()
+            .unwrap()
+            .human_readable()
+            .unwrap();
+        assert_eq!(
+            format!(
+                "A recipe\nYield: 4 servings\nTotal time: {}\nIngredients: 2\nSteps: 3",
+                total
+            ),
+            recipe.to_string()
+        );
+    }
+
+    #[test]
+    fn test_recipe() {
+        assert_eq!(
+            Recipe::new(
+                "A recipe",
+                "This is a recipe",
+                IngredientList::single("An ingredient. Another one")
+            ),
+            serde_json::from_value(
+                json!({"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient. Another one" })
+            ).unwrap()
+        );
+
+        assert_eq!(
+            Recipe::new(
+                "A recipe",
+                "This is a recipe",
+                IngredientList::single("An ingredient. Another one")
+            )
+            .with_directions(InstructionList::Single(Instruction::simple(
+                "Do a thing. Do another thing"
+            ))),
+            serde_json::from_value(json!({
+                    "name": "A recipe",
+                    "description": "This is a recipe",
+                    "recipeIngredient": "An ingredient. Another one",
+                    "recipeInstructions": "Do a thing. Do another thing"
+            }))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scrape_html_with_stats() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">{"@context": "https://schema.org"}</script>
+            <script type="application/ld+json">not json</script>
+            <script type="application/ld+json">
+                {"@graph": [{"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient"}]}
+            </script>
+        </head></html>"#;
+
+        let (entries, stats) = SchemaEntry::scrape_html_with_stats(html);
+        assert_eq!(2, entries.len());
+        assert_eq!(
+            crate::ScrapeStats {
+                scripts_found: 3,
+                scripts_parsed: 2,
+                recipes_extracted: 1,
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn test_scrape_document_recipes() {
+        let html = r##"
+            <script type="application/ld+json">{"@type": "BreadcrumbList", "itemListElement": []}</script>
+            <script type="application/ld+json">{"@id": "#cake", "description": "Fluffy"}</script>
+            <script type="application/ld+json">
+                {"@id": "#cake", "@type": "Recipe", "name": "Cake", "description": "", "recipeIngredient": "flour"}
+            </script>
+            <script type="application/ld+json">{"name": "Toast", "description": "", "ingredients": "bread"}</script>
+            <script type="application/ld+json">{"name": "Jam", "description": "", "\u0069ngredients": "fruit"}</script>
+            <script type="application/ld+json">{"@type": "Organization", "name": "Example"}</script>"##;
+        assert!(!may_hold_recipe(r#"{"@type": "BreadcrumbList"}"#));
+        assert!(may_hold_recipe(r#"{"@type": "RECIPE"}"#));
+
+        let document = scraper::Html::parse_document(html);
+        let (entries, _) = SchemaEntry::scrape_document(&document);
+        let expected = entries
+            .into_iter()
+            .flat_map(SchemaEntry::into_recipes)
+            .collect::<Vec<_>>();
+        let names = expected.iter().map(Recipe::name).collect::<Vec<_>>();
+        assert_eq!(vec!["Cake", "Toast", "Jam"], names);
+        assert_eq!("Fluffy", expected[0].description());
+        assert_eq!(expected, SchemaEntry::scrape_document_recipes(&document));
+
+        let sourced = SchemaEntry::scrape_html_with_source(html);
+        let sourced = sourced.into_iter().map(|s| s.into_parts().0);
+        assert_eq!(expected, sourced.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_scrape_html_breadcrumb_categories() {
+        let html = r#"
+            <script type="application/ld+json">{"@context": "https://schema.org", "@type": "BreadcrumbList",
+                "itemListElement": [
+                    {"@type": "ListItem", "position": 1, "name": "Home", "item": "https://example.com/"},
+                    {"@type": "ListItem", "position": 2, "name": "Breakfast", "item": "https://example.com/breakfast"},
+                    {"@type": "ListItem", "position": 3, "name": "Pancakes"}
+                ]}</script>
+            <script type="application/ld+json">
+                {"@type": "Recipe", "name": "Pancakes", "description": "", "recipeIngredient": "flour"}
+            </script>"#;
+        let categories = |entries: Vec<SchemaEntry>| {
+            let recipes = entries.into_iter().flat_map(SchemaEntry::into_recipes);
+            let recipe = recipes.last().unwrap();
+            let category = recipe.category().as_ref();
+            category.map(|c| c.items().join(", "))
+        };
+
+        let entries = <SchemaEntry as crate::Scrape>::scrape_html(html);
+        assert_eq!(None, categories(entries));
+
+        let options = crate::ResolveOptions {
+            breadcrumbs_as_category: true,
+            ..Default::default()
+        };
+        let entries = SchemaEntry::scrape_html_with_options(html, options);
+        assert_eq!(Some("Breakfast".to_string()), categories(entries));
+
+        let referenced = r##"
+            <script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+                {"@type": "WebPage", "@id": "#page", "breadcrumb": {"@id": "#crumbs"}},
+                {"@type": "Recipe", "name": "Pancakes", "description": "", "recipeIngredient": "flour"}
+            ]}</script>
+            <script type="application/ld+json">{"@type": "WebSite", "@id": "#site", "hasPart": [
+                {"@type": "BreadcrumbList", "@id": "#crumbs", "itemListElement": [
+                    {"@type": "ListItem", "position": 1, "name": "Home"},
+                    {"@type": "ListItem", "position": 2, "name": "Brunch"}
+                ]}
+            ]}</script>"##;
+        let entries = SchemaEntry::scrape_html_with_options(referenced, options);
+        assert_eq!(Some("Brunch".to_string()), categories(entries));
+    }
+
+    #[test]
+    fn test_scrape_html_split_graphs() {
+        let html = r##"
+            <script type="application/ld+json">{"@graph": [
+                {"@id": "#recipe", "@type": "Recipe", "name": "Cake", "description": ""}
+            ]}</script>
+            <script type="application/ld+json">{"@graph": [
+                {"@id": "#recipe", "description": "Fluffy", "recipeIngredient": ["flour"]}
+            ]}</script>"##;
+        let recipes = <SchemaEntry as crate::Scrape>::scrape_html(html)
+            .into_iter()
+            .flat_map(SchemaEntry::into_recipes)
+            .collect::<Vec<_>>();
+
+        assert_eq!(1, recipes.len());
+        assert_eq!("Cake", recipes[0].name());
+        assert_eq!("Fluffy", recipes[0].description());
+        assert_eq!(
+            vec!["flour"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_scrape_html_noscript() {
+        let recipe = |name| {
+            format!(
+                r#"<script type="application/ld+json">{{"name": "{}", "description": "", "recipeIngredient": ""}}</script>"#,
+                name
+            )
+        };
+        let html = format!(
+            "<html><head><noscript>{}</noscript></head><body>{}<noscript><p>Enable JS</p>{}</noscript></body></html>",
+            recipe("Head"),
+            recipe("Body"),
+            recipe("Hidden"),
+        );
+
+        let entries = <SchemaEntry as crate::Scrape>::scrape_html(html);
+        let names = entries
+            .iter()
+            .flat_map(SchemaEntry::recipes)
+            .map(|r| r.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Head", "Body", "Hidden"], names);
+    }
+
+    #[test]
+    fn test_scrape_html_bom_and_comments() {
+        let recipe = r#"{"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient"}"#;
+        let html = format!(
+            r#"<script type="application/ld+json">{bom}{recipe}</script>
+               <script type="application/ld+json"> <!-- {recipe} --> </script>"#,
+            bom = '\u{feff}'
+        );
+
+        let (entries, stats) = SchemaEntry::scrape_html_with_stats(html);
+        assert_eq!(2, stats.scripts_parsed);
+        assert_eq!(2, stats.recipes_extracted);
+        assert_eq!(entries[0], entries[1]);
+    }
+
+    #[test]
+    fn test_scrape_html_concatenated_objects() {
+        let html = r#"<script type="application/ld+json">
+            {"@type": "WebSite", "name": "Example"}
+            {"@type": "Recipe", "name": "Cake", "description": "", "recipeIngredient": "flour"}
+            {"@type": "Recipe", "name": "Pie", "description": "", "recipeIngredient": "apples"}
+        </script>"#;
+
+        let (entries, stats) = SchemaEntry::scrape_html_with_stats(html);
+        assert_eq!(2, stats.recipes_extracted);
+        let recipes = entries.iter().flat_map(SchemaEntry::recipes);
+        assert_eq!(
+            vec!["Cake", "Pie"],
+            recipes.map(|r| r.name().as_str()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_from_microdata_html() {
+        let html = r#"<article itemscope itemtype="http://schema.org/Recipe">
+            <h1 itemprop="name">Toast</h1>
+            <p itemprop="description">Crunchy</p>
+            <span itemprop="recipeIngredient">1 slice bread</span>
+        </article>"#;
+        let recipe = Recipe::from_microdata_html(html).unwrap();
+        assert_eq!("Toast", recipe.name());
+        assert_eq!("Crunchy", recipe.description());
+        assert_eq!(1, recipe.ingredients().len());
+
+        let json_ld = r#"<script type="application/ld+json">
+            {"@type": "Recipe", "name": "Toast", "description": "", "recipeIngredient": ""}
+        </script>"#;
+        assert_eq!(None, Recipe::from_microdata_html(json_ld));
+    }
+
+    #[cfg(feature = "lenient")]
+    #[test]
+    fn test_from_json_str_lenient() {
+        let json = r#"{
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": ["An ingredient", "Another one",],
+        }"#;
+
+        assert!(SchemaEntry::from_json_str(json).is_err());
+        assert_eq!(
+            SchemaEntry::Single(SchemaItem::Recipe(Box::new(Recipe::new(
+                "A recipe",
+                "This is a recipe",
+                IngredientList::multi(["An ingredient", "Another one"])
+            )))),
+            SchemaEntry::from_json_str_lenient(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_image() {
+        assert_eq!(
+            Image::Multi(vec![
+                Image::Url("a.jpg".into()),
+                Image::Object(ImageObject {
+                    url: "b.jpg".into(),
+                    width: None,
+                    height: None,
+                    thumbnail: None,
+                    thumbnail_url: None,
+                })
+            ]),
+            serde_json::from_value(
+                json!(["a.jpg", {"@type": "ImageObject", "contentUrl": "b.jpg"}])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_best_image_url() {
+        let best_image_url = |image| {
+            serde_json::from_value::<Recipe>(recipe_json(json!({ "image": image })))
+                .unwrap()
+                .best_image_url()
+                .map(str::to_string)
+        };
+
+        assert_eq!(
+            Some("b.jpg".into()),
+            best_image_url(json!([
+                "a.jpg",
+                {"url": "b.jpg", "width": 1200, "height": "800"},
+                {"url": "c.jpg", "width": 600, "height": 400},
+            ]))
+        );
+        assert_eq!(
+            Some("a.jpg".into()),
+            best_image_url(json!(["a.jpg", "b.jpg"]))
+        );
+        assert_eq!(None, best_image_url(json!(null)));
+    }
+
+    #[test]
+    fn test_recipe_thumbnail_url() {
+        let image = json!([
+            {"url": "big.jpg", "width": 1200, "height": 800},
+            {"url": "small.jpg", "width": 600, "height": 400, "thumbnailUrl": "tiny.jpg"},
+        ]);
+        let recipe: Recipe =
+            serde_json::from_value(recipe_json(json!({ "image": image }))).unwrap();
+        assert_eq!(Some("big.jpg"), recipe.best_image_url());
+        assert_eq!(Some("tiny.jpg"), recipe.thumbnail_url());
+        assert_eq!(
+            recipe.image().as_ref().unwrap().thumbnail_url(),
+            recipe.thumbnail_url()
+        );
+    }
+
+    #[test]
+    fn test_image_thumbnail_url() {
+        let thumbnail_url = |image| {
+            serde_json::from_value::<Image>(image)
+                .unwrap()
+                .thumbnail_url()
+                .map(str::to_string)
+        };
+
+        assert_eq!(
+            Some("small.jpg".into()),
+            thumbnail_url(json!({
+                "@type": "ImageObject",
+                "url": "huge.jpg",
+                "thumbnail": {"@type": "ImageObject", "contentUrl": "small.jpg"},
+            }))
+        );
+        assert_eq!(
+            Some("b-small.jpg".into()),
+            thumbnail_url(json!(["a.jpg", {"url": "b.jpg", "thumbnailUrl": "b-small.jpg"}]))
+        );
+        assert_eq!(
+            Some("small.jpg".into()),
+            thumbnail_url(json!({
+                "url": "huge.jpg",
+                "thumbnail": "small.jpg",
+                "thumbnailUrl": "other.jpg",
+            }))
+        );
+        assert_eq!(
+            Some("a.jpg".into()),
+            thumbnail_url(json!([{"url": "a.jpg", "thumbnail": 3}, "b.jpg"]))
+        );
+    }
+
+    #[test]
+    fn test_recipe_malformed_image() {
+        let recipe: Recipe = serde_json::from_value(json!({
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": "An ingredient",
+            "image": {"@id": "#unresolved"}
+        }))
+        .unwrap();
+        assert_eq!(&None, recipe.image());
+    }
+
+    #[test]
+    fn test_recipe_malformed_diet() {
+        for malformed in [json!({"@id": "https://schema.org/VeganDiet"}), json!(true)] {
+            let recipe: Recipe =
+                serde_json::from_value(recipe_json(json!({ "suitableForDiet": malformed })))
+                    .unwrap();
+            assert_eq!(&None, recipe.diet());
+            assert_eq!("A recipe", recipe.name());
+        }
+    }
+
+    #[test]
+    fn test_recipe_malformed_url() {
+        for malformed in [json!({"@id": "https://example.com/cake"}), json!(["/cake"])] {
+            let recipe: Recipe =
+                serde_json::from_value(recipe_json(json!({ "url": malformed }))).unwrap();
+            assert_eq!(&None, recipe.url());
+            assert_eq!("A recipe", recipe.name());
+        }
+    }
+
+    #[test]
+    fn test_scrape_html_with_base_url() {
+        let html = r#"<script type="application/ld+json">{
+            "name": "A recipe",
+            "description": "This is a recipe",
+            "recipeIngredient": "An ingredient",
+            "image": ["/img/cake.jpg", {"url": "https://cdn.example.com/cake.jpg"}],
+            "url": "cake"
+        }</script>"#;
+
+        let entries = SchemaEntry::scrape_html_with_base_url(html, "https://example.com/recipes/");
+        let recipe = entries[0].recipes()[0];
+        assert_eq!(
+            vec![
+                "https://example.com/img/cake.jpg",
+                "https://cdn.example.com/cake.jpg"
+            ],
+            recipe.image().as_ref().unwrap().urls()
+        );
+        assert_eq!(
+            Some("https://example.com/recipes/cake"),
+            recipe.url().as_deref()
+        );
+    }
+
+    #[test]
+    fn test_with_base_url() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "url": "cake",
+            "image": {"url": "/img/cake.jpg", "thumbnail": {"url": "/img/cake-small.jpg"}},
+            "author": {"@type": "Person", "name": "Jo", "url": "/authors/jo"},
+            "recipeInstructions": [
+                {"@type": "HowToStep", "text": "Mix", "url": "#step-1", "image": "mix.jpg"},
+                {"@type": "HowToStep", "text": "Bake", "image": [{"url": "https://cdn.example.com/bake.jpg"}]},
+            ],
+        })))
+        .unwrap();
+
+        let resolved = recipe.with_base_url("https://example.com/recipes/");
+        assert_eq!(
+            Some("https://example.com/recipes/cake"),
+            resolved.url().as_deref()
+        );
+        let image = resolved.image().as_ref().unwrap();
+        assert_eq!(vec!["https://example.com/img/cake.jpg"], image.urls());
+        assert_eq!(
+            Some("https://example.com/img/cake-small.jpg"),
+            image.thumbnail_url()
+        );
+        assert_eq!(
+            Some("https://example.com/authors/jo"),
+            resolved.author().as_ref().unwrap().first_url()
+        );
+        assert_eq!(
+            vec![
+                "https://example.com/recipes/mix.jpg",
+                "https://cdn.example.com/bake.jpg"
+            ],
+            resolved.step_images()
+        );
+        let steps = resolved.directions().as_ref().unwrap().steps();
+        assert!(matches!(
+            steps[0],
+            Instruction::Structured { extra, .. }
+                if extra["url"] == "https://example.com/recipes/#step-1"
+        ));
+
+        assert_eq!(recipe, recipe.with_base_url("not a url"));
+        assert_eq!(
+            Some("cake"),
+            recipe.with_base_url("/relative/").url().as_deref()
+        );
+    }
+
+    #[test]
+    fn test_schema_entry_top_level_array() {
+        let recipe = Recipe::new(
+            "A recipe",
+            "This is a recipe",
+            IngredientList::single("An ingredient"),
+        );
+        let json = r#"[
+            {"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient"},
+            {"@context": "https://schema.org"}
+        ]"#;
+        assert_eq!(
+            SchemaEntry::Multi(vec![
+                SchemaItem::Recipe(Box::new(recipe.clone())),
+                SchemaItem::Nonsense {
+                    context: "https://schema.org".into()
+                }
+            ]),
+            SchemaEntry::from_json_str(json).unwrap()
+        );
+
+        let json = r#"[
+            {"@type": "BreadcrumbList"},
+            {"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient"}
+        ]"#;
+        assert_eq!(
+            vec![&recipe],
+            SchemaEntry::from_json_str(json).unwrap().recipes()
+        );
+    }
+
+    #[test]
+    fn test_clean_name() {
+        let recipe = |name| Recipe::new(name, "", IngredientList::multi(Vec::<String>::new()));
+
+        assert_eq!(
+            "Best Chocolate Cake",
+            recipe("Best Chocolate Cake | MyFoodBlog").clean_name()
+        );
+        assert_eq!(
+            "Slow Cooker - Chicken",
+            recipe("Slow Cooker - Chicken").clean_name()
+        );
+        assert_eq!(
+            "Best Chocolate Cake",
+            recipe("Best Chocolate Cake - MyFoodBlog").clean_name_for_site("myfoodblog")
+        );
+
+        let html = |head: &str| {
+            format!(
+                r#"<html><head>{}<script type="application/ld+json">{}</script></head></html>"#,
+                head,
+                recipe_json(json!({"name": "Best Chocolate Cake - MyFoodBlog"}))
+            )
+        };
+        let clean_name = |head| {
+            let entries = <SchemaEntry as crate::Scrape>::scrape_html(html(head));
+            let recipe = entries[0].recipes()[0].clone();
+            (recipe.name().clone(), recipe.clean_name())
+        };
+
+        assert_eq!(
+            (
+                "Best Chocolate Cake - MyFoodBlog".to_string(),
+                "Best Chocolate Cake".to_string()
+            ),
+            clean_name(r#"<meta property="og:site_name" content="MyFoodBlog">"#)
+        );
+        assert_eq!(
+            "Best Chocolate Cake",
+            clean_name("<title>Best Chocolate Cake Recipe - MyFoodBlog</title>").1
+        );
+        assert_eq!(
+            "Best Chocolate Cake - MyFoodBlog",
+            clean_name("<title>Recipes</title>").1
+        );
+    }
+
+    #[test]
+    fn test_item_list() {
+        use crate::{Extract, Scrape};
+
+        let html = r#"<script type="application/ld+json">{"@graph": [
+            {"@type": "WebPage", "name": "Our 3 best cakes"},
+            {"@type": "ItemList", "itemListElement": [
+                {"@type": "ListItem", "position": 1, "url": "https://example.com/sponge",
+                    "name": "Sponge"},
+                {"@type": "ListItem", "position": 2, "@id": "https://roundup.example/#2",
+                    "item": {"@type": "Recipe", "name": "Carrot cake",
+                        "@id": "https://example.com/carrot",
+                        "image": [{"url": "small.jpg", "width": 10, "height": 10},
+                            {"url": "big.jpg", "width": 100, "height": 100}]}},
+                {"@type": "ListItem", "position": 3, "item": "https://example.com/lemon"},
+                {"@type": "ListItem", "position": 4}
+            ]}
+        ]}</script>"#;
+        let lists = ItemList::scrape_html(html);
+        assert_eq!(1, lists.len());
+
+        let stubs = lists[0].extract_recipes();
+        let summary = stubs
+            .iter()
+            .map(|s| {
+                (
+                    s.name().as_deref(),
+                    s.url().as_deref(),
+                    s.image().as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (Some("Sponge"), Some("https://example.com/sponge"), None),
+                (
+                    Some("Carrot cake"),
+                    Some("https://example.com/carrot"),
+                    Some("big.jpg")
+                ),
+                (None, Some("https://example.com/lemon"), None),
+            ],
+            summary
+        );
+    }
+
+    #[test]
+    fn test_raw_recipe() {
+        let value = json!({
+            "name": "Toast",
+            "recipeIngredient": "1 slice bread",
+            "steps": ["Toast it"],
+            "nutrition": {"calories": "80 kcal"},
+        });
+        let raw: RawRecipe = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(Some("Toast"), raw.name());
+        assert_eq!(vec!["1 slice bread"], raw.ingredients());
+        assert_eq!(
+            Some(InstructionList::Multi(vec![Instruction::simple(
+                "Toast it"
+            )])),
+            raw.instructions()
+        );
+        assert!(raw.as_recipe().is_err());
+        assert_eq!(value, serde_json::to_value(&raw).unwrap());
+        assert_eq!(value, raw.into_value());
+    }
+
+    #[test]
+    fn test_scrape_html_with_source() {
+        let source = recipe_json(json!({"x-plugin": true, "author": {"@id": "#jane"}}));
+        let html = format!(
+            r#"<script type="application/ld+json">{}</script>"#,
+            json!({"@graph": [source, {"@id": "#jane", "name": "Jane"}]})
+        );
+
+        let sourced = SchemaEntry::scrape_html_with_source(html);
+        assert_eq!(1, sourced.len());
+        assert_eq!("A recipe", sourced[0].recipe().name());
+        assert_eq!(json!(true), sourced[0].source()["x-plugin"]);
+        assert_eq!(json!("Jane"), sourced[0].source()["author"]["name"]);
+    }
+
+    #[test]
+    fn test_scrape_html_with_source_wrapped() {
+        use crate::Extract;
+
+        let named = |name| recipe_json(json!({ "name": name }));
+        let blocks = [
+            json!({"@type": "WebPage", "mainEntity": named("Paged")}),
+            json!({"@type": "Menu", "hasMenuSection": {
+                "@type": "MenuSection",
+                "hasMenuItem": [{"@type": "MenuItem", "name": "Item", "recipe": recipe_json(json!({"@type": "Recipe", "name": "Menued"}))}],
+            }}),
+            json!([
+                recipe_json(json!({"@id": "#dup", "name": "Dup"})),
+                recipe_json(json!({"@id": "#dup", "name": "Dup", "description": "Again"})),
+            ]),
+        ];
+        let html = blocks
+            .iter()
+            .map(|b| format!(r#"<script type="application/ld+json">{}</script>"#, b))
+            .collect::<String>();
+
+        let scraped = <SchemaEntry as crate::Scrape>::scrape_html(&html);
+        let scraped = scraped.iter().flat_map(|e| e.extract_recipes());
+        let sourced = SchemaEntry::scrape_html_with_source(&html);
+        assert_eq!(
+            scraped.collect::<Vec<_>>(),
+            sourced
+                .iter()
+                .map(|s| s.recipe().clone())
+                .collect::<Vec<_>>()
+        );
+        let names = sourced.iter().map(|s| s.recipe().name().as_str());
+        assert_eq!(vec!["Paged", "Menued", "Dup"], names.collect::<Vec<_>>());
+        assert_eq!(json!("Menued"), sourced[1].source()["name"]);
+        assert_eq!("Again", sourced[2].recipe().description());
+    }
+
+    #[test]
+    fn test_scrape_html_by_language() {
+        let block = |recipe: serde_json::Value| {
+            format!(r#"<script type="application/ld+json">{}</script>"#, recipe)
+        };
+        let html = [
+            block(recipe_json(json!({"name": "Cake", "inLanguage": "en"}))),
+            block(recipe_json(json!({"name": "Gâteau", "inLanguage": "fr"}))),
+            block(recipe_json(
+                json!({"name": "Kuchen", "inLanguage": {"@type": "Language", "name": "de"}}),
+            )),
+            block(recipe_json(json!({"name": "Cupcake", "inLanguage": "en"}))),
+            block(recipe_json(json!({"name": "Torte"}))),
+            block(json!({"@type": "WebPage", "mainEntity": recipe_json(
+                json!({"name": "Pastel", "inLanguage": "es"}),
+            )})),
+        ]
+        .concat();
+
+        let by_language = SchemaEntry::scrape_html_by_language(html);
+        let names = |lang: &str| {
+            let recipes = by_language[lang].iter();
+            recipes.map(|r| r.name().as_str()).collect::<Vec<_>>()
+        };
+        assert_eq!(5, by_language.len());
+        assert_eq!(vec!["Cake", "Cupcake"], names("en"));
+        assert_eq!(vec!["Gâteau"], names("fr"));
+        assert_eq!(vec!["Kuchen"], names("de"));
+        assert_eq!(vec!["Torte"], names(""));
+        assert_eq!(vec!["Pastel"], names("es"));
+    }
+
+    #[test]
+    fn test_preprocessors() {
+        let strip_suffix = |value: &mut serde_json::Value| {
+            if let Some(name) = value["name"].as_str() {
+                value["name"] = name.trim_end_matches(" - Example.com").into();
+            }
+        };
+        struct AddIngredients;
+        impl crate::PreProcessor for AddIngredients {
+            fn fix(&self, value: &mut serde_json::Value) {
+                value["recipeIngredient"] = json!(["bread"]);
+            }
+        }
+        struct Nothing;
+        impl crate::PreProcessor for Nothing {}
+
+        let json = json!({"@type": "Recipe", "name": "Toast - Example.com", "description": ""});
+        assert!(SchemaEntry::from_json_value(json.clone()).is_err());
+        let entry = SchemaEntry::from_json_value_with_preprocessors(
+            json.clone(),
+            &[&strip_suffix, &AddIngredients, &Nothing],
+        )
+        .unwrap();
+        let recipe = entry.recipes()[0];
+        assert_eq!("Toast", recipe.name());
+        assert_eq!(1, recipe.ingredients().len());
+
+        let html = format!(r#"<script type="application/ld+json">{}</script>"#, json);
+        let entries = SchemaEntry::scrape_html_with_preprocessors(html, &[&AddIngredients]);
+        assert_eq!("Toast - Example.com", entries[0].recipes()[0].name());
+    }
+
+    #[test]
+    fn test_menu_nested_recipes() {
+        let recipe = |name: &str| json!({"@type": "Recipe", "name": name, "description": "", "recipeIngredient": "x"});
+        let menu = json!({
+            "@context": "https://schema.org",
+            "@type": "Menu",
+            "name": "Dinner",
+            "hasMenuSection": [{
+                "@type": "MenuSection",
+                "name": "Mains",
+                "hasMenuItem": [
+                    {"@type": "MenuItem", "name": "Stew", "recipe": recipe("Stew")},
+                    {"@type": "MenuItem", "name": "Pie", "offers": {"@type": "Offer", "itemOffered": recipe("Not on the menu")}},
+                    {"@type": ["MenuItem", "Recipe"], "name": "Soup", "description": "", "recipeIngredient": "x"},
+                ],
+            }],
+        });
+        let html = format!(r#"<script type="application/ld+json">{}</script>"#, menu);
+        let entries = <SchemaEntry as crate::Scrape>::scrape_html(&html);
+        let names = entries
+            .iter()
+            .flat_map(SchemaEntry::recipes)
+            .map(Recipe::name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Stew", "Soup"], names);
+
+        let graph = json!({"@graph": [menu["hasMenuSection"][0], recipe("Bread")]});
+        let entry = SchemaEntry::from_json_value(graph).unwrap();
+        let names = entry
+            .recipes()
+            .into_iter()
+            .map(Recipe::name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Stew", "Soup", "Bread"], names);
+    }
+
+    #[test]
+    fn test_scrape_bytes() {
+        let mut html =
+            br#"<meta charset="iso-8859-1"><script type="application/ld+json">"#.to_vec();
+        html.extend(br#"{"name": "Cr"#);
+        html.push(0xe8);
+        html.extend(br#"me br"#);
+        html.push(0xfb);
+        html.extend(br#"l"#);
+        html.push(0xe9);
+        html.extend(br#"e", "description": "", "recipeIngredient": ""}</script>"#);
+
+        let entries = SchemaEntry::scrape_bytes(&html, None);
+        assert_eq!("Crème brûlée", entries[0].recipes()[0].name());
+    }
+
+    #[test]
+    fn test_sanitized() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "name": "Mac & Cheese<script>alert(1)</script>",
+            "recipeIngredient": ["<img src=x onerror=alert(1)>2 cups <b>macaroni</b>"],
+            "recipeInstructions": [{"text": "<a href=\"javascript:alert(1)\">Boil</a>"}],
+            "url": "javascript:alert(1)",
+        })))
+        .unwrap();
+        let sanitized = recipe.sanitized();
+
+        assert_eq!("Mac &amp; Cheese", sanitized.name());
+        assert_eq!(
+            vec!["2 cups <b>macaroni</b>"],
+            sanitized.ingredients().entries().collect::<Vec<_>>()
+        );
+        let steps = sanitized.directions().as_ref().unwrap().steps();
+        assert_eq!("<a>Boil</a>", steps[0].text());
+        assert_eq!(&None, sanitized.url());
+    }
+
+    #[test]
+    fn test_sanitized_every_field() {
+        let xss = "<script>alert(1)</script>x";
+        let js = "javascript:alert(1)";
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "name": xss,
+            "description": xss,
+            "recipeYield": xss,
+            "recipeIngredient": [xss],
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": xss, "itemListElement": [
+                    {"name": xss, "text": xss, "url": js, "image": [js, {"url": js, "thumbnailUrl": js}]},
+                ]},
+            ],
+            "image": [js, {"url": js, "thumbnail": js, "thumbnailUrl": js}],
+            "url": js,
+            "mainEntityOfPage": {"@id": js},
+            "author": [xss, {"@type": "Person", "name": xss, "url": js}],
+            "keywords": xss,
+            "recipeCategory": xss,
+            "recipeCuisine": xss,
+            "suitableForDiet": xss,
+            "cookingTechnique": xss,
+            "recipeNotes": [xss],
+            "nutrition": {"calories": xss, "servingSize": xss, "sodiumContent": xss},
+        })))
+        .unwrap();
+        let sanitized = serde_json::to_string(&recipe.sanitized()).unwrap();
+
+        assert!(!sanitized.contains("<script"), "{}", sanitized);
+        assert!(!sanitized.contains("alert"), "{}", sanitized);
+        assert!(!sanitized.contains("javascript:"), "{}", sanitized);
+        assert_eq!(None, recipe.sanitized().url().as_deref());
+        assert_eq!(
+            Some("x"),
+            recipe
+                .sanitized()
+                .nutrition()
+                .as_ref()
+                .unwrap()
+                .calories()
+                .as_deref()
+        );
+    }
+
+    #[test]
+    fn test_step_images() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Prep", "itemListElement": [
+                    {"text": "Chop", "image": "chop.jpg"},
+                    "Rest",
+                ]},
+                {"@type": "HowToSection", "name": "Cook", "itemListElement": [
+                    {"text": "Fry", "image": ["fry.jpg", {"@type": "ImageObject", "contentUrl": "pan.jpg"}]},
+                    {"text": "Serve", "image": {"url": "serve.jpg", "width": 800}},
+                ]},
+            ],
+        })))
+        .unwrap();
+
+        assert_eq!(
+            vec!["chop.jpg", "fry.jpg", "pan.jpg", "serve.jpg"],
+            recipe.step_images()
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = recipe(json!({
+            "recipeIngredient": ["2 cups flour", "1 tsp salt", "3 eggs"],
+            "recipeInstructions": ["Mix", "Knead", "Bake"],
+            "cookTime": "PT30M",
+            "prepTime": "PT10M",
+        }));
+        let new = recipe(json!({
+            "name": "A better recipe",
+            "recipeIngredient": ["3 eggs", "2 cups flour", "2 tsp salt"],
+            "recipeInstructions": ["Mix", "Rest", "Bake"],
+            "cookTime": "PT0H30M",
+            "prepTime": "PT15M",
+        }));
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            &Some(("A recipe".to_string(), "A better recipe".to_string())),
+            diff.name()
+        );
+        assert_eq!(&None, diff.description());
+        assert_eq!(&vec!["2 tsp salt".to_string()], diff.ingredients_added());
+        assert_eq!(&vec!["1 tsp salt".to_string()], diff.ingredients_removed());
+        assert_eq!(&vec![(1, "Rest".to_string())], diff.steps_added());
+        assert_eq!(&vec![(1, "Knead".to_string())], diff.steps_removed());
+        assert_eq!(&None, diff.cook_time());
+        let (_, prep_time) = diff.prep_time().clone().unwrap();
+        assert_eq!(Some(15), prep_time.and_then(|t| t.total_minutes()));
+        assert_eq!(&None, diff.total_time());
+        assert!(!diff.is_empty());
+
+        let reordered = recipe(json!({
+            "recipeIngredient": ["3 eggs", "1 tsp salt", "2 cups flour"],
+            "recipeInstructions": ["Mix", "Knead", "Bake"],
+            "cookTime": "PT30M",
+            "prepTime": "PT10M",
+        }));
+        assert!(old.diff(&reordered).is_empty());
+        let reordered_steps = recipe(json!({
+            "recipeIngredient": ["2 cups flour", "1 tsp salt", "3 eggs"],
+            "recipeInstructions": ["Knead", "Mix", "Bake"],
+            "cookTime": "PT30M",
+            "prepTime": "PT10M",
+        }));
+        assert!(!old.diff(&reordered_steps).is_empty());
+
+        let time = |total| recipe(json!({ "totalTime": total }));
+        assert!(time("PT90M").diff(&time("PT1H30M")).is_empty());
+        assert!(time("soon").diff(&recipe(json!({}))).is_empty());
+        assert!(!time("PT90M").diff(&time("PT1H")).is_empty());
+        assert!(!time("P1M").diff(&time("P30D")).is_empty());
+    }
+
+    #[test]
+    fn test_sequence_diff() {
+        let steps = |from: usize, to: usize| (from..to).map(|i| i.to_string()).collect::<Vec<_>>();
+        fn strs(steps: &[String]) -> Vec<&str> {
+            steps.iter().map(String::as_str).collect()
+        }
+
+        let (old, new) = (["a", "b", "c", "d", "e"], ["a", "b", "x", "d", "e"]);
+        assert_eq!(
+            (vec![(2, "x".to_string())], vec![(2, "c".to_string())]),
+            sequence_diff(&old, &new)
+        );
+        assert_eq!((vec![], vec![]), sequence_diff(&old, &old));
+        let (old, new) = (["a", "b"], ["a", "b", "b"]);
+        assert_eq!(
+            (vec![(2, "b".to_string())], vec![]),
+            sequence_diff(&old, &new)
+        );
+
+        // Far past the table's limit, entries are compared as sets instead
+        let (old, new) = (steps(0, 3000), steps(1500, 4500));
+        let (added, removed) = sequence_diff(&strs(&old), &strs(&new));
+        assert_eq!(
+            (1500..3000).collect::<Vec<_>>(),
+            added.iter().map(|(i, _)| *i).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            new[1500..].to_vec(),
+            added.into_iter().map(|(_, s)| s).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            old[..1500].to_vec(),
+            removed.into_iter().map(|(_, s)| s).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_timeline() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Prep", "itemListElement": [
+                    "Chop the onions",
+                    "Marinate for 2 hours",
+                ]},
+                {"@type": "HowToSection", "name": "Cook", "itemListElement": [
+                    "Fry for 5 minutes, then simmer for 20 minutes",
+                    "Serve",
+                ]},
+            ],
+        })))
+        .unwrap();
+
+        let minutes = |m: u64| std::time::Duration::from_secs(m * 60);
+        assert_eq!(
+            vec![(1, minutes(120)), (2, minutes(5)), (2, minutes(20))],
+            recipe.timeline()
+        );
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({}))).unwrap();
+        assert!(recipe.timeline().is_empty());
+    }
+
+    #[test]
+    fn test_is_quick() {
+        let half_hour = std::time::Duration::from_secs(30 * 60);
+
+        assert!(recipe(json!({"totalTime": "PT30M", "cookTime": "PT2H"})).is_quick(half_hour));
+        assert!(!recipe(json!({"totalTime": "PT31M"})).is_quick(half_hour));
+        assert!(recipe(json!({"prepTime": "PT10M", "cookTime": "PT20M"})).is_quick(half_hour));
+        assert!(!recipe(json!({"prepTime": "PT10M", "cookTime": "PT25M"})).is_quick(half_hour));
+        assert!(!recipe(json!({"totalTime": "P1M"})).is_quick(half_hour));
+        assert!(!recipe(json!({})).is_quick(half_hour));
+    }
+
+    #[test]
+    fn test_total_time_or_estimate() {
+        let estimate = |extra| {
+            serde_json::from_value::<Recipe>(recipe_json(extra))
+                .unwrap()
+                .total_time_or_estimate()
+                .as_secs()
+                / 60
+        };
+
+        assert_eq!(90, estimate(json!({"totalTime": "PT1H30M"})));
+        assert_eq!(
+            25,
+            estimate(json!({"prepTime": "PT25M", "totalTime": "later"}))
+        );
+        assert_eq!(
+            15,
+            estimate(json!({"recipeInstructions": ["Mix", "Bake", "Serve"]}))
+        );
+        assert_eq!(5, estimate(json!({"totalTime": "P1M"})));
+        assert_eq!(5, estimate(json!({})));
+    }
+
+    #[test]
+    fn test_plan_timeline() {
+        use std::time::{Duration, SystemTime};
+
+        let recipes = [
+            recipe(json!({"name": "Roast", "totalTime": "PT1H30M"})),
+            recipe(json!({"name": "Salad", "recipeInstructions": ["Chop", "Toss"]})),
+        ];
+        let serve_at = SystemTime::UNIX_EPOCH + Duration::from_secs(24 * 60 * 60);
+
+        let minutes_before = plan_timeline(&recipes, serve_at)
+            .into_iter()
+            .map(|(name, start)| (name, serve_at.duration_since(start).unwrap().as_secs() / 60))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("Roast".to_string(), 90), ("Salad".to_string(), 10)],
+            minutes_before
+        );
+    }
+
+    #[test]
+    fn test_ingredients_by_unit_type() {
+        use crate::UnitType;
+
+        let recipe = recipe_json(json!({"recipeIngredient": [
+            "1 cup milk", "200 g flour", "2 eggs", "1 tsp salt", "pepper",
+        ]}));
+        let groups = serde_json::from_value::<Recipe>(recipe)
+            .unwrap()
+            .ingredients_by_unit_type();
+        let names = |unit_type| {
+            let group = groups.get(&unit_type).map_or(&[][..], Vec::as_slice);
+            group.iter().map(|i| i.name().as_str()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["milk", "salt"], names(UnitType::Volume));
+        assert_eq!(vec!["flour"], names(UnitType::Weight));
+        assert_eq!(vec!["eggs"], names(UnitType::Count));
+        assert_eq!(vec!["pepper"], names(UnitType::Unknown));
+    }
+
+    #[test]
+    fn test_estimated_servings() {
+        let servings = |ingredients| {
+            let recipe = recipe_json(json!({ "recipeIngredient": ingredients }));
+            serde_json::from_value::<Recipe>(recipe)
+                .unwrap()
+                .estimated_servings()
+        };
+
+        assert_eq!(
+            Some(4),
+            servings(json!(["600g chicken thighs", "200 g rice", "1 onion"]))
+        );
+        assert_eq!(Some(6), servings(json!(["2 lb salmon fillet", "1 lemon"])));
+        assert_eq!(
+            Some(5),
+            servings(json!(["500 g spaghetti", "2 cloves garlic"]))
+        );
+        assert_eq!(Some(1), servings(json!(["50g tofu"])));
+        assert_eq!(None, servings(json!(["2 chicken breasts", "1 cup rice"])));
+        assert_eq!(None, servings(json!(["200 g flour", "2 eggs"])));
+    }
+
+    #[test]
+    fn test_dedup_best() {
+        let sparse = recipe(json!({"name": "Toast", "totalTime": "PT5M"}));
+        let rich = recipe(json!({"name": "toast", "url": "https://b.example/toast",
+            "image": "https://b.example/toast.jpg", "recipeYield": 1}));
+        let other = recipe(json!({"name": "Jam", "url": "https://a.example/jam"}));
+
+        let best = dedup_best(vec![sparse.clone(), other.clone(), rich.clone()]);
+        assert_eq!(2, best.len());
+        assert_eq!("toast", best[0].name());
+        assert_eq!(&Some("https://b.example/toast".to_string()), best[0].url());
+        assert_eq!(Some(5), best[0].total_minutes());
+        assert_eq!(other, best[1]);
+
+        assert_eq!(
+            Some("toast"),
+            pick_best(vec![other, sparse, rich])
+                .as_ref()
+                .map(|r| r.name().as_str())
+        );
+        assert_eq!(None, pick_best(Vec::new()));
+    }
+
+    #[test]
+    fn test_active_time_human() {
+        let active = |extra| {
+            serde_json::from_value::<Recipe>(recipe_json(extra))
+                .unwrap()
+                .active_time_human()
+        };
+
+        assert_eq!(
+            Some("1h 15m"),
+            active(json!({"prepTime": "PT45M", "cookTime": "PT30M"})).as_deref()
+        );
+        assert_eq!(
+            Some("20m"),
+            active(json!({"cookTime": "PT20M", "prepTime": "soon"})).as_deref()
+        );
+        assert_eq!(None, active(json!({"totalTime": "PT1H"})));
     }
-}
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum SchemaEntry {
-    Graph {
-        #[serde(rename = "@graph")]
-        graph: Vec<GraphEntry>,
-    },
-    Single(SchemaItem),
-    Multi(Vec<SchemaItem>),
-}
+    #[test]
+    fn test_to_json_ld_string() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "@type": "Recipe",
+            "author": [{"@type": "Organization", "name": "Test Kitchen"}, "Jo"],
+            "prepTime": "PT10M",
+            "recipeYield": {"value": 4, "unitText": "servings"},
+            "recipeIngredient": [
+                {"@type": "HowToSupply", "name": "flour", "requiredQuantity": "2 cups"},
+                "1 egg",
+            ],
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Mix", "itemListElement": [
+                    {"@type": "HowToStep", "text": "Whisk", "position": 1},
+                ]},
+            ],
+            "nutrition": {"calories": "200 kcal"},
+        })))
+        .unwrap();
 
-impl crate::Extract for SchemaEntry {
-    type Output = Recipe;
-    type Collection = Vec<Self::Output>;
+        let compact = recipe.to_json_ld_string_compact();
+        let pretty = recipe.to_json_ld_string_pretty();
+        assert!(!compact.contains('\n'));
+        assert!(pretty.contains('\n'));
 
-    fn extract_recipes(&self) -> Self::Collection {
-        match self {
-            Self::Graph { graph } => graph.iter().filter_map(|e| e.recipe()).cloned().collect(),
-            Self::Single(r) => r.recipe().into_iter().cloned().collect(),
-            Self::Multi(entries) => entries.iter().filter_map(|e| e.recipe()).cloned().collect(),
+        for json in [compact, pretty] {
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!("https://schema.org", value["@context"]);
+            assert_eq!(None, value.get("cookTime"));
+            assert_eq!(recipe, serde_json::from_value(value).unwrap());
         }
-    }
-}
 
-impl crate::Scrape for SchemaEntry {
-    type Output = Self;
-    type Collection = Vec<Self::Output>;
+        let untyped = Recipe::new("A recipe", "", IngredientList::single("An ingredient"));
+        let value: serde_json::Value =
+            serde_json::from_str(&untyped.to_json_ld_string_compact()).unwrap();
+        assert_eq!("Recipe", value["@type"]);
 
-    fn scrape_html(html: impl AsRef<str>) -> Self::Collection {
-        let html = scraper::Html::parse_document(html.as_ref());
-        let try_from_json = |json| SchemaEntry::from_json_str(json).ok();
+        let unparseable: Recipe =
+            serde_json::from_value(recipe_json(json!({"@type": "Recipe", "cookTime": "soon"})))
+                .unwrap();
+        let value: serde_json::Value =
+            serde_json::from_str(&unparseable.to_json_ld_string_compact()).unwrap();
+        assert_eq!("soon", value["cookTime"]);
+        assert_eq!(unparseable, serde_json::from_value(value).unwrap());
+    }
 
-        // Unwrap is appropriate here as this is a static selector, which we can reasonably expect
-        // to parse successfully
-        let selector = scraper::Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    #[test]
+    fn test_preview() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "recipeIngredient": "2 cups flour\n1 egg\n\n1 cup milk",
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Batter", "itemListElement": ["Whisk", "Rest"]},
+                {"@type": "HowToSection", "name": "Cook", "itemListElement": ["Fry", "Serve"]},
+            ],
+        })))
+        .unwrap();
 
-        html.select(&selector)
-            .map(|el| el.text().collect::<String>())
-            .filter_map(try_from_json)
-            .collect::<Vec<_>>()
-    }
-}
+        let preview = recipe.preview(2, 3);
+        assert_eq!(
+            vec!["2 cups flour", "1 egg", "…and 1 more"],
+            preview.ingredients().entries().collect::<Vec<_>>()
+        );
+        let steps = preview.directions().as_ref().unwrap().steps();
+        assert_eq!(
+            vec!["Whisk", "Rest", "Fry", "…and 1 more"],
+            steps.iter().map(|s| s.text()).collect::<Vec<_>>()
+        );
 
-impl SchemaEntry {
-    pub fn from_json_bytes(b: impl AsRef<[u8]>) -> Result<Self, serde_json::Error> {
-        serde_json::from_slice(b.as_ref())
+        assert_eq!(recipe, recipe.preview(3, 4));
     }
 
-    pub fn from_json_reader(r: impl std::io::Read) -> Result<Self, serde_json::Error> {
-        serde_json::from_reader(r)
-    }
+    #[test]
+    fn test_to_html() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "@type": "Recipe",
+            "name": "Mac & <b>Cheese</b>",
+            "description": "Creamy </script><script>alert(1)</script>",
+            "recipeIngredient": ["2 cups macaroni", "1 cup \"sharp\" cheddar"],
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Pasta", "itemListElement": ["Boil"]},
+                {"@type": "HowToSection", "name": "Sauce", "itemListElement": ["Melt", "Stir"]},
+            ],
+        })))
+        .unwrap();
 
-    pub fn from_json_str(json: impl AsRef<str>) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json.as_ref())
-    }
+        let html = recipe.to_html();
+        assert!(html.starts_with(
+            "<article class=\"recipe\">\n\
+             <h1>Mac &amp; &lt;b&gt;Cheese&lt;/b&gt;</h1>\n\
+             <p>Creamy &lt;/script&gt;&lt;script&gt;alert(1)&lt;/script&gt;</p>\n\
+             <section class=\"ingredients\">\n<h2>Ingredients</h2>\n<ul>\n\
+             <li>2 cups macaroni</li>\n<li>1 cup &quot;sharp&quot; cheddar</li>\n</ul>\n</section>\n\
+             <section class=\"instructions\">\n<h2>Instructions</h2>\n\
+             <h3>Pasta</h3>\n<ol>\n<li>Boil</li>\n</ol>\n\
+             <h3>Sauce</h3>\n<ol>\n<li>Melt</li>\n<li>Stir</li>\n</ol>\n</section>\n"
+        ));
 
-    pub fn from_json_value(json: serde_json::Value) -> Result<Self, serde_json::Error> {
-        serde_json::from_value(json)
+        let document = scraper::Html::parse_fragment(&html);
+        let selector = scraper::Selector::parse("script").unwrap();
+        assert_eq!(1, document.select(&selector).count());
+        let entries = <SchemaEntry as crate::Scrape>::scrape_html(&html);
+        assert_eq!(vec![&recipe], entries[0].recipes());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
 
     #[test]
-    fn test_maybe_duration() {
+    fn test_sections() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "recipeIngredient": [
+                "For the dough:", "2 cups flour", "1 egg",
+                "For the glaze:", "1 cup sugar",
+                "Sauce:", "1 cup cream",
+            ],
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Dough", "itemListElement": ["Knead"]},
+                {"@type": "HowToSection", "name": "Bake", "itemListElement": ["Bake"]},
+                {"@type": "HowToSection", "name": "Sauce", "itemListElement": ["Whisk"]},
+            ],
+        })))
+        .unwrap();
+
         assert_eq!(
-            MaybeDuration::from_secs(2i8),
-            serde_json::from_value(json!("PT2S")).unwrap()
+            vec![
+                ("dough", vec!["2 cups flour", "1 egg"]),
+                ("glaze", vec!["1 cup sugar"]),
+                ("Sauce", vec!["1 cup cream"]),
+            ],
+            recipe.ingredients().groups()
         );
 
+        let sections = recipe.sections();
+        let summary = sections
+            .iter()
+            .map(|s| (s.name().as_str(), s.ingredients().len(), s.steps().len()))
+            .collect::<Vec<_>>();
         assert_eq!(
-            MaybeDuration(None),
-            serde_json::from_value(json!("PTnullH")).unwrap()
+            vec![
+                ("glaze", 1, 0),
+                ("Dough", 2, 1),
+                ("Bake", 0, 1),
+                ("Sauce", 1, 1)
+            ],
+            summary
         );
+
+        let plain: Recipe = serde_json::from_value(recipe_json(json!({
+            "recipeIngredient": ["1 cup: rice", "2 cups water"],
+            "recipeInstructions": ["Boil", "Simmer"],
+        })))
+        .unwrap();
+        let sections = plain.sections();
+        assert_eq!(1, sections.len());
+        assert_eq!("", sections[0].name());
+        assert_eq!(2, sections[0].ingredients().len());
+        assert_eq!(2, sections[0].steps().len());
     }
 
     #[test]
-    fn test_ingredient_list_single() {
-        let data = json!("The first ingredient.\nThe second one.");
-        let result = serde_json::from_value(data);
-        assert!(result.is_ok());
+    fn test_to_cooklang() {
+        let recipe: Recipe = serde_json::from_value(recipe_json(json!({
+            "name": "Soup",
+            "url": "https://example.com/soup",
+            "recipeYield": "4",
+            "recipeIngredient": ["2 cups stock", "1 onion, diced", "salt"],
+            "recipeInstructions": [
+                {"@type": "HowToSection", "name": "Prep", "itemListElement": ["Dice the onion"]},
+                {"@type": "HowToSection", "name": "Cook", "itemListElement": ["Simmer\n  gently"]},
+            ],
+        })))
+        .unwrap();
+
         assert_eq!(
-            IngredientList::single("The first ingredient.\nThe second one."),
-            result.unwrap()
-        )
+            ">> title: Soup\n\
+             >> source: https://example.com/soup\n\
+             >> servings: 4\n\
+             \n\
+             @stock{2%cup}\n\
+             @onion{1}(diced)\n\
+             @salt{}\n\
+             \n\
+             == Prep ==\n\
+             \n\
+             Dice the onion\n\
+             \n\
+             == Cook ==\n\
+             \n\
+             Simmer gently\n",
+            recipe.to_cooklang()
+        );
     }
 
     #[test]
-    fn test_ingredient_list_multi() {
-        let data = json!(["The first ingredient", "The second one"]);
-        let result = serde_json::from_value(data);
-        assert!(result.is_ok());
+    fn test_content_hash() {
+        let hash = |extra| {
+            serde_json::from_value::<Recipe>(recipe_json(extra))
+                .unwrap()
+                .content_hash()
+        };
+        let base = hash(
+            json!({"name": "Toast", "recipeIngredient": ["1 slice bread"],
+            "recipeInstructions": ["Toast it"]}),
+        );
+
         assert_eq!(
-            IngredientList::multi(["The first ingredient", "The second one"]),
-            result.unwrap()
+            base,
+            hash(
+                json!({"name": " TOAST ", "recipeIngredient": ["1  slice bread"],
+                "recipeInstructions": [{"@type": "HowToSection", "name": "Only",
+                    "itemListElement": ["toast it"]}],
+                "url": "https://example.com/toast", "totalTime": "PT5M"})
+            )
+        );
+        assert_ne!(
+            base,
+            hash(json!({"name": "Toast", "recipeIngredient": ["1 slice bread", "Toast it"]}))
+        );
+        assert_ne!(
+            base,
+            hash(json!({"name": "Toast", "recipeIngredient": ["1 slice bread"]}))
         );
     }
 
     #[test]
-    fn test_instruction_simple() {
-        let data = json!("Do a thing");
-        let result = serde_json::from_value(data);
-        assert!(result.is_ok());
-        assert_eq!(Instruction::simple("Do a thing"), result.unwrap());
+    fn test_eq_ignoring_times() {
+        let timed = recipe(json!({"recipeInstructions": ["Toast"], "totalTime": "PT5M"}));
+
+        assert!(timed.eq_ignoring_times(&recipe(json!({"recipeInstructions": ["Toast"]}))));
+        assert!(timed.eq_ignoring_times(&recipe(json!({
+            "recipeInstructions": ["Toast"], "prepTime": "PT1M", "cookTime": "PT4M"
+        }))));
+        assert!(!timed.eq_ignoring_times(&recipe(json!({"recipeInstructions": ["Burn"]}))));
     }
 
     #[test]
-    fn test_instruction_structured() {
-        let data = json!({"name": "Do a thing", "text": "Do a thing"});
-        let result = serde_json::from_value(data);
-        assert!(result.is_ok());
-        assert_eq!(Instruction::structured("Do a thing"), result.unwrap());
+    fn test_recipe_types() {
+        let types = |extra| {
+            serde_json::from_value::<Recipe>(recipe_json(extra))
+                .unwrap()
+                .types()
+        };
+
+        assert_eq!(vec!["Recipe"], types(json!({"@type": "Recipe"})));
+        assert_eq!(
+            vec!["Recipe", "NewsArticle"],
+            types(json!({"@type": ["Recipe", "NewsArticle"]}))
+        );
+        assert!(types(json!({})).is_empty());
+        assert!(types(json!({"@type": {"@id": "Recipe"}})).is_empty());
     }
 
     #[test]
-    fn test_instruction_section() {
-        let data = json!({"name": "Prep the thing", "itemListElement": [{"name": "Do a thing", "text": "Do a thing"}]});
-        let result = serde_json::from_value(data);
-        assert!(result.is_ok());
+    fn test_json_script_recipes() {
+        let state = json!({
+            "props": {"pageProps": {"content": [
+                {"@type": "Recipe", "name": "Nested", "description": "", "recipeIngredient": ""},
+                {"schema": {"@type": ["Recipe"], "name": "Deeper", "description": "", "recipeIngredient": ""}},
+                {"@type": "Article", "name": "Not a recipe"},
+            ]}},
+        });
+        let html = format!(
+            r#"<script id="__NEXT_DATA__" type="application/json">{}</script>
+            <script type="application/json">{{"@type": "Recipe", "name": "{}"}}</script>
+            <script type="application/json">not json</script>"#,
+            state,
+            "x".repeat(MAX_JSON_SCRIPT_BYTES)
+        );
+        let recipes = json_script_recipes(&scraper::Html::parse_document(&html));
+
         assert_eq!(
-            InstructionSection::new("Prep the thing", [Instruction::structured("Do a thing")]),
-            result.unwrap()
+            vec!["Nested", "Deeper"],
+            recipes.iter().map(Recipe::name).collect::<Vec<_>>()
         );
     }
 
     #[test]
-    fn test_instruction_list_single() {
+    fn test_attribute_recipes() {
+        let html = r#"
+            <div data-recipe='{"@type": "Recipe", "name": "Mac &amp; Cheese", "description": "", "recipeIngredient": ""}'></div>
+            <div data-props="{&amp;quot;recipe&amp;quot;: {&amp;quot;@type&amp;quot;: &amp;quot;Recipe&amp;quot;, &amp;quot;name&amp;quot;: &amp;quot;Toast&amp;quot;, &amp;quot;description&amp;quot;: &amp;quot;&amp;quot;, &amp;quot;recipeIngredient&amp;quot;: &amp;quot;&amp;quot;}}"></div>
+            <div data-other='{"@type": "Recipe", "name": "Ignored", "description": "", "recipeIngredient": ""}'></div>
+            <div data-recipe="not json"></div>"#;
+        let attributes = vec!["data-recipe".to_string(), "data-props".to_string()];
+        let recipes = attribute_recipes(&scraper::Html::parse_document(html), &attributes);
+
         assert_eq!(
-            InstructionList::Single(Instruction::simple("Do a thing")),
-            serde_json::from_value(json!("Do a thing")).unwrap(),
+            vec!["Mac & Cheese", "Toast"],
+            recipes.iter().map(Recipe::name).collect::<Vec<_>>()
         );
+    }
+
+    #[test]
+    fn test_has_recipe() {
+        let script =
+            |body: &str| format!(r#"<script type="application/ld+json">{}</script>"#, body);
+
+        assert!(has_recipe(&script(
+            r#"{"@type": "Recipe", "name": "Cake"}"#
+        )));
+        assert!(has_recipe(&script(
+            r#"{"@graph": [{"@type": ["NewsArticle", "Recipe"]}]}"#
+        )));
+        assert!(!has_recipe(&script(
+            r#"{"@type": "WebPage", "name": "Recipe index"}"#
+        )));
+        assert!(!has_recipe(r#"<p>"@type": "Recipe"</p>"#));
+    }
 
+    #[test]
+    fn test_list_schema_types() {
+        let html = r#"
+            <script type="application/ld+json">
+                {"@context": "https://schema.org", "@graph": [
+                    {"@type": "WebPage", "breadcrumb": {"@type": "BreadcrumbList"}},
+                    {"@type": ["Recipe", "NewsArticle"], "author": {"@type": "Person"}}
+                ]}
+            </script>
+            <script type="application/ld+json">{"@type": "WebPage"}</script>
+            <script type="application/ld+json">not json</script>"#;
         assert_eq!(
-            InstructionList::Single(Instruction::structured("Do a thing")),
-            serde_json::from_value(json!({"name": "Do a thing", "text": "Do a thing"})).unwrap()
+            vec![
+                "WebPage",
+                "BreadcrumbList",
+                "Recipe",
+                "NewsArticle",
+                "Person"
+            ],
+            list_schema_types(html)
         );
+        assert!(list_schema_types("<p>Nothing here</p>").is_empty());
     }
 
     #[test]
-    fn test_instruction_list_multi() {
+    fn test_schema_entry_context_and_graph() {
+        let recipe = recipe_json(json!({}));
+        let names = |json: serde_json::Value| {
+            let entry = SchemaEntry::from_json_value(json).unwrap();
+            let recipes = entry.recipes();
+            recipes.iter().map(|r| r.name().clone()).collect::<Vec<_>>()
+        };
+
+        let graph = json!({"@context": "https://schema.org", "@graph": [recipe.clone()]});
+        assert_eq!(vec!["A recipe"], names(graph.clone()));
         assert_eq!(
-            InstructionList::Multi(vec![
-                Instruction::simple("Do a thing"),
-                Instruction::simple("Do another thing")
-            ]),
-            serde_json::from_value(json!(["Do a thing", "Do another thing"])).unwrap(),
+            vec!["A recipe", "A recipe"],
+            names(json!([graph, {"@context": "https://schema.org"}, recipe]))
         );
+    }
+
+    #[test]
+    fn test_schema_entry_context_forms() {
+        let recipe = recipe_json(json!({"@type": "Recipe"}));
+        let contexts = [
+            json!(["https://schema.org", {"@language": "en"}]),
+            json!({"@vocab": "https://schema.org/"}),
+            json!([{"ex": "https://example.com/"}, {"@vocab": "http://schema.org/"}]),
+        ];
+
+        for context in contexts {
+            let mut node = recipe.clone();
+            node["@context"] = context.clone();
+            let graph = json!({"@context": context.clone(), "@graph": [recipe.clone()]});
+            let site = json!({"@context": context, "@type": "WebSite"});
+
+            for json in [node, graph, json!([site.clone(), recipe.clone()])] {
+                let entry = SchemaEntry::from_json_value(json).unwrap();
+                assert_eq!(1, entry.recipes().len());
+            }
+            let entry = SchemaEntry::from_json_value(site).unwrap();
+            assert!(matches!(
+                entry,
+                SchemaEntry::Single(SchemaItem::Nonsense { context }) if context.contains("schema.org")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_schema_entry_main_entity() {
+        let recipe = |name: &str| recipe_json(json!({"name": name}));
+        let names = |json: serde_json::Value| {
+            let entry = SchemaEntry::from_json_value(json).unwrap();
+            let recipes = entry.recipes();
+            recipes.iter().map(|r| r.name().clone()).collect::<Vec<_>>()
+        };
 
         assert_eq!(
-            InstructionList::Multi(vec![
-                Instruction::structured("Do a thing"),
-                Instruction::structured("Do another thing"),
-            ]),
-            serde_json::from_value(json!([
-                    {"name": "Do a thing", "text": "Do a thing"},
-                    {"name": "Do another thing", "text": "Do another thing"}
-            ]))
-            .unwrap()
+            vec!["Single"],
+            names(json!({"@type": "WebPage", "mainEntity": recipe("Single")}))
         );
-
         assert_eq!(
-            InstructionList::Multi(vec![
-                Instruction::simple("Do a thing"),
-                Instruction::structured("Do another thing")
-            ]),
-            serde_json::from_value(json!([
-                    "Do a thing",
-                    {"name": "Do another thing", "text": "Do another thing"}
-            ]))
-            .unwrap()
+            vec!["First", "Second"],
+            names(json!({
+                "@context": "https://schema.org",
+                "@type": "WebPage",
+                "mainEntity": [recipe("First"), {"@type": "Person"}, recipe("Second")],
+            }))
+        );
+        assert_eq!(
+            vec!["Nested"],
+            names(json!([{"@type": "WebPage", "mainEntity": [recipe("Nested")]}]))
         );
     }
 
     #[test]
-    fn test_instruction_list_sections() {
+    fn test_extract_recipes_dedupes_by_id() {
+        use crate::Extract;
+
+        let entry = SchemaEntry::from_json_value(json!([
+            {"@context": "https://schema.org", "@graph": [
+                {"@id": "#recipe", "@type": "Recipe", "name": "Cake", "description": "", "recipeIngredient": "flour"},
+                {"@type": "Recipe", "name": "Anonymous", "description": "", "recipeIngredient": ""},
+            ]},
+            {"@id": "#recipe", "@type": "Recipe", "name": "Cake", "description": "Fluffy", "recipeIngredient": "", "recipeYield": "8"},
+            {"@type": "Recipe", "name": "Anonymous", "description": "", "recipeIngredient": ""},
+        ]))
+        .unwrap();
+
+        let recipes = entry.extract_recipes();
+        let names = recipes
+            .iter()
+            .map(|r| r.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Cake", "Anonymous", "Anonymous"], names);
+        assert_eq!(&Some("#recipe".to_string()), recipes[0].id());
+        assert_eq!("Fluffy", recipes[0].description());
         assert_eq!(
-            InstructionList::Sections(vec![
-                InstructionSection::new(
-                    "Prep the thing",
-                    [Instruction::structured("Do the thing")]
-                ),
-                InstructionSection::new(
-                    "Cook the thing",
-                    [Instruction::structured("Do the other thing")]
-                )
-            ]),
-            serde_json::from_value(json!([
-                {"name": "Prep the thing", "itemListElement": [{"name": "Do the thing", "text": "Do the thing"}]},
-                {"name": "Cook the thing", "itemListElement": [{"name": "Do the other thing", "text": "Do the other thing"}]},
-            ]))
-            .unwrap(),
+            vec!["flour"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
         );
+        assert_eq!("8", recipes[0].yields().as_ref().unwrap().to_string());
     }
 
     #[test]
-    fn test_quantity() {
+    fn test_json_ld_keywords() {
+        let json = json!({
+            "@context": {"@vocab": "https://schema.org/", "@language": "en", "@base": "https://example.com/"},
+            "@graph": [
+                {"@type": "WebPage", "@id": "#page", "@reverse": {"isPartOf": {"@id": "#recipe"}}},
+                {
+                    "@type": "Person",
+                    "@id": "#jane",
+                    "name": "Jane",
+                    "@reverse": {"author": [{"@id": "#recipe"}]},
+                },
+                {
+                    "@type": "Recipe",
+                    "@id": "#recipe",
+                    "@language": "en",
+                    "@index": "main",
+                    "name": {"@value": "Cake", "@language": "en"},
+                    "description": {"@value": "Fluffy"},
+                    "recipeIngredient": [{"@value": "flour", "@language": "en"}, "eggs"],
+                    "author": {"@id": "#jane"},
+                    "@reverse": {"hasPart": {"@id": "#page"}},
+                },
+            ],
+        });
+
+        let entry = SchemaEntry::from_json_value(json.clone()).unwrap();
+        let recipes = entry.recipes();
+        assert_eq!(1, recipes.len());
+        assert_eq!("Cake", recipes[0].name());
+        assert_eq!("Fluffy", recipes[0].description());
         assert_eq!(
-            Quantity::number(2),
-            serde_json::from_value(json!(2)).unwrap()
+            vec!["flour", "eggs"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
         );
-
+        assert_eq!(Some("#recipe"), recipes[0].id().as_deref());
         assert_eq!(
-            Quantity::string("2 cups"),
-            serde_json::from_value(json!("2 cups")).unwrap()
+            recipes,
+            SchemaEntry::extract_recipes_tolerant(json)
+                .iter()
+                .collect::<Vec<_>>()
         );
     }
 
     #[test]
-    fn test_yield() {
+    fn test_extract_recipes_tolerant() {
+        let json = json!({"@context": "https://schema.org", "@graph": [
+            {"@type": "WebPage", "name": "Not a recipe"},
+            {
+                "@type": "Recipe",
+                "name": "Cake",
+                "description": "Fluffy",
+                "recipeIngredient": ["flour"],
+                "recipeInstructions": 5,
+                "recipeYield": true,
+                "suitableForDiet": {"diet": 1},
+                "cookTime": "PT30M",
+            },
+            {"@type": "Recipe", "name": ["Not", "a", "string"], "recipeIngredient": {}},
+        ]});
+        assert!(SchemaEntry::from_json_value(json.clone())
+            .unwrap()
+            .recipes()
+            .is_empty());
+
+        let recipes = SchemaEntry::extract_recipes_tolerant(json);
+        assert_eq!(2, recipes.len());
+        assert_eq!("Cake", recipes[0].name());
+        assert_eq!("Fluffy", recipes[0].description());
         assert_eq!(
-            Yield::Single(Quantity::number(2)),
-            serde_json::from_value(json!(2)).unwrap()
+            vec!["flour"],
+            recipes[0].ingredients().entries().collect::<Vec<_>>()
         );
+        assert_eq!(None, recipes[0].directions().as_ref());
+        assert_eq!(None, recipes[0].yields().as_ref());
+        assert_eq!(None, recipes[0].diet().as_ref());
+        assert_eq!(Some(30), recipes[0].cook_minutes());
+        assert_eq!("", recipes[1].name());
+        assert!(recipes[1].ingredients().is_empty());
+    }
+
+    #[test]
+    fn test_extract_recipes_preserves_order() {
+        use crate::Extract;
+
+        let recipe = |name: &str| json!({"name": name, "description": "", "recipeIngredient": ""});
+        let entry: SchemaEntry = serde_json::from_value(json!({"@graph": [
+            recipe("First"),
+            {"@id": "website"},
+            recipe("Second"),
+            {"@id": "breadcrumbs"},
+            recipe("Third"),
+        ]}))
+        .unwrap();
+
+        let names = entry.extract_recipes();
+        let names = names.iter().map(Recipe::name).collect::<Vec<_>>();
+        assert_eq!(vec!["First", "Second", "Third"], names);
+    }
+
+    #[test]
+    fn test_graph_references() {
+        let entry = SchemaEntry::from_json_value(json!({"@graph": [
+            {
+                "@id": "#recipe",
+                "name": "A recipe",
+                "description": "This is a recipe",
+                "recipeIngredient": "An ingredient",
+                "author": {"@id": "#author"},
+                "isPartOf": {"@id": "#recipe"}
+            },
+            {"@id": "#author", "@type": "Person", "name": "Jane", "url": "https://example.com/jane"},
+        ]}))
+        .unwrap();
 
         assert_eq!(
-            Yield::Multi(vec![Quantity::string("2"), Quantity::string("2 cups"),]),
-            serde_json::from_value(json!(["2", "2 cups"])).unwrap()
+            Some("https://example.com/jane"),
+            entry.recipes()[0].first_author_url()
         );
     }
 
     #[test]
-    fn test_recipe() {
-        assert_eq!(
-            Recipe::new(
-                "A recipe",
-                "This is a recipe",
-                IngredientList::single("An ingredient. Another one")
+    fn test_into_recipes() {
+        use crate::Extract;
+
+        let entry: SchemaEntry = serde_json::from_value(json!({"@graph": [
+            {"@id": "#website"},
+            {"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient"},
+        ]}))
+        .unwrap();
+
+        let extracted = entry.extract_recipes();
+        assert_eq!(extracted.iter().collect::<Vec<_>>(), entry.recipes());
+        assert_eq!(extracted, entry.into_recipes());
+    }
+
+    #[test]
+    fn test_primary_recipe() {
+        use crate::Extract;
+
+        let recipe = |name: &str, extra: serde_json::Value| {
+            let mut value =
+                json!({"name": name, "description": "", "recipeIngredient": "An ingredient"});
+            value
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            value
+        };
+
+        let entry: SchemaEntry = serde_json::from_value(json!([
+            recipe("First", json!({})),
+            recipe(
+                "Complete",
+                json!({"description": "A description", "url": "/complete"})
             ),
-            serde_json::from_value(
-                json!({"name": "A recipe", "description": "This is a recipe", "recipeIngredient": "An ingredient. Another one" })
-            ).unwrap()
-        );
+        ]))
+        .unwrap();
+        assert_eq!("Complete", entry.primary_recipe().unwrap().name());
+
+        let entry: SchemaEntry = serde_json::from_value(json!([
+            recipe(
+                "Complete",
+                json!({"description": "A description", "url": "/complete"})
+            ),
+            recipe(
+                "Main",
+                json!({"mainEntityOfPage": "https://example.com/main"})
+            ),
+        ]))
+        .unwrap();
+        assert_eq!("Main", entry.primary_recipe().unwrap().name());
+
+        let entry: SchemaEntry = serde_json::from_value(json!([
+            recipe("First", json!({})),
+            recipe("Second", json!({}))
+        ]))
+        .unwrap();
+        assert_eq!("First", entry.primary_recipe().unwrap().name());
 
+        let entry: SchemaEntry = serde_json::from_value(json!([
+            recipe("Cake", json!({"@id": "#r", "recipeIngredient": ["flour", "eggs"]})),
+            recipe(
+                "Cake",
+                json!({"@id": "#r", "recipeIngredient": [], "description": "Fluffy", "url": "/cake"})
+            ),
+        ]))
+        .unwrap();
+        let primary = entry.primary_recipe().unwrap();
+        assert_eq!(entry.extract_recipes(), vec![primary.clone()]);
         assert_eq!(
-            Recipe::new(
-                "A recipe",
-                "This is a recipe",
-                IngredientList::single("An ingredient. Another one")
-            )
-            .with_directions(InstructionList::Single(Instruction::simple(
-                "Do a thing. Do another thing"
-            ))),
-            serde_json::from_value(json!({
-                    "name": "A recipe",
-                    "description": "This is a recipe",
-                    "recipeIngredient": "An ingredient. Another one",
-                    "recipeInstructions": "Do a thing. Do another thing"
-            }))
-            .unwrap()
+            vec!["flour", "eggs"],
+            primary.ingredients().entries().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_recipes_from_ndjson_reader() {
+        let ndjson = concat!(
+            r#"{"name": "First", "description": "", "recipeIngredient": "An ingredient"}"#,
+            "\n\n",
+            "not json\n",
+            r#"{"@graph": [{"@id": "website"}, {"name": "Second", "description": "", "recipeIngredient": "An ingredient"}]}"#,
+            "\n",
+        );
+
+        let results = recipes_from_ndjson_reader(ndjson.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(3, results.len());
+        assert_eq!("First", results[0].as_ref().unwrap().name());
+        assert!(matches!(results[1], Err(crate::ScrapeError::Json(_))));
+        assert_eq!("Second", results[2].as_ref().unwrap().name());
+    }
+
+    /// Inputs which have previously panicked (or overflowed the stack) while parsing or while
+    /// formatting what was parsed
+    const NASTY_BLOBS: &[&str] = &[
+        r#"{"@type": "Recipe", "name": "", "description": "", "recipeIngredient": "",
+            "totalTime": "PT99999999999999999999999999999999999999999S"}"#,
+        r#"{"@type": "Recipe", "name": "", "description": "", "recipeIngredient": "",
+            "cookTime": {"minutes": -5}, "prepTime": "PT-5M"}"#,
+        r#"{"@type": "Recipe", "name": "", "description": "", "recipeIngredient": "",
+            "recipeInstructions": "Bake for 99999999999999999999999 minutes"}"#,
+        r#"{"@type": "Recipe", "name": "&#x110000;&#99999999999;&", "description": "&#",
+            "recipeIngredient": ["1/0 cup", "1e400 g", "½"], "recipeYield": "1/0"}"#,
+        "\u{feff}{\"@graph\": [{\"@id\": \"#a\", \"sameAs\": {\"@id\": \"#a\"}}]}",
+    ];
+
+    /// Parse `json` as both a document and a script block, formatting whatever recipes result
+    fn parse_everything(json: &str) {
+        let html = format!(r#"<script type="application/ld+json">{}</script>"#, json);
+        let scraper = crate::Scraper::new()
+            .heuristics(true)
+            .base_url("https://example.com/");
+        let value = serde_json::from_str(json).unwrap_or_default();
+        let recipes = SchemaEntry::from_json_str(json)
+            .map(|entry| crate::Extract::extract_recipes(&entry))
+            .unwrap_or_default()
+            .into_iter()
+            .chain(SchemaEntry::extract_recipes_tolerant(value))
+            .chain(scraper.scrape(&html))
+            .chain(scraper.scrape(&html[..html.len() / 2]));
+        for recipe in recipes {
+            for time in [recipe.total_time(), recipe.prep_time(), recipe.cook_time()] {
+                let time = time.as_ref();
+                let _ = time.map(|t| (t.human_readable(), t.human_readable_lang("de"), t.hms()));
+            }
+            for step in recipe
+                .directions()
+                .as_ref()
+                .map_or(Vec::new(), |d| d.steps())
+            {
+                let _ = step.detected_timers();
+            }
+            let _ = (
+                recipe.total_time_or_estimate(),
+                recipe.difficulty(),
+                recipe.to_html(),
+            );
+            let _ = (
+                recipe.sections(),
+                recipe.detected_allergens(),
+                recipe.scale(2.5),
+            );
+            let _ = (
+                recipe.ingredients().parse(),
+                recipe.clean_instructions().to_cooklang(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_never_panics() {
+        for blob in NASTY_BLOBS {
+            parse_everything(blob);
+        }
+
+        let nested = 10_000;
+        let deep_json = format!("{}{}", "[".repeat(nested), "]".repeat(nested));
+        assert!(SchemaEntry::from_json_str(&deep_json).is_err());
+        let deep_noscript = format!(
+            r#"{}<script type="application/ld+json">{{}}</script>{}"#,
+            "<noscript>".repeat(nested),
+            "</noscript>".repeat(nested)
         );
+        assert!(<SchemaEntry as crate::Scrape>::scrape_html(deep_noscript).is_empty());
+
+        // Recipes built at (deterministic) random from a pool of awkward keys and values
+        let keys = [
+            "name",
+            "recipeIngredient",
+            "recipeInstructions",
+            "itemListElement",
+            "text",
+            "totalTime",
+            "cookTime",
+            "minutes",
+            "recipeYield",
+            "image",
+            "nutrition",
+            "calories",
+            "@type",
+            "@id",
+        ];
+        let values = [
+            json!(null),
+            json!(-5),
+            json!(1e300),
+            json!("PT99999999999999999999999S"),
+            json!("P1Y2M"),
+            json!("HowToSection"),
+            json!("For the dough:"),
+            json!("Simmer 15-20 minutes"),
+            json!("2 to 99999999999999999999 hours"),
+            json!("1/0"),
+            json!("../a"),
+            json!("&#x110000;"),
+            json!(["x", 1]),
+            json!({"@id": "#a"}),
+        ];
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |n: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize % n
+        };
+        for _ in 0..500 {
+            let recipe = recipe_json(json!({"@type": "Recipe"}));
+            let mut recipe = recipe.as_object().cloned().unwrap();
+            let mut nested = serde_json::Map::new();
+            for _ in 0..next(8) {
+                let value = values[next(values.len())].clone();
+                nested.insert(keys[next(keys.len())].to_string(), value);
+            }
+            for _ in 0..next(8) {
+                let value = match next(3) {
+                    0 => serde_json::Value::Object(nested.clone()),
+                    1 => json!([nested.clone(), values[next(values.len())]]),
+                    _ => values[next(values.len())].clone(),
+                };
+                recipe.insert(keys[next(keys.len())].to_string(), value);
+            }
+            parse_everything(&serde_json::Value::Object(recipe).to_string());
+        }
     }
 }
@@ -0,0 +1,674 @@
+//! A configurable entry point combining the various scraping and post-processing options
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "parallel")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "parallel")]
+use std::thread;
+
+use accessory::Accessors;
+
+use crate::{SchemaOrgEntry, SchemaOrgRecipe as Recipe, ScrapeError};
+
+/// The number of threads [`Scraper::scrape_many_html`] spreads its inputs across unless
+/// configured otherwise with [`Scraper::threads`]
+#[cfg(feature = "parallel")]
+pub const DEFAULT_THREADS: usize = 8;
+
+/// The structured data formats a [`Scraper`] can extract recipes from
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Format {
+    /// [schema.org Recipe](https://schema.org/Recipe) objects in `ld+json` script blocks
+    JsonLd,
+    /// schema.org Recipe objects nested anywhere within generic `application/json` script
+    /// blocks, such as Next.js's `__NEXT_DATA__`. Blocks over 2 MiB are skipped
+    Json,
+    /// schema.org Recipe objects nested within JSON held by element attributes, such as
+    /// `data-recipe`, as stashed by some single-page app frameworks. The attributes searched are
+    /// set by [`Scraper::json_attributes`], and default to [`DEFAULT_JSON_ATTRIBUTES`]
+    Attributes,
+    /// Recipes laid out for printing (as saved to PDF), found by the container and field classes
+    /// of common print layouts, such as `.recipe-print` and `.wprm-recipe-ingredient`. These
+    /// pages often hold no structured data at all
+    Print,
+    /// schema.org Recipe items marked up with HTML microdata (`itemscope`, `itemtype` and
+    /// `itemprop` attributes) on the page's own elements
+    Microdata,
+    /// schema.org Recipe resources marked up with RDFa (`typeof` and `property` attributes, with
+    /// or without a `schema:` prefix) on the page's own elements
+    Rdfa,
+}
+
+/// Where a [`Scraper`] found one of a recipe's fields
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum FieldSource {
+    /// Structured data in one of the searched [`Format`]s
+    Format(Format),
+    /// `<meta>` tags such as Open Graph's `og:title`, as read by the
+    /// [heuristic](Scraper::heuristics) fallback
+    Metadata,
+    /// The page's headings and the lists following them, as read by the heuristic fallback
+    DomHeuristic,
+}
+
+/// Where each populated field of a recipe was found, keyed by the field's schema.org property
+/// name (such as `recipeIngredient`)
+pub type FieldProvenance = HashMap<String, FieldSource>;
+
+/// The recipes scraped from one file by [`Scraper::scrape_dir`], or why it couldn't be read
+pub type FileRecipes = Result<Vec<Recipe>, ScrapeError>;
+
+/// A recipe alongside the [provenance](FieldProvenance) of its fields, as returned by
+/// [`Scraper::scrape_with_provenance`]
+#[derive(Clone, Debug, PartialEq, Accessors)]
+#[access(get)]
+pub struct ScrapedRecipe {
+    recipe: Recipe,
+    provenance: FieldProvenance,
+}
+
+impl ScrapedRecipe {
+    /// Where the field with the given schema.org property name was found, or `None` when it is
+    /// empty
+    pub fn source(&self, field: &str) -> Option<FieldSource> {
+        self.provenance.get(field).copied()
+    }
+
+    /// Consume the scraped recipe, moving out the recipe and its provenance without cloning them
+    pub fn into_parts(self) -> (Recipe, FieldProvenance) {
+        (self.recipe, self.provenance)
+    }
+}
+
+/// A recipe that [`Scraper`] post-processing applies to, either alone or carrying some extra
+/// data alongside it
+trait HasRecipe {
+    fn recipe(&self) -> &Recipe;
+    fn recipe_mut(&mut self) -> &mut Recipe;
+}
+
+impl HasRecipe for Recipe {
+    fn recipe(&self) -> &Recipe {
+        self
+    }
+
+    fn recipe_mut(&mut self) -> &mut Recipe {
+        self
+    }
+}
+
+impl<T> HasRecipe for (Recipe, T) {
+    fn recipe(&self) -> &Recipe {
+        &self.0
+    }
+
+    fn recipe_mut(&mut self) -> &mut Recipe {
+        &mut self.0
+    }
+}
+
+/// How a recipe was found, from which its [`FieldProvenance`] is built on request
+enum Origin {
+    Format(Format),
+    Heuristic(Vec<(&'static str, FieldSource)>),
+}
+
+impl Origin {
+    fn provenance(self, recipe: &Recipe) -> FieldProvenance {
+        use serde_json::Value;
+
+        let format = match self {
+            Self::Format(format) => format,
+            Self::Heuristic(sources) => {
+                let sources = sources.into_iter().map(|(k, s)| (k.to_string(), s));
+                return sources.collect();
+            }
+        };
+
+        let populated = |v: &Value| match v {
+            Value::Null => false,
+            Value::String(s) => !s.trim().is_empty(),
+            Value::Array(v) => !v.is_empty(),
+            Value::Object(o) => !o.is_empty(),
+            _ => true,
+        };
+        match serde_json::to_value(recipe) {
+            Ok(Value::Object(fields)) => fields
+                .into_iter()
+                .filter(|(k, v)| !k.starts_with('@') && populated(v))
+                .map(|(k, _)| (k, FieldSource::Format(format)))
+                .collect(),
+            _ => FieldProvenance::new(),
+        }
+    }
+}
+
+/// The attributes searched by [`Format::Attributes`] unless configured otherwise
+pub const DEFAULT_JSON_ATTRIBUTES: &[&str] = &["data-recipe", "data-json", "data-schema"];
+
+/// Scrapes recipes from HTML documents, according to its configuration
+///
+/// The default configuration matches [`Scrape::scrape_html`](crate::Scrape::scrape_html):
+/// only JSON-LD is searched, and recipes are returned exactly as found
+///
+/// ```
+/// use recipe_scraper::{Format, Scraper};
+///
+/// let scraper = Scraper::new()
+///     .formats([Format::JsonLd])
+///     .dedupe(true)
+///     .base_url("https://example.com/recipes/")
+///     .decode_entities(true);
+/// # let html = "";
+/// let recipes = scraper.scrape(html);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scraper {
+    formats: Vec<Format>,
+    dedupe: bool,
+    base_url: Option<String>,
+    decode_entities: bool,
+    heuristics: bool,
+    json_attributes: Vec<String>,
+    #[cfg(feature = "parallel")]
+    threads: usize,
+}
+
+impl Default for Scraper {
+    fn default() -> Self {
+        Self {
+            formats: vec![Format::JsonLd],
+            dedupe: false,
+            base_url: None,
+            decode_entities: false,
+            heuristics: false,
+            json_attributes: DEFAULT_JSON_ATTRIBUTES
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            #[cfg(feature = "parallel")]
+            threads: DEFAULT_THREADS,
+        }
+    }
+}
+
+impl Scraper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The formats to search, in order. Recipes are returned grouped by format, in this order
+    pub fn formats(self, formats: impl IntoIterator<Item = Format>) -> Self {
+        let formats = formats.into_iter().collect();
+        Self { formats, ..self }
+    }
+
+    /// Whether to drop recipes identical to one found earlier in the document
+    pub fn dedupe(self, dedupe: bool) -> Self {
+        Self { dedupe, ..self }
+    }
+
+    /// Resolve relative URLs against `base_url`, per [`Recipe::with_base_url`]
+    pub fn base_url(self, base_url: impl Into<String>) -> Self {
+        let base_url = Some(base_url.into());
+        Self { base_url, ..self }
+    }
+
+    /// Whether to decode HTML character references in recipe text, per
+    /// [`Recipe::decode_entities`]
+    pub fn decode_entities(self, decode_entities: bool) -> Self {
+        Self {
+            decode_entities,
+            ..self
+        }
+    }
+
+    /// Whether to fall back to piecing a recipe together from Open Graph metadata and the lists
+    /// following "Ingredients" and "Instructions" headings when a page holds no structured
+    /// recipes. This is a last resort: such recipes are sparse, and may contain unrelated text
+    pub fn heuristics(self, heuristics: bool) -> Self {
+        Self { heuristics, ..self }
+    }
+
+    /// How many threads [`scrape_many_html`](Self::scrape_many_html) spreads its inputs across,
+    /// [`DEFAULT_THREADS`] unless set (and at least one). The default doesn't depend on the
+    /// machine: pass `std::thread::available_parallelism()` to match its cores
+    #[cfg(feature = "parallel")]
+    pub fn threads(self, threads: usize) -> Self {
+        let threads = threads.max(1);
+        Self { threads, ..self }
+    }
+
+    /// The element attributes searched for recipe JSON by [`Format::Attributes`]
+    pub fn json_attributes<S: Into<String>>(self, attributes: impl IntoIterator<Item = S>) -> Self {
+        let json_attributes = attributes.into_iter().map(Into::into).collect();
+        Self {
+            json_attributes,
+            ..self
+        }
+    }
+
+    /// Scrape every recipe from `html` using the configured formats and post-processing
+    pub fn scrape(&self, html: impl AsRef<str>) -> Vec<Recipe> {
+        let recipes = self.scrape_origins(html.as_ref());
+        recipes.into_iter().map(|(recipe, _)| recipe).collect()
+    }
+
+    /// Like [`scrape`](Self::scrape), but alongside where each of a recipe's fields was found:
+    /// in which format of structured data, or by which part of the heuristic fallback. Fields of
+    /// structured recipes all share the format they were found in
+    pub fn scrape_with_provenance(&self, html: impl AsRef<str>) -> Vec<ScrapedRecipe> {
+        let recipes = self.scrape_origins(html.as_ref());
+        recipes
+            .into_iter()
+            .map(|(recipe, origin)| ScrapedRecipe {
+                provenance: origin.provenance(&recipe),
+                recipe,
+            })
+            .collect()
+    }
+
+    fn scrape_origins(&self, html: &str) -> Vec<(Recipe, Origin)> {
+        let document = scraper::Html::parse_document(html);
+
+        let mut recipes = Vec::new();
+        for &format in &self.formats {
+            let found = match format {
+                Format::JsonLd => SchemaOrgEntry::scrape_document_recipes(&document),
+                Format::Json => crate::schema_org::json_script_recipes(&document),
+                Format::Attributes => {
+                    crate::schema_org::attribute_recipes(&document, &self.json_attributes)
+                }
+                Format::Print => crate::print::scrape(&document),
+                Format::Microdata => crate::microdata::scrape(&document),
+                Format::Rdfa => crate::rdfa::scrape(&document),
+            };
+            recipes.extend(found.into_iter().map(|r| (r, Origin::Format(format))));
+        }
+
+        if recipes.is_empty() && self.heuristics {
+            let found = crate::heuristic::scrape_with_sources(&document);
+            recipes.extend(found.map(|(r, sources)| (r, Origin::Heuristic(sources))));
+        }
+
+        self.post_process(recipes)
+    }
+
+    /// Apply the configured deduplication, URL resolution and entity decoding to `recipes`, each
+    /// of which may carry some extra data alongside it
+    fn post_process<R: HasRecipe>(&self, mut recipes: Vec<R>) -> Vec<R> {
+        if self.dedupe {
+            let mut unique: Vec<R> = Vec::with_capacity(recipes.len());
+            for item in recipes {
+                if !unique.iter().any(|u| u.recipe() == item.recipe()) {
+                    unique.push(item);
+                }
+            }
+            recipes = unique;
+        }
+
+        for recipe in recipes.iter_mut().map(HasRecipe::recipe_mut) {
+            if let Some(base_url) = &self.base_url {
+                recipe.resolve_urls(base_url);
+            }
+            if self.decode_entities {
+                *recipe = recipe.decode_entities();
+            }
+        }
+
+        recipes
+    }
+
+    /// Read an HTML file (such as a saved page) and scrape it as by [`scrape`](Self::scrape).
+    /// The file's character encoding is detected from its byte-order mark or `<meta>` tags,
+    /// defaulting to UTF-8
+    pub fn scrape_file(&self, path: impl AsRef<Path>) -> Result<Vec<Recipe>, ScrapeError> {
+        self.scrape_reader(std::fs::File::open(path)?)
+    }
+
+    /// Read an HTML document from `reader` and scrape it as by [`scrape`](Self::scrape),
+    /// detecting its character encoding as [`scrape_file`](Self::scrape_file) does. HTML parsing
+    /// needs the whole document, so it is read to the end before scraping begins
+    pub fn scrape_reader(&self, mut reader: impl Read) -> Result<Vec<Recipe>, ScrapeError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(self.scrape(crate::charset::decode(&bytes, None)))
+    }
+
+    /// Scrape every `.html` (or `.htm`) and `.json` file directly within the directory at `path`,
+    /// such as a local recipe archive, pairing the recipes of each with its path (in path order).
+    /// HTML files are scraped as by [`scrape_file`](Self::scrape_file), while JSON files are
+    /// parsed as schema entries, and post-processed the same way. Files which hold no recipes (or
+    /// aren't valid JSON) yield no recipes, and other files and subdirectories are skipped
+    ///
+    /// Only failing to read the directory itself is an error. A file which can't be read is
+    /// paired with its own error, so that one bad file doesn't lose the rest of the archive, and
+    /// directory entries which can't be read at all are skipped
+    pub fn scrape_dir(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<(PathBuf, FileRecipes)>, ScrapeError> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(path)?.flatten() {
+            let path = entry.path();
+            let extension = path.extension().and_then(|e| e.to_str());
+            let extension = extension.map(str::to_ascii_lowercase);
+            if path.is_file() && matches!(extension.as_deref(), Some("html" | "htm" | "json")) {
+                paths.push((path, extension));
+            }
+        }
+        paths.sort();
+
+        let scraped = paths.into_iter().map(|(path, extension)| {
+            let recipes = match extension.as_deref() {
+                Some("json") => std::fs::read(&path)
+                    .map_err(ScrapeError::from)
+                    .map(|bytes| {
+                        let entry = SchemaOrgEntry::from_json_bytes(bytes);
+                        let recipes =
+                            entry.map_or_else(|_| Vec::new(), SchemaOrgEntry::into_recipes);
+                        self.post_process(recipes)
+                    }),
+                _ => self.scrape_file(&path),
+            };
+            (path, recipes)
+        });
+        Ok(scraped.collect())
+    }
+
+    /// Scrape many HTML documents in parallel across [`threads`](Self::threads) threads,
+    /// returning the recipes found in each document in the same order as the inputs. Each
+    /// document is handled exactly as by [`scrape`](Self::scrape). Inputs are taken from `htmls`
+    /// only as threads become free, so a lazy iterator (such as one reading files) never holds
+    /// more than one document per thread in memory
+    #[cfg(feature = "parallel")]
+    pub fn scrape_many_html<I>(&self, htmls: I) -> Vec<Vec<Recipe>>
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: Send + 'static,
+    {
+        let queue = Arc::new(Mutex::new(htmls.into_iter().enumerate()));
+
+        let workers = (0..self.threads)
+            .map(|_| {
+                let scraper = self.clone();
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    let mut scraped = Vec::new();
+                    loop {
+                        let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                        match next {
+                            Some((idx, html)) => scraped.push((idx, scraper.scrape(html))),
+                            None => return scraped,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut scraped = Vec::new();
+        for worker in workers {
+            let found = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            scraped.extend(found);
+        }
+        scraped.sort_by_key(|(idx, _)| *idx);
+        scraped.into_iter().map(|(_, recipes)| recipes).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"
+        <script type="application/ld+json">
+            {"name": "Mac &amp; Cheese", "description": "", "recipeIngredient": "", "url": "mac"}
+        </script>
+        <script type="application/ld+json">
+            {"name": "Mac &amp; Cheese", "description": "", "recipeIngredient": "", "url": "mac"}
+        </script>
+    "#;
+
+    #[test]
+    fn test_scraper_defaults() {
+        let recipes = Scraper::new().scrape(HTML);
+        assert_eq!(2, recipes.len());
+        assert_eq!("Mac &amp; Cheese", recipes[0].name());
+        assert_eq!(Some("mac"), recipes[0].url().as_deref());
+    }
+
+    #[test]
+    fn test_scraper_options() {
+        let recipes = Scraper::new()
+            .dedupe(true)
+            .base_url("https://example.com/recipes/")
+            .decode_entities(true)
+            .scrape(HTML);
+
+        assert_eq!(1, recipes.len());
+        assert_eq!("Mac & Cheese", recipes[0].name());
+        assert_eq!(
+            Some("https://example.com/recipes/mac"),
+            recipes[0].url().as_deref()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_scrape_many_html() {
+        let htmls = (0..20).map(|i| match i % 2 {
+            0 => HTML.to_string(),
+            _ => String::new(),
+        });
+        let scraped = Scraper::new().dedupe(true).scrape_many_html(htmls);
+
+        assert_eq!(20, scraped.len());
+        for (idx, recipes) in scraped.iter().enumerate() {
+            assert_eq!(1 - idx % 2, recipes.len());
+        }
+
+        let htmls = (0..5).map(|_| HTML.to_string());
+        let scraped = Scraper::new().threads(2).scrape_many_html(htmls);
+        assert_eq!(vec![2; 5], scraped.iter().map(Vec::len).collect::<Vec<_>>());
+        assert!(Scraper::new()
+            .threads(0)
+            .scrape_many_html(Vec::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_scraper_heuristics() {
+        let html = "<h1>Toast</h1><h2>Ingredients</h2><ul><li>1 slice bread</li></ul>";
+        assert!(Scraper::new().scrape(html).is_empty());
+
+        let recipes = Scraper::new().heuristics(true).scrape(html);
+        assert_eq!(
+            vec!["Toast"],
+            recipes.iter().map(Recipe::name).collect::<Vec<_>>()
+        );
+
+        let recipes = Scraper::new().heuristics(true).scrape(HTML);
+        assert_eq!("Mac &amp; Cheese", recipes[0].name());
+    }
+
+    #[test]
+    fn test_scraper_json_format() {
+        let html = r#"<script type="application/json">
+            {"page": {"recipe": {"@type": "Recipe", "name": "Toast", "description": "", "recipeIngredient": ""}}}
+        </script>"#;
+        assert!(Scraper::new().scrape(html).is_empty());
+
+        let recipes = Scraper::new()
+            .formats([Format::JsonLd, Format::Json])
+            .scrape(format!("{}{}", HTML, html));
+        assert_eq!(
+            vec!["Mac &amp; Cheese", "Mac &amp; Cheese", "Toast"],
+            recipes.iter().map(Recipe::name).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_scraper_attributes_format() {
+        let html = r#"<div data-recipe='{"@type": "Recipe", "name": "Toast", "description": "", "recipeIngredient": ""}'
+            data-state='{"recipe": {"@type": "Recipe", "name": "Jam", "description": "", "recipeIngredient": ""}}'></div>"#;
+        assert!(Scraper::new().scrape(html).is_empty());
+
+        let names = |scraper: Scraper| {
+            let recipes = scraper.formats([Format::Attributes]).scrape(html);
+            recipes.iter().map(|r| r.name().clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["Toast"], names(Scraper::new()));
+        assert_eq!(
+            vec!["Jam"],
+            names(Scraper::new().json_attributes(["data-state"]))
+        );
+    }
+
+    #[test]
+    fn test_scraper_print_format() {
+        let html = r#"<div class="recipe-print"><h1 class="recipe-title">Toast</h1>
+            <ul class="recipe-ingredients"><li>1 slice bread</li></ul></div>"#;
+        assert!(Scraper::new().scrape(html).is_empty());
+
+        let recipes = Scraper::new()
+            .formats([Format::JsonLd, Format::Print])
+            .scrape(html);
+        assert_eq!(
+            vec!["Toast"],
+            recipes.iter().map(Recipe::name).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_scrape_with_provenance() {
+        let recipes = Scraper::new().scrape_with_provenance(HTML);
+        let recipe = &recipes[0];
+        assert_eq!(
+            Some(FieldSource::Format(Format::JsonLd)),
+            recipe.source("name")
+        );
+        assert_eq!(None, recipe.source("recipeYield"));
+
+        let html = r#"<head><meta property="og:title" content="Toast"></head>
+            <h2>Ingredients</h2><ul><li>1 slice bread</li></ul>"#;
+        let recipes = Scraper::new().heuristics(true).scrape_with_provenance(html);
+        let recipe = &recipes[0];
+        assert_eq!("Toast", recipe.recipe().name());
+        assert_eq!(Some(FieldSource::Metadata), recipe.source("name"));
+        assert_eq!(
+            Some(FieldSource::DomHeuristic),
+            recipe.source("recipeIngredient")
+        );
+    }
+
+    #[test]
+    fn test_scrape_file() {
+        let path = std::env::temp_dir().join(format!("recipe-scraper-{}.html", std::process::id()));
+        std::fs::write(&path, HTML).unwrap();
+        let recipes = Scraper::new().dedupe(true).scrape_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(1, recipes.unwrap().len());
+
+        let missing = Scraper::new().scrape_file(path);
+        assert!(matches!(missing, Err(ScrapeError::Io(_))));
+    }
+
+    #[test]
+    fn test_scrape_dir() {
+        let dir = std::env::temp_dir().join(format!("recipe-scraper-dir-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested.html")).unwrap();
+        let files = [
+            ("a.html", HTML),
+            (
+                "b.JSON",
+                r#"{"name": "Toast", "description": "", "recipeIngredient": ""}"#,
+            ),
+            ("c.json", "not json"),
+            ("d.htm", "<p>Nothing here</p>"),
+            ("e.txt", HTML),
+        ];
+        for (name, contents) in files {
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+        let scraped = Scraper::new().dedupe(true).scrape_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let scraped = scraped
+            .unwrap()
+            .into_iter()
+            .map(|(path, recipes)| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (
+                    name,
+                    recipes
+                        .unwrap()
+                        .iter()
+                        .map(|r| r.name().clone())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("a.html".to_string(), names(&["Mac &amp; Cheese"])),
+                ("b.JSON".to_string(), names(&["Toast"])),
+                ("c.json".to_string(), names(&[])),
+                ("d.htm".to_string(), names(&[])),
+            ],
+            scraped
+        );
+
+        assert!(matches!(
+            Scraper::new().scrape_dir(&dir),
+            Err(ScrapeError::Io(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_scrape_dir_unreadable_file() {
+        let dir =
+            std::env::temp_dir().join(format!("recipe-scraper-unreadable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.html"), HTML).unwrap();
+        // A regular file which fails to read (with EIO) even as root
+        std::os::unix::fs::symlink("/proc/self/mem", dir.join("b.html")).unwrap();
+        std::fs::write(dir.join("c.html"), HTML).unwrap();
+        let scraped = Scraper::new().scrape_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let scraped = scraped.unwrap();
+        let names = scraped.iter().map(|(path, _)| path.file_name().unwrap());
+        assert_eq!(
+            vec!["a.html", "b.html", "c.html"],
+            names.collect::<Vec<_>>()
+        );
+        assert!(!scraped[0].1.as_ref().unwrap().is_empty());
+        assert!(matches!(scraped[1].1, Err(ScrapeError::Io(_))));
+        assert!(!scraped[2].1.as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scrape_reader() {
+        let recipes = Scraper::new().dedupe(true).scrape_reader(HTML.as_bytes());
+        assert_eq!(1, recipes.unwrap().len());
+
+        let latin1 = b"<meta charset=\"iso-8859-1\"><script type=\"application/ld+json\">\
+            {\"name\": \"Cr\xe8me\", \"description\": \"\", \"recipeIngredient\": \"\"}\
+            </script>";
+        let recipes = Scraper::new().scrape_reader(&latin1[..]).unwrap();
+        assert_eq!("Crème", recipes[0].name());
+    }
+
+    #[test]
+    fn test_scraper_no_formats() {
+        assert!(Scraper::new().formats([]).scrape(HTML).is_empty());
+    }
+}
@@ -0,0 +1,431 @@
+//! Helpers for cleaning up the free text found in recipes
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// Decode HTML character references (`&amp;`, `&#189;`, `&#xBD;`) in `text`, leaving anything
+/// which isn't a well-formed, known reference untouched
+pub(crate) fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find('&') {
+        decoded.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let reference = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 32)
+            .map(|end| &rest[1..=end + 1]);
+        match reference.and_then(decode_reference) {
+            Some(chars) => {
+                decoded.extend(chars.iter().flatten());
+                rest = &rest[reference.map_or(0, str::len) + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    Cow::Owned(decoded)
+}
+
+/// Decode a single reference, given without its leading `&` but with its trailing `;`
+fn decode_reference(reference: &str) -> Option<[Option<char>; 2]> {
+    let name = reference.strip_suffix(';')?;
+    let codepoints = match name.strip_prefix('#') {
+        Some(num) => {
+            let cp = match num.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => num.parse().ok()?,
+            };
+            (cp, 0)
+        }
+        None => *html5ever::data::NAMED_ENTITIES.get(reference)?,
+    };
+
+    match codepoints {
+        (0, _) => None,
+        (first, second) => Some([
+            Some(char::from_u32(first)?),
+            char::from_u32(second).filter(|_| second != 0),
+        ]),
+    }
+}
+
+/// Inline formatting tags which [`sanitize`] keeps, without any of their attributes (save for
+/// the `href` of links)
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "br", "em", "i", "li", "ol", "p", "small", "strong", "sub", "sup", "u", "ul",
+];
+
+/// Tags whose content is dropped entirely, rather than kept as text
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "noscript", "template", "svg", "math",
+];
+
+/// Whether `url` is safe to link to: relative, or using one of a few known-safe schemes
+pub(crate) fn is_safe_url(url: &str) -> bool {
+    let url = url
+        .trim()
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match url.find(':') {
+        Some(idx) if !url[..idx].contains(['/', '?', '#']) => {
+            matches!(&url[..idx], "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+fn escape_into(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// `text` with the characters significant to HTML escaped, for use as element content or within
+/// a quoted attribute value
+pub(crate) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    escape_into(&mut out, text);
+    out
+}
+
+fn sanitize_into(out: &mut String, el: scraper::ElementRef<'_>) {
+    for child in el.children() {
+        match child.value() {
+            scraper::Node::Text(text) => escape_into(out, text),
+            scraper::Node::Element(el) => {
+                let name = el.name();
+                let child = match scraper::ElementRef::wrap(child) {
+                    Some(child) => child,
+                    None => continue,
+                };
+                if DROPPED_TAGS.contains(&name) {
+                    continue;
+                }
+                if !ALLOWED_TAGS.contains(&name) {
+                    sanitize_into(out, child);
+                    continue;
+                }
+
+                out.push('<');
+                out.push_str(name);
+                if let Some(href) = el.attr("href").filter(|h| name == "a" && is_safe_url(h)) {
+                    out.push_str(" href=\"");
+                    escape_into(out, href);
+                    out.push('"');
+                }
+                out.push('>');
+                if name != "br" {
+                    sanitize_into(out, child);
+                    out.push_str("</");
+                    out.push_str(name);
+                    out.push('>');
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reduce `text` to safe HTML: only the [`ALLOWED_TAGS`] are kept (stripped of all attributes
+/// but safe link targets), dangerous elements such as `<script>` are removed along with their
+/// content, other tags are unwrapped and all text is escaped
+pub(crate) fn sanitize(text: &str) -> Cow<'_, str> {
+    if !text.contains(['<', '>', '&', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+
+    let fragment = scraper::Html::parse_fragment(text);
+    let mut out = String::with_capacity(text.len());
+    sanitize_into(&mut out, fragment.root_element());
+    Cow::Owned(out)
+}
+
+fn strip_tags_into(out: &mut String, el: scraper::ElementRef<'_>) {
+    for child in el.children() {
+        match child.value() {
+            scraper::Node::Text(text) => out.push_str(text),
+            scraper::Node::Element(el) if DROPPED_TAGS.contains(&el.name()) => {}
+            scraper::Node::Element(_) => {
+                if let Some(child) = scraper::ElementRef::wrap(child) {
+                    strip_tags_into(out, child);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `text` without any HTML tags, keeping their text content except for that of the
+/// [`DROPPED_TAGS`]. Like parsing HTML, this also decodes character references
+pub(crate) fn strip_tags(text: &str) -> Cow<'_, str> {
+    if !text.contains('<') {
+        return Cow::Borrowed(text);
+    }
+
+    let fragment = scraper::Html::parse_fragment(text);
+    let mut out = String::with_capacity(text.len());
+    strip_tags_into(&mut out, fragment.root_element());
+    Cow::Owned(out)
+}
+
+/// Lowercase abbreviations (without their final period) which commonly appear mid-sentence in
+/// recipes, and whose period therefore doesn't end a sentence unless the next word is
+/// capitalized, as in "Bake 20 min. Serve warm."
+const ABBREVIATIONS: &[&str] = &[
+    "approx", "appr", "ca", "e.g", "i.e", "vs", "no", "min", "mins", "hr", "hrs", "sec", "secs",
+    "tsp", "tbsp", "tbs", "oz", "fl", "lb", "lbs", "pkg", "pt", "qt", "gal", "doz", "deg", "temp",
+    "med", "lg", "sm", "dr", "st", "mr", "mrs", "ms",
+];
+
+/// Those of the [`ABBREVIATIONS`] which qualify the word following them ("approx. 5", "e.g.
+/// Parmesan", "Dr. Oetker"), so that the sentence goes on even when it's capitalized
+const LEADING_ABBREVIATIONS: &[&str] = &[
+    "approx", "appr", "ca", "e.g", "i.e", "vs", "no", "dr", "st", "mr", "mrs", "ms",
+];
+
+/// Split `text` into its sentences, each trimmed. A sentence ends at a `.`, `!` or `?` (and any
+/// closing quotes or brackets) followed by whitespace, unless the period ends one of the
+/// [`ABBREVIATIONS`] (and no capitalized word follows it) or an initial like "F." in "350 F.
+/// oven". Decimal points, which aren't followed by whitespace, never end a sentence
+pub(crate) fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+
+        let mut end = idx + c.len_utf8();
+        while let Some(&(i, c)) = chars.peek() {
+            if !matches!(c, '.' | '!' | '?' | ')' | ']' | '"' | '\'' | '”' | '’') {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        if !chars.peek().map_or(false, |(_, c)| c.is_whitespace()) {
+            continue;
+        }
+
+        let word = text[start..idx]
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        let capitalized = text[end..]
+            .trim_start()
+            .chars()
+            .next()
+            .map_or(false, char::is_uppercase);
+        let abbreviation = ABBREVIATIONS.contains(&word.as_str())
+            && (!capitalized || LEADING_ABBREVIATIONS.contains(&word.as_str()));
+        let abbreviated = c == '.' && end == idx + 1 && (abbreviation || word.chars().count() == 1);
+        if !abbreviated {
+            sentences.push(text[start..end].trim());
+            start = end;
+        }
+    }
+    sentences.push(text[start..].trim());
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+/// Words for units of time, alongside their length in seconds
+const TIME_UNITS: &[(&[&str], u64)] = &[
+    (&["second", "seconds", "sec", "secs", "s"], 1),
+    (&["minute", "minutes", "min", "mins", "m"], 60),
+    (&["hour", "hours", "hr", "hrs", "h"], 60 * 60),
+    (&["day", "days"], 24 * 60 * 60),
+];
+
+/// A quantity of time at the start of `text`, such as "20 minutes" or "2–3 hours" (which yields
+/// the upper bound), alongside the text following it
+fn leading_duration(text: &str) -> Option<(Duration, &str)> {
+    use crate::ingredient::split_quantity;
+
+    let (mut quantity, rest) = split_quantity(text)?;
+    let consumed = &text[..text.len() - rest.len()];
+    let mut rest = rest;
+    if let Some((_, upper)) = consumed.split_once(['-', '–']) {
+        quantity = split_quantity(upper).map_or(quantity, |(upper, _)| upper);
+    } else if let Some((upper, after)) = rest
+        .trim_start()
+        .strip_prefix("to ")
+        .and_then(split_quantity)
+    {
+        quantity = upper;
+        rest = after;
+    }
+
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(rest.len());
+    let unit = rest[..end].to_lowercase();
+    let seconds = TIME_UNITS
+        .iter()
+        .find(|(words, _)| words.contains(&unit.as_str()))?
+        .1;
+    // Implausibly long times would overflow a `Duration` (which panics), so are ignored instead
+    let seconds = quantity * seconds as f64;
+    let duration = (seconds < u64::MAX as f64).then(|| Duration::from_secs_f64(seconds))?;
+    Some((duration, &rest[end..]))
+}
+
+/// Every length of time mentioned in `text`, such as "simmer for 20 minutes", in order. Ranges
+/// ("15-20 minutes", "2 to 3 hours") yield their upper bound, and compound times ("1 hour 30
+/// minutes", "1 hour and 15 minutes") are added together, leaving out those whose sum would
+/// overflow a `Duration`
+pub(crate) fn durations(text: &str) -> Vec<Duration> {
+    // Compound times which overflowed are kept as `None` until the end, so that their remaining
+    // parts aren't mistaken for separate times
+    let mut durations: Vec<Option<Duration>> = Vec::new();
+    let mut idx = 0;
+    let mut compound = false;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        let starts_word = text[..idx]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !c.is_alphanumeric());
+        let found = rest
+            .chars()
+            .next()
+            .filter(|c| starts_word && (c.is_ascii_digit() || "½⅓⅔¼¾⅛".contains(*c)))
+            .and_then(|_| leading_duration(rest));
+
+        match found {
+            Some((duration, after)) => {
+                match durations.last_mut() {
+                    Some(last) if compound => *last = last.and_then(|l| l.checked_add(duration)),
+                    _ => durations.push(Some(duration)),
+                }
+                let between = after.trim_start();
+                let between = between.strip_prefix("and ").unwrap_or(between);
+                compound = between.starts_with(|c: char| c.is_ascii_digit());
+                idx = text.len() - if compound { between } else { after }.len();
+            }
+            None => {
+                idx += rest.chars().next().map_or(1, char::len_utf8);
+                compound = false;
+            }
+        }
+    }
+    durations.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!("Mac & Cheese", decode_entities("Mac &amp; Cheese"));
+        assert_eq!("½ cup", decode_entities("&frac12; cup"));
+        assert_eq!("½ cup ½", decode_entities("&#189; cup &#xBD;"));
+        assert_eq!("Salt & pepper", decode_entities("Salt & pepper"));
+        assert_eq!("&bogus; &#xZZ;", decode_entities("&bogus; &#xZZ;"));
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!("Mix <b>well</b>", sanitize("Mix <b class=\"x\">well</b>"));
+        assert_eq!("Mix ", sanitize("Mix <script>alert(1)</script>"));
+        assert_eq!("Bake", sanitize("<img src=x onerror=alert(1)>Bake"));
+        assert_eq!(
+            "<a>link</a> <a href=\"https://example.com\">ok</a>",
+            sanitize(
+                r#"<a href="javascript:alert(1)">link</a> <a href="https://example.com">ok</a>"#
+            )
+        );
+        assert_eq!("Mac &amp; Cheese &lt;3", sanitize("Mac & Cheese <3"));
+        assert_eq!("Plain text", sanitize("Plain text"));
+    }
+
+    #[test]
+    fn test_strip_tags() {
+        assert_eq!("Mix well", strip_tags("<p>Mix <b class=\"x\">well</b></p>"));
+        assert_eq!("Mix ", strip_tags("Mix <script>alert(1)</script>"));
+        assert_eq!("Mac & Cheese", strip_tags("Mac & Cheese"));
+    }
+
+    #[test]
+    fn test_sentences() {
+        assert_eq!(
+            vec![
+                "Preheat the oven to 350.5 F. for approx. 10 min. before baking.",
+                "Mix well!",
+                "Is it smooth?",
+                "(It should be.)",
+                "Bake",
+            ],
+            sentences(
+                "Preheat the oven to 350.5 F. for approx. 10 min. before baking.  Mix well! \
+                 Is it smooth? (It should be.) Bake"
+            )
+        );
+        assert_eq!(
+            vec!["Add 1 tsp. salt, e.g. kosher."],
+            sentences("Add 1 tsp. salt, e.g. kosher.")
+        );
+        assert_eq!(
+            vec!["Bake 20 min.", "Serve."],
+            sentences("Bake 20 min. Serve.")
+        );
+        assert_eq!(
+            vec!["Add approx. Two cups of Dr. Oetker's mix, e.g. Vanilla."],
+            sentences("Add approx. Two cups of Dr. Oetker's mix, e.g. Vanilla.")
+        );
+        assert!(sentences("  ").is_empty());
+    }
+
+    #[test]
+    fn test_durations() {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+
+        assert_eq!(
+            vec![minutes(20)],
+            durations("Simmer for 20 minutes, stirring.")
+        );
+        assert_eq!(
+            vec![minutes(20), minutes(180)],
+            durations("Rest 15–20 mins, then roast for 2 to 3 hours at 350F.")
+        );
+        assert_eq!(
+            vec![minutes(90), minutes(75)],
+            durations("Bake 1 hour 30 minutes, or 1 hour and 15 minutes if small")
+        );
+        assert_eq!(vec![minutes(90)], durations("Chill for 1 1/2 hrs"));
+        assert_eq!(vec![Duration::from_secs(30)], durations("Blend 30 seconds"));
+        assert!(durations("Add 2 cups flour and 3 eggs").is_empty());
+        assert!(durations("Bake for 99999999999999999999999 minutes").is_empty());
+        assert_eq!(
+            vec![minutes(5)],
+            durations(&format!(
+                "{}then 5 minutes",
+                "5000000000000000000 seconds ".repeat(4)
+            ))
+        );
+    }
+}